@@ -20,7 +20,7 @@ fn main() -> Result<(), DriverError> {
     )?);
     let dst_buf = render_graph.bind_node(Buffer::create_from_slice(
         &device,
-        vk::BufferUsageFlags::TRANSFER_DST,
+        vk::BufferUsageFlags::TRANSFER_DST | vk::BufferUsageFlags::TRANSFER_SRC,
         [0, 0, 0, 0],
     )?);
 
@@ -52,5 +52,18 @@ fn main() -> Result<(), DriverError> {
     println!("Waited {}μs", (Instant::now() - started).as_micros());
 
     // It is now safe to read back what we did!
-    Ok(println!("{:?}", Buffer::mapped_slice(&dst_buf)))
+    println!("{:?}", Buffer::mapped_slice(&dst_buf));
+
+    // Alternatively the render graph may copy any buffer or image into a staging buffer leased
+    // from the pool; the returned handle can also be polled using try_read or awaited as a Future
+    let mut render_graph = RenderGraph::new();
+    let dst_buf = render_graph.bind_node(dst_buf);
+    render_graph.fill_buffer(dst_buf, 0x01);
+
+    let readback = render_graph.read_buffer(dst_buf);
+    render_graph
+        .resolve()
+        .submit(&mut HashPool::new(&device), 0, 0)?;
+
+    Ok(println!("{:?}", readback.wait()?))
 }
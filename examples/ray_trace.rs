@@ -325,10 +325,6 @@ static SHADER_SHADOW_MISS: &[u32] = inline_spirv!(
 )
 .as_slice();
 
-fn align_up(val: u32, atom: u32) -> u32 {
-    (val + atom - 1) & !(atom - 1)
-}
-
 fn create_ray_trace_pipeline(device: &Arc<Device>) -> Result<Arc<RayTracePipeline>, DriverError> {
     Ok(Arc::new(RayTracePipeline::create(
         device,
@@ -494,73 +490,17 @@ fn main() -> anyhow::Result<()> {
     // Setup the ray tracing pipeline
     // ------------------------------------------------------------------------------------------ //
 
-    let &RayTraceProperties {
-        shader_group_base_alignment,
-        shader_group_handle_alignment,
-        shader_group_handle_size,
-        ..
-    } = event_loop
-        .device
-        .physical_device
-        .ray_trace_properties
-        .as_ref()
-        .unwrap();
     let ray_trace_pipeline = create_ray_trace_pipeline(&event_loop.device)?;
 
     // ------------------------------------------------------------------------------------------ //
     // Setup a shader binding table
     // ------------------------------------------------------------------------------------------ //
 
-    let sbt_handle_size = align_up(shader_group_handle_size, shader_group_handle_alignment);
-    let sbt_rgen_size = sbt_handle_size;
-    let sbt_hit_size = sbt_handle_size;
-    let sbt_miss_size = 2 * sbt_handle_size;
-    let sbt_buf = Arc::new({
-        let mut buf = Buffer::create(
-            &event_loop.device,
-            BufferInfo::host_mem(
-                (sbt_rgen_size + sbt_hit_size + sbt_miss_size) as _,
-                vk::BufferUsageFlags::SHADER_BINDING_TABLE_KHR
-                    | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
-            )
-            .to_builder()
-            .alignment(shader_group_base_alignment as _),
-        )
-        .unwrap();
-
-        let mut data = Buffer::mapped_slice_mut(&mut buf);
-        data.fill(0);
-
-        let rgen_handle = RayTracePipeline::group_handle(&ray_trace_pipeline, 0)?;
-        data[0..rgen_handle.len()].copy_from_slice(rgen_handle);
-        data = &mut data[sbt_rgen_size as _..];
-
-        // If hit/miss had different strides we would need to iterate each here
-        for idx in 1..4 {
-            let handle = RayTracePipeline::group_handle(&ray_trace_pipeline, idx)?;
-            data[0..handle.len()].copy_from_slice(handle);
-            data = &mut data[sbt_handle_size as _..];
-        }
-
-        buf
-    });
-    let sbt_address = Buffer::device_address(&sbt_buf);
-    let sbt_rgen = vk::StridedDeviceAddressRegionKHR {
-        device_address: sbt_address,
-        stride: sbt_rgen_size as _,
-        size: sbt_rgen_size as _,
-    };
-    let sbt_hit = vk::StridedDeviceAddressRegionKHR {
-        device_address: sbt_rgen.device_address + sbt_rgen_size as vk::DeviceAddress,
-        stride: sbt_handle_size as _,
-        size: sbt_hit_size as _,
-    };
-    let sbt_miss = vk::StridedDeviceAddressRegionKHR {
-        device_address: sbt_hit.device_address + sbt_hit_size as vk::DeviceAddress,
-        stride: sbt_handle_size as _,
-        size: sbt_miss_size as _,
-    };
-    let sbt_callable = vk::StridedDeviceAddressRegionKHR::default();
+    let sbt = Arc::new(ShaderBindingTable::create(
+        &mut cache,
+        &ray_trace_pipeline,
+        ShaderBindingTableInfo::new(0).hit(1).miss(2).miss(3),
+    )?);
 
     // ------------------------------------------------------------------------------------------ //
     // Load the .obj cube scene
@@ -854,7 +794,8 @@ fn main() -> anyhow::Result<()> {
         let vertex_buf_node = frame.render_graph.bind_node(&vertex_buf);
         let material_id_buf_node = frame.render_graph.bind_node(&material_id_buf);
         let material_buf_node = frame.render_graph.bind_node(&material_buf);
-        let sbt_node = frame.render_graph.bind_node(&sbt_buf);
+        let sbt_node = frame.render_graph.bind_node(&sbt.buffer);
+        let sbt = Arc::clone(&sbt);
 
         frame
             .render_graph
@@ -881,15 +822,7 @@ fn main() -> anyhow::Result<()> {
             )
             .access_descriptor(6, material_buf_node, AccessType::RayTracingShaderReadOther)
             .record_ray_trace(move |ray_trace, _| {
-                ray_trace.trace_rays(
-                    &sbt_rgen,
-                    &sbt_miss,
-                    &sbt_hit,
-                    &sbt_callable,
-                    frame.width,
-                    frame.height,
-                    1,
-                );
+                ray_trace.trace_rays_with_table(&sbt, frame.width, frame.height, 1);
             })
             .submit_pass()
             .copy_image(image_node, frame.swapchain_image);
@@ -4,10 +4,11 @@ use {
     super::{
         access_type_from_u8, access_type_into_u8, device::Device, Buffer, BufferInfo, DriverError,
     },
-    ash::vk,
+    ash::{extensions::khr, vk},
     derive_builder::{Builder, UninitializedFieldError},
     log::warn,
     std::{
+        ffi::c_void,
         mem::size_of_val,
         ops::Deref,
         sync::{
@@ -56,7 +57,7 @@ pub struct AccelerationStructure {
     /// Backing storage buffer for this object.
     pub buffer: Buffer,
 
-    device: Arc<Device>,
+    pub(crate) device: Arc<Device>,
 
     /// Information used to create this object.
     pub info: AccelerationStructureInfo,
@@ -186,6 +187,140 @@ impl AccelerationStructure {
         )
     }
 
+    /// Returns the most recent access of this object without modifying it.
+    pub(crate) fn peek_access(this: &Self) -> AccessType {
+        access_type_from_u8(this.prev_access.load(Ordering::Relaxed))
+    }
+
+    /// Builds this acceleration structure on the host, without recording any commands.
+    ///
+    /// Host builds may run on worker threads and read geometry data from host memory; every
+    /// geometry must use [`DeviceOrHostAddress::HostAddress`]. `scratch` must be at least the
+    /// `build_size` value returned by [`AccelerationStructure::size_of`].
+    ///
+    /// Returns [`DriverError::Unsupported`] if the device does not support
+    /// [`AccelerationStructureFeatures::acceleration_structure_host_commands`], or
+    /// [`DriverError::InvalidData`] if any geometry uses a device address or `scratch` is too small.
+    ///
+    /// [`AccelerationStructureFeatures::acceleration_structure_host_commands`]: super::physical_device::AccelerationStructureFeatures::acceleration_structure_host_commands
+    ///
+    /// # Safety
+    ///
+    /// The host addresses of `build_info` must point to valid geometry data of the sizes described
+    /// by `build_info` and `build_ranges`, and this acceleration structure must not be in use by
+    /// the device.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::accel_struct::{AccelerationStructure, AccelerationStructureGeometry, AccelerationStructureGeometryData, AccelerationStructureGeometryInfo, AccelerationStructureInfo};
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// let indices = [0u32, 1, 2];
+    /// let vertices = [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+    /// let info = AccelerationStructureGeometryInfo {
+    ///     ty: vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL,
+    ///     flags: vk::BuildAccelerationStructureFlagsKHR::PREFER_FAST_TRACE,
+    ///     geometries: vec![AccelerationStructureGeometry {
+    ///         max_primitive_count: 1,
+    ///         flags: vk::GeometryFlagsKHR::OPAQUE,
+    ///         geometry: AccelerationStructureGeometryData::Triangles {
+    ///             index_data: indices.as_slice().into(),
+    ///             index_type: vk::IndexType::UINT32,
+    ///             max_vertex: 2,
+    ///             transform_data: None,
+    ///             vertex_data: vertices.as_slice().into(),
+    ///             vertex_format: vk::Format::R32G32B32_SFLOAT,
+    ///             vertex_stride: 12,
+    ///         },
+    ///     }],
+    /// };
+    /// let size = AccelerationStructure::size_of(&device, &info);
+    /// let blas = AccelerationStructure::create(&device, AccelerationStructureInfo::blas(size.create_size))?;
+    /// let mut scratch = vec![0; size.build_size as usize];
+    /// let range = vk::AccelerationStructureBuildRangeInfoKHR {
+    ///     primitive_count: 1,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// unsafe {
+    ///     AccelerationStructure::build_host(&blas, &mut scratch, &info, &[range])?;
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    pub unsafe fn build_host(
+        this: &Self,
+        scratch: &mut [u8],
+        build_info: &AccelerationStructureGeometryInfo,
+        build_ranges: &[vk::AccelerationStructureBuildRangeInfoKHR],
+    ) -> Result<(), DriverError> {
+        use std::slice::from_ref;
+
+        let accel_struct_ext = Self::host_commands_ext(&this.device)?;
+
+        debug_assert_eq!(
+            build_info.geometries.len(),
+            build_ranges.len(),
+            "Each geometry must have a build range"
+        );
+
+        if build_info
+            .geometries
+            .iter()
+            .any(AccelerationStructureGeometry::has_device_address)
+        {
+            warn!("host builds require host addresses");
+
+            return Err(DriverError::InvalidData);
+        }
+
+        let build_size = Self::size_of(&this.device, build_info).build_size;
+        if (scratch.len() as vk::DeviceSize) < build_size {
+            warn!("host builds require {build_size} bytes of scratch memory");
+
+            return Err(DriverError::InvalidData);
+        }
+
+        let geometries = build_info
+            .geometries
+            .iter()
+            .map(|geometry| geometry.into_vk())
+            .collect::<Box<_>>();
+        let info = vk::AccelerationStructureBuildGeometryInfoKHR::builder()
+            .ty(build_info.ty)
+            .flags(build_info.flags)
+            .mode(vk::BuildAccelerationStructureModeKHR::BUILD)
+            .geometries(&geometries)
+            .dst_acceleration_structure(this.accel_struct)
+            .scratch_data(vk::DeviceOrHostAddressKHR {
+                host_address: scratch.as_mut_ptr() as _,
+            });
+
+        accel_struct_ext
+            .build_acceleration_structures(
+                vk::DeferredOperationKHR::null(),
+                from_ref(&info),
+                from_ref(&build_ranges),
+            )
+            .map_err(|err| {
+                warn!("{err}");
+
+                DriverError::OutOfMemory
+            })?;
+
+        // Host writes are visible to command buffers submitted afterwards
+        Self::access(this, AccessType::HostWrite);
+
+        Ok(())
+    }
+
     /// Returns the device address of this object.
     ///
     /// # Examples
@@ -222,6 +357,95 @@ impl AccelerationStructure {
         }
     }
 
+    /// Returns the size of the acceleration structure which must be created in order to
+    /// deserialize `data`, which was previously serialized using
+    /// [`Acceleration::serialize_structure`](crate::graph::pass_ref::Acceleration::serialize_structure).
+    ///
+    /// Serialized data may only be deserialized by devices which use a compatible driver. Returns
+    /// [`DriverError::InvalidData`] if `data` is incomplete or was serialized by an incompatible
+    /// device, or [`DriverError::Unsupported`] if the device does not support acceleration
+    /// structures.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::accel_struct::{AccelerationStructure, AccelerationStructureInfo};
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let my_data = std::fs::read("my_blas.bin").unwrap();
+    /// let size = AccelerationStructure::deserialized_size(&device, &my_data)?;
+    /// let info = AccelerationStructureInfo::blas(size);
+    /// let my_blas = AccelerationStructure::create(&device, info)?;
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    pub fn deserialized_size(device: &Device, data: &[u8]) -> Result<vk::DeviceSize, DriverError> {
+        let (version_data, size) = Self::serialized_header(data).ok_or_else(|| {
+            warn!("invalid serialized acceleration structure");
+
+            DriverError::InvalidData
+        })?;
+        let accel_struct_ext = device.accel_struct_ext.as_ref().ok_or_else(|| {
+            warn!("acceleration structures are not supported");
+
+            DriverError::Unsupported
+        })?;
+        let compatibility = unsafe {
+            accel_struct_ext.get_device_acceleration_structure_compatibility(
+                &vk::AccelerationStructureVersionInfoKHR::builder().version_data(version_data),
+            )
+        };
+
+        if compatibility != vk::AccelerationStructureCompatibilityKHR::COMPATIBLE {
+            warn!("incompatible serialized acceleration structure");
+
+            return Err(DriverError::InvalidData);
+        }
+
+        Ok(size)
+    }
+
+    // Serialized data begins with the driver and compatibility UUIDs, followed by the serialized
+    // size, the deserialized size and the count and handles of any referenced structures
+    fn serialized_header(data: &[u8]) -> Option<(&[u8; 2 * vk::UUID_SIZE], vk::DeviceSize)> {
+        const VERSION_LEN: usize = 2 * vk::UUID_SIZE;
+
+        let version_data = data.get(..VERSION_LEN)?.try_into().ok()?;
+        let read_size = |offset: usize| {
+            data.get(offset..offset + 8)
+                .and_then(|size| size.try_into().ok())
+                .map(vk::DeviceSize::from_ne_bytes)
+        };
+        let serialized_size = read_size(VERSION_LEN)?;
+        let deserialized_size = read_size(VERSION_LEN + 8)?;
+
+        (serialized_size <= data.len() as vk::DeviceSize)
+            .then_some((version_data, deserialized_size))
+    }
+
+    fn host_commands_ext(device: &Device) -> Result<&khr::AccelerationStructure, DriverError> {
+        if !device
+            .physical_device
+            .accel_struct_features
+            .acceleration_structure_host_commands
+        {
+            warn!("acceleration structure host commands are not supported");
+
+            return Err(DriverError::Unsupported);
+        }
+
+        device
+            .accel_struct_ext
+            .as_ref()
+            .ok_or(DriverError::Unsupported)
+    }
+
     /// Helper function which is used to prepare instance buffers.
     pub fn instance_slice(instances: &[vk::AccelerationStructureInstanceKHR]) -> &[u8] {
         use std::slice::from_raw_parts;
@@ -328,17 +552,7 @@ impl AccelerationStructure {
                                 vertex_stride,
                                 max_vertex,
                                 index_type,
-                                transform_data: match transform_data {
-                                    Some(DeviceOrHostAddress::DeviceAddress(device_address)) => {
-                                        vk::DeviceOrHostAddressConstKHR { device_address }
-                                    }
-                                    Some(DeviceOrHostAddress::HostAddress) => {
-                                        vk::DeviceOrHostAddressConstKHR {
-                                            host_address: std::ptr::null(),
-                                        } // TODO
-                                    }
-                                    None => vk::DeviceOrHostAddressConstKHR { device_address: 0 },
-                                },
+                                transform_data: transform_data.map(Into::into).unwrap_or_default(),
                                 ..Default::default()
                             },
                         },
@@ -422,6 +636,33 @@ pub struct AccelerationStructureGeometry {
 }
 
 impl AccelerationStructureGeometry {
+    fn addresses(&self) -> impl Iterator<Item = DeviceOrHostAddress> {
+        match self.geometry {
+            AccelerationStructureGeometryData::AABBs { .. } => [None, None, None],
+            AccelerationStructureGeometryData::Instances { data, .. } => [Some(data), None, None],
+            AccelerationStructureGeometryData::Triangles {
+                index_data,
+                transform_data,
+                vertex_data,
+                ..
+            } => [Some(index_data), transform_data, Some(vertex_data)],
+        }
+        .into_iter()
+        .flatten()
+    }
+
+    /// Returns `true` if any geometry data is read from a device address.
+    pub(crate) fn has_device_address(&self) -> bool {
+        self.addresses()
+            .any(|addr| matches!(addr, DeviceOrHostAddress::DeviceAddress(_)))
+    }
+
+    /// Returns `true` if any geometry data is read from a host address.
+    pub(crate) fn has_host_address(&self) -> bool {
+        self.addresses()
+            .any(|addr| matches!(addr, DeviceOrHostAddress::HostAddress(_)))
+    }
+
     pub(crate) fn into_vk(self) -> vk::AccelerationStructureGeometryKHR {
         let (geometry_type, geometry) = match self.geometry {
             AccelerationStructureGeometryData::AABBs { stride } => (
@@ -441,12 +682,7 @@ impl AccelerationStructureGeometry {
                 vk::AccelerationStructureGeometryDataKHR {
                     instances: vk::AccelerationStructureGeometryInstancesDataKHR {
                         array_of_pointers: array_of_pointers as _,
-                        data: match data {
-                            DeviceOrHostAddress::DeviceAddress(device_address) => {
-                                vk::DeviceOrHostAddressConstKHR { device_address }
-                            }
-                            DeviceOrHostAddress::HostAddress => todo!(),
-                        },
+                        data: data.into(),
                         ..Default::default()
                     },
                 },
@@ -463,27 +699,11 @@ impl AccelerationStructureGeometry {
                 vk::GeometryTypeKHR::TRIANGLES,
                 vk::AccelerationStructureGeometryDataKHR {
                     triangles: vk::AccelerationStructureGeometryTrianglesDataKHR {
-                        index_data: match index_data {
-                            DeviceOrHostAddress::DeviceAddress(device_address) => {
-                                vk::DeviceOrHostAddressConstKHR { device_address }
-                            }
-                            DeviceOrHostAddress::HostAddress => todo!(),
-                        },
+                        index_data: index_data.into(),
                         index_type,
                         max_vertex,
-                        transform_data: match transform_data {
-                            Some(DeviceOrHostAddress::DeviceAddress(device_address)) => {
-                                vk::DeviceOrHostAddressConstKHR { device_address }
-                            }
-                            Some(DeviceOrHostAddress::HostAddress) => todo!(),
-                            None => vk::DeviceOrHostAddressConstKHR { device_address: 0 },
-                        },
-                        vertex_data: match vertex_data {
-                            DeviceOrHostAddress::DeviceAddress(device_address) => {
-                                vk::DeviceOrHostAddressConstKHR { device_address }
-                            }
-                            DeviceOrHostAddress::HostAddress => todo!(),
-                        },
+                        transform_data: transform_data.map(Into::into).unwrap_or_default(),
+                        vertex_data: vertex_data.into(),
                         vertex_format,
                         vertex_stride,
                         ..Default::default()
@@ -729,16 +949,22 @@ pub struct AccelerationStructureSize {
 
 /// Specifies a constant device or host address.
 ///
+/// Device addresses are used by acceleration structures built using a render graph and host
+/// addresses are used by acceleration structures built using
+/// [`AccelerationStructure::build_host`].
+///
 /// See
 /// [VkDeviceOrHostAddressConstKHR](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkDeviceOrHostAddressConstKHR.html)
 /// for more information.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum DeviceOrHostAddress {
-    /// An address value returned from [`AccelerationStructure::device_address`].
+    /// An address value returned from [`Buffer::device_address`] or
+    /// [`AccelerationStructure::device_address`].
     DeviceAddress(vk::DeviceAddress),
 
-    /// TODO: Not yet supported
-    HostAddress,
+    /// A pointer to host memory, which must remain valid until host commands using it have
+    /// returned.
+    HostAddress(*const c_void),
 }
 
 impl From<vk::DeviceAddress> for DeviceOrHostAddress {
@@ -747,6 +973,26 @@ impl From<vk::DeviceAddress> for DeviceOrHostAddress {
     }
 }
 
+impl<T> From<&[T]> for DeviceOrHostAddress {
+    fn from(host_data: &[T]) -> Self {
+        Self::HostAddress(host_data.as_ptr() as _)
+    }
+}
+
+impl From<DeviceOrHostAddress> for vk::DeviceOrHostAddressConstKHR {
+    fn from(addr: DeviceOrHostAddress) -> Self {
+        match addr {
+            DeviceOrHostAddress::DeviceAddress(device_address) => Self { device_address },
+            DeviceOrHostAddress::HostAddress(host_address) => Self { host_address },
+        }
+    }
+}
+
+// Host addresses are only dereferenced by host commands, which are unsafe and require the caller
+// to keep the memory valid
+unsafe impl Send for DeviceOrHostAddress {}
+unsafe impl Sync for DeviceOrHostAddress {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -775,4 +1021,66 @@ mod tests {
     pub fn accel_struct_info_builder_uninit_size() {
         Builder::default().build();
     }
+
+    #[test]
+    pub fn device_or_host_address() {
+        let data = [1u32, 2, 3];
+        let addr = DeviceOrHostAddress::from(data.as_slice());
+
+        assert_eq!(addr, DeviceOrHostAddress::HostAddress(data.as_ptr() as _));
+
+        let addr = vk::DeviceOrHostAddressConstKHR::from(addr);
+
+        assert_eq!(unsafe { addr.host_address }, data.as_ptr() as _);
+
+        let addr = vk::DeviceOrHostAddressConstKHR::from(DeviceOrHostAddress::DeviceAddress(42));
+
+        assert_eq!(unsafe { addr.device_address }, 42);
+    }
+
+    #[test]
+    pub fn geometry_addresses() {
+        let vertices = [0.0f32; 9];
+        let geometry = |vertex_data| AccelerationStructureGeometry {
+            max_primitive_count: 1,
+            flags: vk::GeometryFlagsKHR::OPAQUE,
+            geometry: AccelerationStructureGeometryData::Triangles {
+                index_data: DeviceOrHostAddress::DeviceAddress(0),
+                index_type: vk::IndexType::NONE_KHR,
+                max_vertex: 2,
+                transform_data: None,
+                vertex_data,
+                vertex_format: vk::Format::R32G32B32_SFLOAT,
+                vertex_stride: 12,
+            },
+        };
+
+        let device = geometry(DeviceOrHostAddress::DeviceAddress(42));
+
+        assert!(device.has_device_address());
+        assert!(!device.has_host_address());
+
+        let mixed = geometry(vertices.as_slice().into());
+
+        assert!(mixed.has_device_address());
+        assert!(mixed.has_host_address());
+    }
+
+    #[test]
+    pub fn serialized_header() {
+        let mut data = vec![7u8; 2 * vk::UUID_SIZE];
+        data.extend_from_slice(&64u64.to_ne_bytes());
+        data.extend_from_slice(&1024u64.to_ne_bytes());
+        data.extend_from_slice(&0u64.to_ne_bytes());
+
+        assert!(AccelerationStructure::serialized_header(&data).is_none());
+
+        data.resize(64, 0);
+
+        let (version_data, size) = AccelerationStructure::serialized_header(&data).unwrap();
+
+        assert_eq!(version_data, &[7; 2 * vk::UUID_SIZE]);
+        assert_eq!(size, 1024);
+        assert!(AccelerationStructure::serialized_header(&data[..40]).is_none());
+    }
 }
@@ -1,7 +1,7 @@
 //! Buffer resource types
 
 use {
-    super::{access_type_from_u8, access_type_into_u8, device::Device, DriverError},
+    super::{access_type_from_u8, access_type_into_u8, device::Device, DriverError, MemoryBlock},
     ash::vk,
     derive_builder::{Builder, UninitializedFieldError},
     gpu_allocator::{
@@ -11,11 +11,12 @@ use {
     log::trace,
     log::warn,
     std::{
+        collections::hash_map::DefaultHasher,
         fmt::{Debug, Formatter},
-        mem::ManuallyDrop,
+        hash::{Hash, Hasher},
         ops::{Deref, Range},
         sync::{
-            atomic::{AtomicU8, Ordering},
+            atomic::{AtomicU64, AtomicU8, Ordering},
             Arc,
         },
         thread::panicking,
@@ -52,16 +53,24 @@ use {
 /// [deref]: core::ops::Deref
 /// [fully qualified syntax]: https://doc.rust-lang.org/book/ch19-03-advanced-traits.html#fully-qualified-syntax-for-disambiguation-calling-methods-with-the-same-name
 pub struct Buffer {
-    allocation: ManuallyDrop<Allocation>,
+    allocation: Option<Allocation>, // None when the memory is shared with other buffers
     buffer: vk::Buffer,
     device: Arc<Device>,
 
     /// Information used to create this object.
     pub info: BufferInfo,
 
+    _memory_block: Option<Arc<MemoryBlock>>,
+
     /// A name for debugging purposes.
+    ///
+    /// The name is given to the Vulkan buffer object when debug layers are enabled and the buffer
+    /// is used by a render graph, or by [`Self::with_name`].
     pub name: Option<String>,
 
+    /// A hash of the name which was last given to the Vulkan object, or zero.
+    named: AtomicU64,
+
     prev_access: AtomicU8,
 }
 
@@ -97,18 +106,7 @@ impl Buffer {
         debug_assert_ne!(info.size, 0, "Size must be non-zero");
 
         let device = Arc::clone(device);
-        let buffer_info = vk::BufferCreateInfo::builder()
-            .size(info.size)
-            .usage(info.usage)
-            .sharing_mode(vk::SharingMode::CONCURRENT)
-            .queue_family_indices(&device.physical_device.queue_family_indices);
-        let buffer = unsafe {
-            device.create_buffer(&buffer_info, None).map_err(|err| {
-                warn!("{err}");
-
-                DriverError::Unsupported
-            })?
-        };
+        let buffer = Self::create_unbound(&device, info)?;
         let mut requirements = unsafe { device.get_buffer_memory_requirements(buffer) };
         requirements.alignment = requirements.alignment.max(info.alignment);
 
@@ -153,11 +151,58 @@ impl Buffer {
         };
 
         Ok(Self {
-            allocation: ManuallyDrop::new(allocation),
+            allocation: Some(allocation),
             buffer,
             device,
             info,
+            _memory_block: None,
             name: None,
+            named: AtomicU64::new(0),
+            prev_access: AtomicU8::new(access_type_into_u8(AccessType::Nothing)),
+        })
+    }
+
+    /// Creates a new buffer on the given device which is bound to `memory_block` at `offset`.
+    ///
+    /// The memory is not owned by the buffer and may be shared with other resources.
+    #[profiling::function]
+    pub(crate) fn create_aliased(
+        device: &Arc<Device>,
+        info: BufferInfo,
+        memory_block: &Arc<MemoryBlock>,
+        offset: vk::DeviceSize,
+    ) -> Result<Self, DriverError> {
+        trace!("create_aliased: {:?}", info);
+
+        debug_assert!(!info.mappable, "Shared memory is not mappable");
+
+        let device = Arc::clone(device);
+        let buffer = Self::create_unbound(&device, info)?;
+
+        unsafe {
+            device
+                .bind_buffer_memory(
+                    buffer,
+                    MemoryBlock::memory(memory_block),
+                    MemoryBlock::offset(memory_block) + offset,
+                )
+                .map_err(|err| {
+                    warn!("{err}");
+
+                    device.destroy_buffer(buffer, None);
+
+                    DriverError::Unsupported
+                })?;
+        }
+
+        Ok(Self {
+            allocation: None,
+            buffer,
+            device,
+            info,
+            _memory_block: Some(Arc::clone(memory_block)),
+            name: None,
+            named: AtomicU64::new(0),
             prev_access: AtomicU8::new(access_type_into_u8(AccessType::Nothing)),
         })
     }
@@ -250,6 +295,11 @@ impl Buffer {
         )
     }
 
+    /// Returns the most recent access of this object without modifying it.
+    pub(crate) fn peek_access(this: &Self) -> AccessType {
+        access_type_from_u8(this.prev_access.load(Ordering::Relaxed))
+    }
+
     /// Updates a mappable buffer starting at `offset` with the data in `slice`.
     ///
     /// # Panics
@@ -283,6 +333,22 @@ impl Buffer {
             .copy_from_slice(slice);
     }
 
+    fn create_unbound(device: &Device, info: BufferInfo) -> Result<vk::Buffer, DriverError> {
+        let buffer_info = vk::BufferCreateInfo::builder()
+            .size(info.size)
+            .usage(info.usage)
+            .sharing_mode(vk::SharingMode::CONCURRENT)
+            .queue_family_indices(&device.physical_device.queue_family_indices);
+
+        unsafe {
+            device.create_buffer(&buffer_info, None).map_err(|err| {
+                warn!("{err}");
+
+                DriverError::Unsupported
+            })
+        }
+    }
+
     /// Returns the device address of this object.
     ///
     /// # Panics
@@ -351,7 +417,7 @@ impl Buffer {
             "Buffer is not mappable - create using mappable flag"
         );
 
-        &this.allocation.mapped_slice().unwrap()[0..this.info.size as usize]
+        &this.allocation.as_ref().unwrap().mapped_slice().unwrap()[0..this.info.size as usize]
     }
 
     /// Returns a mapped mutable slice.
@@ -389,7 +455,55 @@ impl Buffer {
             "Buffer is not mappable - create using mappable flag"
         );
 
-        &mut this.allocation.mapped_slice_mut().unwrap()[0..this.info.size as usize]
+        &mut this
+            .allocation
+            .as_mut()
+            .unwrap()
+            .mapped_slice_mut()
+            .unwrap()[0..this.info.size as usize]
+    }
+
+    /// Returns the memory requirements of a buffer created using the given information.
+    #[profiling::function]
+    pub(crate) fn memory_requirements(
+        device: &Device,
+        info: BufferInfo,
+    ) -> Result<vk::MemoryRequirements, DriverError> {
+        // Vulkan 1.2 requires a buffer object in order to query the requirements
+        let buffer = Self::create_unbound(device, info)?;
+
+        unsafe {
+            let mut requirements = device.get_buffer_memory_requirements(buffer);
+            requirements.alignment = requirements.alignment.max(info.alignment);
+            device.destroy_buffer(buffer, None);
+
+            Ok(requirements)
+        }
+    }
+
+    /// Gives the name of this buffer to the Vulkan object if it has changed since it was last given.
+    pub(crate) fn name_object(this: &Self) {
+        let Some(name) = &this.name else {
+            return;
+        };
+
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        let hash = hasher.finish().max(1);
+
+        if this.named.swap(hash, Ordering::Relaxed) != hash {
+            Device::set_debug_name(&this.device, this.buffer, name);
+        }
+    }
+
+    /// Sets the debugging name assigned to this buffer.
+    ///
+    /// The name is also given to the Vulkan buffer object when debug layers are enabled, so that
+    /// it appears in validation messages and graphics debugging tools.
+    pub fn with_name(mut this: Self, name: impl Into<String>) -> Self {
+        this.name = Some(name.into());
+        Self::name_object(&this);
+        this
     }
 }
 
@@ -418,7 +532,7 @@ impl Drop for Buffer {
             return;
         }
 
-        {
+        if let Some(allocation) = self.allocation.take() {
             profiling::scope!("deallocate");
 
             #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
@@ -427,9 +541,10 @@ impl Drop for Buffer {
             #[cfg(not(feature = "parking_lot"))]
             let mut allocator = allocator.unwrap();
 
-            allocator.free(unsafe { ManuallyDrop::take(&mut self.allocation) })
+            allocator
+                .free(allocation)
+                .unwrap_or_else(|_| warn!("Unable to free buffer allocation"));
         }
-        .unwrap_or_else(|_| warn!("Unable to free buffer allocation"));
 
         unsafe {
             self.device.destroy_buffer(self.buffer, None);
@@ -577,7 +692,7 @@ impl From<UninitializedFieldError> for BufferInfoBuilderError {
 }
 
 /// Specifies a range of buffer data.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct BufferSubresource {
     /// The start of range.
     pub start: vk::DeviceSize,
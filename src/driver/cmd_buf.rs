@@ -1,8 +1,15 @@
 use {
-    super::{device::Device, DriverError},
+    super::{
+        buffer::{Buffer, BufferInfo},
+        device::Device,
+        image::{Image, ImageInfo},
+        memory::MemoryBlock,
+        DriverError,
+    },
     ash::vk,
     log::{error, trace, warn},
     std::{fmt::Debug, ops::Deref, sync::Arc, thread::panicking},
+    vk_sync::AccessType,
 };
 
 /// Represents a Vulkan command buffer to which some work has been submitted.
@@ -11,12 +18,18 @@ pub struct CommandBuffer {
     cmd_buf: vk::CommandBuffer,
     pub(crate) device: Arc<Device>,
     droppables: Vec<Box<dyn Debug + Send + 'static>>,
+    event_count: usize,
+    events: Vec<vk::Event>,
     pub(crate) fence: vk::Fence, // Keeps state because everyone wants this
 
     /// Information used to create this object.
     pub info: CommandBufferInfo,
 
     pub(crate) pool: vk::CommandPool,
+    secondary_pools: Vec<SecondaryCommandPool>,
+    semaphore_count: usize,
+    semaphores: Vec<vk::Semaphore>,
+    transients: TransientCache,
 }
 
 impl CommandBuffer {
@@ -60,9 +73,15 @@ impl CommandBuffer {
             cmd_buf,
             device,
             droppables: vec![],
+            event_count: 0,
+            events: vec![],
             fence,
             info,
             pool,
+            secondary_pools: vec![],
+            semaphore_count: 0,
+            semaphores: vec![],
+            transients: Default::default(),
         })
     }
 
@@ -74,6 +93,25 @@ impl CommandBuffer {
         }
 
         this.droppables.clear();
+
+        // Events are signalled by the previous submission so they must be reset before reuse
+        for event in this.events[0..this.event_count].iter().copied() {
+            if let Err(err) = unsafe { this.device.reset_event(event) } {
+                warn!("unable to reset event: {err}");
+            }
+        }
+
+        this.event_count = 0;
+
+        // Secondary command buffers were executed by the previous submission so they may be reused
+        for secondary_pool in &mut this.secondary_pools {
+            SecondaryCommandPool::reset(secondary_pool, &this.device);
+        }
+
+        // Binary semaphores are unsignalled once the waits of the previous submission have executed
+        this.semaphore_count = 0;
+
+        TransientCache::reset(&mut this.transients);
     }
 
     /// Returns `true` after the GPU has executed the previous submission to this command buffer.
@@ -100,11 +138,235 @@ impl CommandBuffer {
         }
     }
 
+    /// Returns an unsignalled event which is not used by any other commands recorded since the
+    /// previous submission to this command buffer.
+    pub(crate) fn next_event(this: &mut Self) -> Result<vk::Event, DriverError> {
+        if this.event_count == this.events.len() {
+            let event = unsafe {
+                this.device
+                    .create_event(&vk::EventCreateInfo::default(), None)
+                    .map_err(|err| {
+                        warn!("{err}");
+
+                        DriverError::OutOfMemory
+                    })?
+            };
+            this.events.push(event);
+        }
+
+        let event = this.events[this.event_count];
+        this.event_count += 1;
+
+        Ok(event)
+    }
+
+    /// Returns an unsignalled binary semaphore which is not used by any other submissions since the
+    /// previous submission to this command buffer.
+    ///
+    /// Each semaphore must be signalled and waited on by submissions which have executed before
+    /// this command buffer has.
+    pub(crate) fn next_semaphore(this: &mut Self) -> Result<vk::Semaphore, DriverError> {
+        if this.semaphore_count == this.semaphores.len() {
+            let semaphore = Device::create_semaphore(&this.device)?;
+            this.semaphores.push(semaphore);
+        }
+
+        let semaphore = this.semaphores[this.semaphore_count];
+        this.semaphore_count += 1;
+
+        Ok(semaphore)
+    }
+
+    /// Returns a buffer which is not used by any other commands recorded since the previous
+    /// submission to this command buffer. The previous access of the buffer is nothing.
+    ///
+    /// Buffers which are given a memory block are bound to it at the given offset, and the block
+    /// must have been returned by [`Self::next_transient_memory`] since the previous submission.
+    /// Other buffers, such as mappable buffers, own their memory.
+    pub(crate) fn next_transient_buffer(
+        this: &mut Self,
+        info: BufferInfo,
+        memory_block: Option<(&Arc<MemoryBlock>, vk::DeviceSize)>,
+    ) -> Result<Arc<Buffer>, DriverError> {
+        let device = &this.device;
+        let (buffer, ..) = TransientCache::next(
+            &mut this.transients.buffer_count,
+            &mut this.transients.buffers,
+            |(buffer, _, placement)| {
+                buffer.info == info
+                    && Arc::strong_count(buffer) == 1
+                    && TransientCache::is_placed(placement, memory_block)
+            },
+            || {
+                Ok(if let Some((memory_block, offset)) = memory_block {
+                    let mut buffer = Buffer::create_aliased(device, info, memory_block, offset)?;
+                    buffer.name = Some("transient buffer".to_owned());
+
+                    let requirements = unsafe { device.get_buffer_memory_requirements(*buffer) };
+
+                    (
+                        Arc::new(buffer),
+                        Some(requirements),
+                        Some((Arc::clone(memory_block), offset)),
+                    )
+                } else {
+                    let mut buffer = Buffer::create(device, info)?;
+                    buffer.name = Some("transient buffer".to_owned());
+
+                    (Arc::new(buffer), None, None)
+                })
+            },
+        )?;
+
+        Buffer::access(buffer, AccessType::Nothing);
+
+        Ok(Arc::clone(buffer))
+    }
+
+    /// Returns the memory requirements of a buffer which is not mappable.
+    pub(crate) fn transient_buffer_requirements(
+        this: &Self,
+        info: BufferInfo,
+    ) -> Result<vk::MemoryRequirements, DriverError> {
+        this.transients
+            .buffers
+            .iter()
+            .find_map(|(buffer, requirements, _)| {
+                requirements
+                    .filter(|_| buffer.info == info)
+                    .map(|mut requirements| {
+                        requirements.alignment = requirements.alignment.max(info.alignment);
+                        requirements
+                    })
+            })
+            .map(Ok)
+            .unwrap_or_else(|| Buffer::memory_requirements(&this.device, info))
+    }
+
+    /// Returns an image bound to `memory_block` at `offset` which is not used by any other commands
+    /// recorded since the previous submission to this command buffer. The previous access of the
+    /// image is nothing.
+    ///
+    /// The memory block must have been returned by [`Self::next_transient_memory`] since the
+    /// previous submission.
+    pub(crate) fn next_transient_image(
+        this: &mut Self,
+        info: ImageInfo,
+        memory_block: &Arc<MemoryBlock>,
+        offset: vk::DeviceSize,
+    ) -> Result<Arc<Image>, DriverError> {
+        let device = &this.device;
+        let (image, ..) = TransientCache::next(
+            &mut this.transients.image_count,
+            &mut this.transients.images,
+            |(image, _, placement)| {
+                image.info == info
+                    && Arc::strong_count(image) == 1
+                    && TransientCache::is_placed(placement, Some((memory_block, offset)))
+            },
+            || {
+                let mut image = Image::create_aliased(device, info, memory_block, offset)?;
+                image.name = Some("transient image".to_owned());
+
+                let requirements = unsafe { device.get_image_memory_requirements(*image) };
+
+                Ok((
+                    Arc::new(image),
+                    Some(requirements),
+                    Some((Arc::clone(memory_block), offset)),
+                ))
+            },
+        )?;
+
+        Image::access(image, AccessType::Nothing);
+
+        Ok(Arc::clone(image))
+    }
+
+    /// Returns the memory requirements of an image.
+    pub(crate) fn transient_image_requirements(
+        this: &Self,
+        info: ImageInfo,
+    ) -> Result<vk::MemoryRequirements, DriverError> {
+        this.transients
+            .images
+            .iter()
+            .find_map(|(image, requirements, _)| requirements.filter(|_| image.info == info))
+            .map(Ok)
+            .unwrap_or_else(|| Image::memory_requirements(&this.device, info))
+    }
+
+    /// Returns a block of memory which is not used by any other commands recorded since the
+    /// previous submission to this command buffer.
+    ///
+    /// The block is at least as large as `requirements` and it is only shared with the buffers and
+    /// images returned by [`Self::next_transient_buffer`] and [`Self::next_transient_image`].
+    pub(crate) fn next_transient_memory(
+        this: &mut Self,
+        requirements: vk::MemoryRequirements,
+        linear: bool,
+    ) -> Result<Arc<MemoryBlock>, DriverError> {
+        let device = &this.device;
+        let transients = &mut this.transients;
+
+        // Memory is free once the resources bound to it are only referenced by this cache
+        let (memory_block, ..) = TransientCache::next(
+            &mut transients.memory_block_count,
+            &mut transients.memory_blocks,
+            |(memory_block, block_requirements, block_linear)| {
+                *block_linear == linear
+                    && block_requirements.memory_type_bits == requirements.memory_type_bits
+                    && block_requirements.size >= requirements.size
+                    && block_requirements.alignment % requirements.alignment == 0
+                    && transients
+                        .buffers
+                        .iter()
+                        .filter(|(_, _, placement)| {
+                            TransientCache::is_bound(placement, memory_block)
+                        })
+                        .all(|(buffer, ..)| Arc::strong_count(buffer) == 1)
+                    && transients
+                        .images
+                        .iter()
+                        .filter(|(_, _, placement)| {
+                            TransientCache::is_bound(placement, memory_block)
+                        })
+                        .all(|(image, ..)| Arc::strong_count(image) == 1)
+            },
+            || {
+                Ok((
+                    Arc::new(MemoryBlock::create(device, requirements, linear)?),
+                    requirements,
+                    linear,
+                ))
+            },
+        )?;
+
+        Ok(Arc::clone(memory_block))
+    }
+
     /// Drops an item after execution has been completed
     pub(crate) fn push_fenced_drop(this: &mut Self, thing_to_drop: impl Debug + Send + 'static) {
         this.droppables.push(Box::new(thing_to_drop));
     }
 
+    /// Returns `count` command pools which each record secondary command buffers on one thread.
+    ///
+    /// The pools, and the command buffers they allocate, are reused once the previous submission
+    /// to this command buffer has executed.
+    pub(crate) fn secondary_pools(
+        this: &mut Self,
+        count: usize,
+    ) -> Result<&mut [SecondaryCommandPool], DriverError> {
+        while this.secondary_pools.len() < count {
+            let secondary_pool =
+                SecondaryCommandPool::create(&this.device, this.info.queue_family_index)?;
+            this.secondary_pools.push(secondary_pool);
+        }
+
+        Ok(&mut this.secondary_pools[0..count])
+    }
+
     /// Stalls by blocking the current thread until the GPU has executed the previous submission to
     /// this command buffer.
     ///
@@ -137,6 +399,22 @@ impl Drop for CommandBuffer {
                 return;
             }
 
+            // Things waiting on the fence are dropped before it is destroyed
+            self.droppables.clear();
+            self.transients = Default::default();
+
+            for event in self.events.drain(..) {
+                self.device.destroy_event(event, None);
+            }
+
+            for secondary_pool in self.secondary_pools.drain(..) {
+                self.device.destroy_command_pool(secondary_pool.pool, None);
+            }
+
+            for semaphore in self.semaphores.drain(..) {
+                self.device.destroy_semaphore(semaphore, None);
+            }
+
             self.device
                 .free_command_buffers(self.pool, from_ref(&self.cmd_buf));
             self.device.destroy_command_pool(self.pool, None);
@@ -145,6 +423,159 @@ impl Drop for CommandBuffer {
     }
 }
 
+/// A command pool which allocates secondary command buffers for use by a single thread.
+#[derive(Debug)]
+pub(crate) struct SecondaryCommandPool {
+    cmd_buf_count: usize,
+    cmd_bufs: Vec<vk::CommandBuffer>,
+    pool: vk::CommandPool,
+}
+
+impl SecondaryCommandPool {
+    fn create(device: &Device, queue_family_index: u32) -> Result<Self, DriverError> {
+        let cmd_pool_info = vk::CommandPoolCreateInfo::builder()
+            .flags(vk::CommandPoolCreateFlags::TRANSIENT)
+            .queue_family_index(queue_family_index);
+        let pool = unsafe {
+            device
+                .create_command_pool(&cmd_pool_info, None)
+                .map_err(|err| {
+                    warn!("{err}");
+
+                    DriverError::Unsupported
+                })?
+        };
+
+        Ok(Self {
+            cmd_buf_count: 0,
+            cmd_bufs: vec![],
+            pool,
+        })
+    }
+
+    /// Returns a secondary command buffer which is not used by any other commands recorded since
+    /// the previous submission of the primary command buffer which owns this pool.
+    pub(crate) fn next_cmd_buf(
+        this: &mut Self,
+        device: &Device,
+    ) -> Result<vk::CommandBuffer, DriverError> {
+        if this.cmd_buf_count == this.cmd_bufs.len() {
+            let cmd_buf_info = vk::CommandBufferAllocateInfo::builder()
+                .command_buffer_count(1)
+                .command_pool(this.pool)
+                .level(vk::CommandBufferLevel::SECONDARY);
+            let cmd_buf = unsafe {
+                device
+                    .allocate_command_buffers(&cmd_buf_info)
+                    .map_err(|err| {
+                        warn!("{err}");
+
+                        DriverError::OutOfMemory
+                    })?
+            }[0];
+            this.cmd_bufs.push(cmd_buf);
+        }
+
+        let cmd_buf = this.cmd_bufs[this.cmd_buf_count];
+        this.cmd_buf_count += 1;
+
+        Ok(cmd_buf)
+    }
+
+    fn reset(this: &mut Self, device: &Device) {
+        if this.cmd_buf_count == 0 {
+            return;
+        }
+
+        if let Err(err) =
+            unsafe { device.reset_command_pool(this.pool, vk::CommandPoolResetFlags::empty()) }
+        {
+            warn!("unable to reset secondary command pool: {err}");
+        }
+
+        this.cmd_buf_count = 0;
+    }
+}
+
+/// The buffers, images and blocks of memory used by the transient resources of render graphs
+/// which are recorded into a command buffer.
+///
+/// The items which are used since the previous submission are kept at the front of each list, and
+/// the items which were not used by the previous submission are released once it has executed.
+#[derive(Debug, Default)]
+struct TransientCache {
+    buffer_count: usize,
+    buffers: Vec<TransientCacheItem<Buffer>>,
+    image_count: usize,
+    images: Vec<TransientCacheItem<Image>>,
+    memory_block_count: usize,
+    memory_blocks: Vec<(Arc<MemoryBlock>, vk::MemoryRequirements, bool)>,
+}
+
+/// A resource, the memory requirements of resources with the same information, and the memory
+/// block and offset it is bound to; buffers without a memory block own their memory.
+type TransientCacheItem<T> = (
+    Arc<T>,
+    Option<vk::MemoryRequirements>,
+    Option<(Arc<MemoryBlock>, vk::DeviceSize)>,
+);
+
+impl TransientCache {
+    fn is_bound(
+        placement: &Option<(Arc<MemoryBlock>, vk::DeviceSize)>,
+        memory_block: &Arc<MemoryBlock>,
+    ) -> bool {
+        placement
+            .as_ref()
+            .map(|(other, _)| Arc::ptr_eq(other, memory_block))
+            .unwrap_or_default()
+    }
+
+    fn is_placed(
+        placement: &Option<(Arc<MemoryBlock>, vk::DeviceSize)>,
+        memory_block: Option<(&Arc<MemoryBlock>, vk::DeviceSize)>,
+    ) -> bool {
+        match (placement, memory_block) {
+            (Some((other, other_offset)), Some((memory_block, offset))) => {
+                Arc::ptr_eq(other, memory_block) && *other_offset == offset
+            }
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    // Moves the first unused item which matches, or a new item, to the end of the used items
+    fn next<'a, T>(
+        count: &mut usize,
+        items: &'a mut Vec<T>,
+        is_match: impl FnMut(&T) -> bool,
+        create: impl FnOnce() -> Result<T, DriverError>,
+    ) -> Result<&'a T, DriverError> {
+        let idx = if let Some(idx) = items[*count..].iter().position(is_match) {
+            *count + idx
+        } else {
+            items.push(create()?);
+            items.len() - 1
+        };
+
+        items.swap(*count, idx);
+        *count += 1;
+
+        Ok(&items[*count - 1])
+    }
+
+    fn reset(this: &mut Self) {
+        this.buffers.truncate(this.buffer_count);
+        this.buffer_count = 0;
+
+        this.images.truncate(this.image_count);
+        this.image_count = 0;
+
+        this.memory_blocks.truncate(this.memory_block_count);
+        this.memory_block_count = 0;
+    }
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub struct CommandBufferInfo {
     pub queue_family_index: u32,
@@ -3,6 +3,9 @@
 use {
     super::{
         device::Device,
+        format_aspect_mask,
+        image::{Image, ImageInfo, ImageType, ImageViewInfo},
+        is_integer_format, is_signed_integer_format,
         shader::{DescriptorBindingMap, PipelineDescriptorInfo, Shader},
         DriverError,
     },
@@ -173,7 +176,9 @@ impl ComputePipeline {
 
     /// Sets the debugging name assigned to this pipeline.
     pub fn with_name(mut this: Self, name: impl Into<String>) -> Self {
-        this.name = Some(name.into());
+        let name = name.into();
+        Device::set_debug_name(&this.device, this.pipeline, &name);
+        this.name = Some(name);
         this
     }
 }
@@ -288,6 +293,674 @@ impl From<UninitializedFieldError> for ComputePipelineInfoBuilderError {
     }
 }
 
+/// Internal compute pipeline which skips the dispatches of conditional passes on devices which do
+/// not support `VK_EXT_conditional_rendering`.
+///
+/// Each dispatch of a conditional pass is replaced by an indirect dispatch which reads its group
+/// counts from one `uvec4` element of a storage buffer bound at set `0`, binding `0`. The group
+/// counts are stored in the first three components and the condition in the last; this pipeline
+/// zeroes the element selected by the X workgroup ID when the condition is zero, so that the
+/// indirect dispatch then executes no workgroups.
+#[derive(Debug)]
+pub(crate) struct ConditionalDispatchPipeline {
+    pub(crate) descriptor_set_layout: vk::DescriptorSetLayout,
+    pub(crate) layout: vk::PipelineLayout,
+    pub(crate) pipeline: vk::Pipeline,
+}
+
+impl ConditionalDispatchPipeline {
+    // Compiled from the following GLSL:
+    //
+    // #version 450
+    //
+    // layout(local_size_x = 1) in;
+    //
+    // layout(binding = 0) buffer Args {
+    //     uvec4 args[];
+    // };
+    //
+    // void main() {
+    //     if (args[gl_WorkGroupID.x].w == 0) {
+    //         args[gl_WorkGroupID.x] = uvec4(0);
+    //     }
+    // }
+    const SPIRV: &'static [u32] = &[
+        0x07230203, 0x00010000, 0x00000000, 0x0000001f, 0x00000000, 0x00020011, 0x00000001,
+        0x0003000e, 0x00000000, 0x00000001, 0x0006000f, 0x00000005, 0x00000001, 0x6e69616d,
+        0x00000000, 0x00000002, 0x00060010, 0x00000001, 0x00000011, 0x00000001, 0x00000001,
+        0x00000001, 0x00040047, 0x00000002, 0x0000000b, 0x0000001a, 0x00040047, 0x00000003,
+        0x00000006, 0x00000010, 0x00050048, 0x00000004, 0x00000000, 0x00000023, 0x00000000,
+        0x00030047, 0x00000004, 0x00000003, 0x00040047, 0x00000005, 0x00000022, 0x00000000,
+        0x00040047, 0x00000005, 0x00000021, 0x00000000, 0x00020013, 0x00000006, 0x00030021,
+        0x00000007, 0x00000006, 0x00040015, 0x00000008, 0x00000020, 0x00000000, 0x00040015,
+        0x00000009, 0x00000020, 0x00000001, 0x00040017, 0x0000000a, 0x00000008, 0x00000003,
+        0x00040017, 0x0000000b, 0x00000008, 0x00000004, 0x0003001d, 0x00000003, 0x0000000b,
+        0x0003001e, 0x00000004, 0x00000003, 0x00040020, 0x0000000c, 0x00000002, 0x00000004,
+        0x00040020, 0x0000000d, 0x00000002, 0x0000000b, 0x00040020, 0x0000000e, 0x00000002,
+        0x00000008, 0x00040020, 0x0000000f, 0x00000001, 0x0000000a, 0x00040020, 0x00000010,
+        0x00000001, 0x00000008, 0x00020014, 0x00000011, 0x0004002b, 0x00000009, 0x00000012,
+        0x00000000, 0x0004002b, 0x00000008, 0x00000013, 0x00000000, 0x0004002b, 0x00000008,
+        0x00000014, 0x00000003, 0x0003002e, 0x0000000b, 0x00000015, 0x0004003b, 0x0000000c,
+        0x00000005, 0x00000002, 0x0004003b, 0x0000000f, 0x00000002, 0x00000001, 0x00050036,
+        0x00000006, 0x00000001, 0x00000000, 0x00000007, 0x000200f8, 0x00000016, 0x00050041,
+        0x00000010, 0x00000017, 0x00000002, 0x00000013, 0x0004003d, 0x00000008, 0x00000018,
+        0x00000017, 0x00070041, 0x0000000e, 0x00000019, 0x00000005, 0x00000012, 0x00000018,
+        0x00000014, 0x0004003d, 0x00000008, 0x0000001a, 0x00000019, 0x000500aa, 0x00000011,
+        0x0000001b, 0x0000001a, 0x00000013, 0x000300f7, 0x0000001c, 0x00000000, 0x000400fa,
+        0x0000001b, 0x0000001d, 0x0000001c, 0x000200f8, 0x0000001d, 0x00060041, 0x0000000d,
+        0x0000001e, 0x00000005, 0x00000012, 0x00000018, 0x0003003e, 0x0000001e, 0x00000015,
+        0x000200f9, 0x0000001c, 0x000200f8, 0x0000001c, 0x000100fd, 0x00010038,
+    ];
+
+    #[profiling::function]
+    pub(crate) fn create(device: &Device) -> Result<Self, DriverError> {
+        trace!("create conditional dispatch pipeline");
+
+        let (descriptor_set_layout, layout, pipeline) = create_internal_pipeline(
+            device,
+            &[vk::DescriptorSetLayoutBinding {
+                binding: 0,
+                descriptor_type: vk::DescriptorType::STORAGE_BUFFER,
+                descriptor_count: 1,
+                stage_flags: vk::ShaderStageFlags::COMPUTE,
+                ..Default::default()
+            }],
+            vk::DescriptorSetLayoutCreateFlags::empty(),
+            None,
+            vk::PipelineCreateFlags::DISPATCH_BASE,
+            Self::SPIRV,
+            "conditional dispatch",
+        )?;
+
+        Ok(Self {
+            descriptor_set_layout,
+            layout,
+            pipeline,
+        })
+    }
+
+    pub(crate) fn destroy(this: &Self, device: &Device) {
+        unsafe {
+            device.destroy_pipeline(this.pipeline, None);
+            device.destroy_pipeline_layout(this.layout, None);
+            device.destroy_descriptor_set_layout(this.descriptor_set_layout, None);
+        }
+    }
+}
+
+/// Internal compute pipeline which generates the mip levels of images whose format does not support
+/// linear-filtered blits.
+///
+/// Each dispatch reads one mip level as a sampled image at set `0`, binding `0` and writes the next
+/// mip level as a storage image at binding `1`, using views of every array layer which are bound
+/// with `VK_KHR_push_descriptor`. The push constants hold the size of the written mip level in the
+/// first two components and the largest texel coordinate of the read mip level in the last two.
+/// Floating point formats are averaged over each 2x2 block of texels and integer formats use the
+/// first texel of each block.
+#[derive(Debug)]
+pub(crate) struct DownsamplePipeline {
+    pub(crate) descriptor_set_layout: vk::DescriptorSetLayout,
+    pub(crate) layout: vk::PipelineLayout,
+    pub(crate) pipeline: vk::Pipeline,
+}
+
+impl DownsamplePipeline {
+    /// The number of threads in the X and Y dimensions of each workgroup.
+    pub(crate) const GROUP_SIZE: u32 = 8;
+
+    // Compiled from the following GLSL, where `texture2DArray`, `image2DArray` and `vec4` are
+    // replaced by their integer variants for integer formats, which store `texelFetch(src, p, 0)`:
+    //
+    // #version 450
+    //
+    // layout(local_size_x = 8, local_size_y = 8) in;
+    //
+    // layout(binding = 0) uniform texture2DArray src;
+    // layout(binding = 1) uniform writeonly image2DArray dst;
+    //
+    // layout(push_constant) uniform Size {
+    //     uvec4 size;
+    // };
+    //
+    // void main() {
+    //     uvec3 id = gl_GlobalInvocationID;
+    //     if (all(lessThan(id.xy, size.xy))) {
+    //         uvec2 p0 = id.xy * 2;
+    //         uvec2 p1 = min(p0 + 1, size.zw);
+    //         vec4 a = texelFetch(src, ivec3(p0.x, p0.y, id.z), 0);
+    //         vec4 b = texelFetch(src, ivec3(p1.x, p0.y, id.z), 0);
+    //         vec4 c = texelFetch(src, ivec3(p0.x, p1.y, id.z), 0);
+    //         vec4 d = texelFetch(src, ivec3(p1.x, p1.y, id.z), 0);
+    //         imageStore(dst, ivec3(id), (a + b + c + d) * 0.25);
+    //     }
+    // }
+    const SPIRV_FLOAT: &'static [u32] = &[
+        0x07230203, 0x00010000, 0x00000000, 0x00000047, 0x00000000, 0x00020011, 0x00000001,
+        0x00020011, 0x00000038, 0x0003000e, 0x00000000, 0x00000001, 0x0006000f, 0x00000005,
+        0x00000001, 0x6e69616d, 0x00000000, 0x00000002, 0x00060010, 0x00000001, 0x00000011,
+        0x00000008, 0x00000008, 0x00000001, 0x00040047, 0x00000002, 0x0000000b, 0x0000001c,
+        0x00040047, 0x00000003, 0x00000022, 0x00000000, 0x00040047, 0x00000003, 0x00000021,
+        0x00000000, 0x00040047, 0x00000004, 0x00000022, 0x00000000, 0x00040047, 0x00000004,
+        0x00000021, 0x00000001, 0x00030047, 0x00000004, 0x00000019, 0x00030047, 0x00000005,
+        0x00000002, 0x00050048, 0x00000005, 0x00000000, 0x00000023, 0x00000000, 0x00020013,
+        0x00000006, 0x00030021, 0x00000007, 0x00000006, 0x00020014, 0x00000008, 0x00040015,
+        0x00000009, 0x00000020, 0x00000000, 0x00040015, 0x0000000a, 0x00000020, 0x00000001,
+        0x00040017, 0x0000000b, 0x00000008, 0x00000002, 0x00040017, 0x0000000c, 0x00000009,
+        0x00000002, 0x00040017, 0x0000000d, 0x00000009, 0x00000003, 0x00040017, 0x0000000e,
+        0x00000009, 0x00000004, 0x00040017, 0x0000000f, 0x0000000a, 0x00000003, 0x00030016,
+        0x00000010, 0x00000020, 0x00040017, 0x00000011, 0x00000010, 0x00000004, 0x00090019,
+        0x00000012, 0x00000010, 0x00000001, 0x00000000, 0x00000001, 0x00000000, 0x00000001,
+        0x00000000, 0x00090019, 0x00000013, 0x00000010, 0x00000001, 0x00000000, 0x00000001,
+        0x00000000, 0x00000002, 0x00000000, 0x00040020, 0x00000014, 0x00000000, 0x00000012,
+        0x00040020, 0x00000015, 0x00000000, 0x00000013, 0x0003001e, 0x00000005, 0x0000000e,
+        0x00040020, 0x00000016, 0x00000009, 0x00000005, 0x00040020, 0x00000017, 0x00000009,
+        0x0000000e, 0x00040020, 0x00000018, 0x00000001, 0x0000000d, 0x0004002b, 0x0000000a,
+        0x00000019, 0x00000000, 0x0004002b, 0x00000009, 0x0000001a, 0x00000001, 0x0004002b,
+        0x00000009, 0x0000001b, 0x00000002, 0x0005002c, 0x0000000c, 0x0000001c, 0x0000001a,
+        0x0000001a, 0x0005002c, 0x0000000c, 0x0000001d, 0x0000001b, 0x0000001b, 0x0004002b,
+        0x00000010, 0x0000001e, 0x3e800000, 0x0004003b, 0x00000014, 0x00000003, 0x00000000,
+        0x0004003b, 0x00000015, 0x00000004, 0x00000000, 0x0004003b, 0x00000016, 0x0000001f,
+        0x00000009, 0x0004003b, 0x00000018, 0x00000002, 0x00000001, 0x00050036, 0x00000006,
+        0x00000001, 0x00000000, 0x00000007, 0x000200f8, 0x00000020, 0x0004003d, 0x0000000d,
+        0x00000021, 0x00000002, 0x00050041, 0x00000017, 0x00000022, 0x0000001f, 0x00000019,
+        0x0004003d, 0x0000000e, 0x00000023, 0x00000022, 0x0007004f, 0x0000000c, 0x00000024,
+        0x00000021, 0x00000021, 0x00000000, 0x00000001, 0x0007004f, 0x0000000c, 0x00000025,
+        0x00000023, 0x00000023, 0x00000000, 0x00000001, 0x0007004f, 0x0000000c, 0x00000026,
+        0x00000023, 0x00000023, 0x00000002, 0x00000003, 0x000500b0, 0x0000000b, 0x00000027,
+        0x00000024, 0x00000025, 0x0004009b, 0x00000008, 0x00000028, 0x00000027, 0x000300f7,
+        0x00000029, 0x00000000, 0x000400fa, 0x00000028, 0x0000002a, 0x00000029, 0x000200f8,
+        0x0000002a, 0x00050084, 0x0000000c, 0x0000002b, 0x00000024, 0x0000001d, 0x00050080,
+        0x0000000c, 0x0000002c, 0x0000002b, 0x0000001c, 0x000500b2, 0x0000000b, 0x0000002d,
+        0x0000002c, 0x00000026, 0x000600a9, 0x0000000c, 0x0000002e, 0x0000002d, 0x0000002c,
+        0x00000026, 0x00050051, 0x00000009, 0x0000002f, 0x0000002b, 0x00000000, 0x00050051,
+        0x00000009, 0x00000030, 0x0000002b, 0x00000001, 0x00050051, 0x00000009, 0x00000031,
+        0x0000002e, 0x00000000, 0x00050051, 0x00000009, 0x00000032, 0x0000002e, 0x00000001,
+        0x00050051, 0x00000009, 0x00000033, 0x00000021, 0x00000002, 0x0004003d, 0x00000012,
+        0x00000034, 0x00000003, 0x00060050, 0x0000000d, 0x00000035, 0x0000002f, 0x00000030,
+        0x00000033, 0x0004007c, 0x0000000f, 0x00000036, 0x00000035, 0x0007005f, 0x00000011,
+        0x00000037, 0x00000034, 0x00000036, 0x00000002, 0x00000019, 0x00060050, 0x0000000d,
+        0x00000038, 0x00000031, 0x00000030, 0x00000033, 0x0004007c, 0x0000000f, 0x00000039,
+        0x00000038, 0x0007005f, 0x00000011, 0x0000003a, 0x00000034, 0x00000039, 0x00000002,
+        0x00000019, 0x00060050, 0x0000000d, 0x0000003b, 0x0000002f, 0x00000032, 0x00000033,
+        0x0004007c, 0x0000000f, 0x0000003c, 0x0000003b, 0x0007005f, 0x00000011, 0x0000003d,
+        0x00000034, 0x0000003c, 0x00000002, 0x00000019, 0x00060050, 0x0000000d, 0x0000003e,
+        0x00000031, 0x00000032, 0x00000033, 0x0004007c, 0x0000000f, 0x0000003f, 0x0000003e,
+        0x0007005f, 0x00000011, 0x00000040, 0x00000034, 0x0000003f, 0x00000002, 0x00000019,
+        0x00050081, 0x00000011, 0x00000041, 0x00000037, 0x0000003a, 0x00050081, 0x00000011,
+        0x00000042, 0x00000041, 0x0000003d, 0x00050081, 0x00000011, 0x00000043, 0x00000042,
+        0x00000040, 0x0005008e, 0x00000011, 0x00000044, 0x00000043, 0x0000001e, 0x0004003d,
+        0x00000013, 0x00000045, 0x00000004, 0x0004007c, 0x0000000f, 0x00000046, 0x00000021,
+        0x00040063, 0x00000045, 0x00000046, 0x00000044, 0x000200f9, 0x00000029, 0x000200f8,
+        0x00000029, 0x000100fd, 0x00010038,
+    ];
+    const SPIRV_SINT: &'static [u32] = &[
+        0x07230203, 0x00010000, 0x00000000, 0x00000038, 0x00000000, 0x00020011, 0x00000001,
+        0x00020011, 0x00000038, 0x0003000e, 0x00000000, 0x00000001, 0x0006000f, 0x00000005,
+        0x00000001, 0x6e69616d, 0x00000000, 0x00000002, 0x00060010, 0x00000001, 0x00000011,
+        0x00000008, 0x00000008, 0x00000001, 0x00040047, 0x00000002, 0x0000000b, 0x0000001c,
+        0x00040047, 0x00000003, 0x00000022, 0x00000000, 0x00040047, 0x00000003, 0x00000021,
+        0x00000000, 0x00040047, 0x00000004, 0x00000022, 0x00000000, 0x00040047, 0x00000004,
+        0x00000021, 0x00000001, 0x00030047, 0x00000004, 0x00000019, 0x00030047, 0x00000005,
+        0x00000002, 0x00050048, 0x00000005, 0x00000000, 0x00000023, 0x00000000, 0x00020013,
+        0x00000006, 0x00030021, 0x00000007, 0x00000006, 0x00020014, 0x00000008, 0x00040015,
+        0x00000009, 0x00000020, 0x00000000, 0x00040015, 0x0000000a, 0x00000020, 0x00000001,
+        0x00040017, 0x0000000b, 0x00000008, 0x00000002, 0x00040017, 0x0000000c, 0x00000009,
+        0x00000002, 0x00040017, 0x0000000d, 0x00000009, 0x00000003, 0x00040017, 0x0000000e,
+        0x00000009, 0x00000004, 0x00040017, 0x0000000f, 0x0000000a, 0x00000003, 0x00040017,
+        0x00000010, 0x0000000a, 0x00000004, 0x00090019, 0x00000011, 0x0000000a, 0x00000001,
+        0x00000000, 0x00000001, 0x00000000, 0x00000001, 0x00000000, 0x00090019, 0x00000012,
+        0x0000000a, 0x00000001, 0x00000000, 0x00000001, 0x00000000, 0x00000002, 0x00000000,
+        0x00040020, 0x00000013, 0x00000000, 0x00000011, 0x00040020, 0x00000014, 0x00000000,
+        0x00000012, 0x0003001e, 0x00000005, 0x0000000e, 0x00040020, 0x00000015, 0x00000009,
+        0x00000005, 0x00040020, 0x00000016, 0x00000009, 0x0000000e, 0x00040020, 0x00000017,
+        0x00000001, 0x0000000d, 0x0004002b, 0x0000000a, 0x00000018, 0x00000000, 0x0004002b,
+        0x00000009, 0x00000019, 0x00000001, 0x0004002b, 0x00000009, 0x0000001a, 0x00000002,
+        0x0005002c, 0x0000000c, 0x0000001b, 0x00000019, 0x00000019, 0x0005002c, 0x0000000c,
+        0x0000001c, 0x0000001a, 0x0000001a, 0x0004003b, 0x00000013, 0x00000003, 0x00000000,
+        0x0004003b, 0x00000014, 0x00000004, 0x00000000, 0x0004003b, 0x00000015, 0x0000001d,
+        0x00000009, 0x0004003b, 0x00000017, 0x00000002, 0x00000001, 0x00050036, 0x00000006,
+        0x00000001, 0x00000000, 0x00000007, 0x000200f8, 0x0000001e, 0x0004003d, 0x0000000d,
+        0x0000001f, 0x00000002, 0x00050041, 0x00000016, 0x00000020, 0x0000001d, 0x00000018,
+        0x0004003d, 0x0000000e, 0x00000021, 0x00000020, 0x0007004f, 0x0000000c, 0x00000022,
+        0x0000001f, 0x0000001f, 0x00000000, 0x00000001, 0x0007004f, 0x0000000c, 0x00000023,
+        0x00000021, 0x00000021, 0x00000000, 0x00000001, 0x0007004f, 0x0000000c, 0x00000024,
+        0x00000021, 0x00000021, 0x00000002, 0x00000003, 0x000500b0, 0x0000000b, 0x00000025,
+        0x00000022, 0x00000023, 0x0004009b, 0x00000008, 0x00000026, 0x00000025, 0x000300f7,
+        0x00000027, 0x00000000, 0x000400fa, 0x00000026, 0x00000028, 0x00000027, 0x000200f8,
+        0x00000028, 0x00050084, 0x0000000c, 0x00000029, 0x00000022, 0x0000001c, 0x00050080,
+        0x0000000c, 0x0000002a, 0x00000029, 0x0000001b, 0x000500b2, 0x0000000b, 0x0000002b,
+        0x0000002a, 0x00000024, 0x000600a9, 0x0000000c, 0x0000002c, 0x0000002b, 0x0000002a,
+        0x00000024, 0x00050051, 0x00000009, 0x0000002d, 0x00000029, 0x00000000, 0x00050051,
+        0x00000009, 0x0000002e, 0x00000029, 0x00000001, 0x00050051, 0x00000009, 0x0000002f,
+        0x0000002c, 0x00000000, 0x00050051, 0x00000009, 0x00000030, 0x0000002c, 0x00000001,
+        0x00050051, 0x00000009, 0x00000031, 0x0000001f, 0x00000002, 0x0004003d, 0x00000011,
+        0x00000032, 0x00000003, 0x00060050, 0x0000000d, 0x00000033, 0x0000002d, 0x0000002e,
+        0x00000031, 0x0004007c, 0x0000000f, 0x00000034, 0x00000033, 0x0007005f, 0x00000010,
+        0x00000035, 0x00000032, 0x00000034, 0x00000002, 0x00000018, 0x0004003d, 0x00000012,
+        0x00000036, 0x00000004, 0x0004007c, 0x0000000f, 0x00000037, 0x0000001f, 0x00040063,
+        0x00000036, 0x00000037, 0x00000035, 0x000200f9, 0x00000027, 0x000200f8, 0x00000027,
+        0x000100fd, 0x00010038,
+    ];
+    const SPIRV_UINT: &'static [u32] = &[
+        0x07230203, 0x00010000, 0x00000000, 0x00000037, 0x00000000, 0x00020011, 0x00000001,
+        0x00020011, 0x00000038, 0x0003000e, 0x00000000, 0x00000001, 0x0006000f, 0x00000005,
+        0x00000001, 0x6e69616d, 0x00000000, 0x00000002, 0x00060010, 0x00000001, 0x00000011,
+        0x00000008, 0x00000008, 0x00000001, 0x00040047, 0x00000002, 0x0000000b, 0x0000001c,
+        0x00040047, 0x00000003, 0x00000022, 0x00000000, 0x00040047, 0x00000003, 0x00000021,
+        0x00000000, 0x00040047, 0x00000004, 0x00000022, 0x00000000, 0x00040047, 0x00000004,
+        0x00000021, 0x00000001, 0x00030047, 0x00000004, 0x00000019, 0x00030047, 0x00000005,
+        0x00000002, 0x00050048, 0x00000005, 0x00000000, 0x00000023, 0x00000000, 0x00020013,
+        0x00000006, 0x00030021, 0x00000007, 0x00000006, 0x00020014, 0x00000008, 0x00040015,
+        0x00000009, 0x00000020, 0x00000000, 0x00040015, 0x0000000a, 0x00000020, 0x00000001,
+        0x00040017, 0x0000000b, 0x00000008, 0x00000002, 0x00040017, 0x0000000c, 0x00000009,
+        0x00000002, 0x00040017, 0x0000000d, 0x00000009, 0x00000003, 0x00040017, 0x0000000e,
+        0x00000009, 0x00000004, 0x00040017, 0x0000000f, 0x0000000a, 0x00000003, 0x00090019,
+        0x00000010, 0x00000009, 0x00000001, 0x00000000, 0x00000001, 0x00000000, 0x00000001,
+        0x00000000, 0x00090019, 0x00000011, 0x00000009, 0x00000001, 0x00000000, 0x00000001,
+        0x00000000, 0x00000002, 0x00000000, 0x00040020, 0x00000012, 0x00000000, 0x00000010,
+        0x00040020, 0x00000013, 0x00000000, 0x00000011, 0x0003001e, 0x00000005, 0x0000000e,
+        0x00040020, 0x00000014, 0x00000009, 0x00000005, 0x00040020, 0x00000015, 0x00000009,
+        0x0000000e, 0x00040020, 0x00000016, 0x00000001, 0x0000000d, 0x0004002b, 0x0000000a,
+        0x00000017, 0x00000000, 0x0004002b, 0x00000009, 0x00000018, 0x00000001, 0x0004002b,
+        0x00000009, 0x00000019, 0x00000002, 0x0005002c, 0x0000000c, 0x0000001a, 0x00000018,
+        0x00000018, 0x0005002c, 0x0000000c, 0x0000001b, 0x00000019, 0x00000019, 0x0004003b,
+        0x00000012, 0x00000003, 0x00000000, 0x0004003b, 0x00000013, 0x00000004, 0x00000000,
+        0x0004003b, 0x00000014, 0x0000001c, 0x00000009, 0x0004003b, 0x00000016, 0x00000002,
+        0x00000001, 0x00050036, 0x00000006, 0x00000001, 0x00000000, 0x00000007, 0x000200f8,
+        0x0000001d, 0x0004003d, 0x0000000d, 0x0000001e, 0x00000002, 0x00050041, 0x00000015,
+        0x0000001f, 0x0000001c, 0x00000017, 0x0004003d, 0x0000000e, 0x00000020, 0x0000001f,
+        0x0007004f, 0x0000000c, 0x00000021, 0x0000001e, 0x0000001e, 0x00000000, 0x00000001,
+        0x0007004f, 0x0000000c, 0x00000022, 0x00000020, 0x00000020, 0x00000000, 0x00000001,
+        0x0007004f, 0x0000000c, 0x00000023, 0x00000020, 0x00000020, 0x00000002, 0x00000003,
+        0x000500b0, 0x0000000b, 0x00000024, 0x00000021, 0x00000022, 0x0004009b, 0x00000008,
+        0x00000025, 0x00000024, 0x000300f7, 0x00000026, 0x00000000, 0x000400fa, 0x00000025,
+        0x00000027, 0x00000026, 0x000200f8, 0x00000027, 0x00050084, 0x0000000c, 0x00000028,
+        0x00000021, 0x0000001b, 0x00050080, 0x0000000c, 0x00000029, 0x00000028, 0x0000001a,
+        0x000500b2, 0x0000000b, 0x0000002a, 0x00000029, 0x00000023, 0x000600a9, 0x0000000c,
+        0x0000002b, 0x0000002a, 0x00000029, 0x00000023, 0x00050051, 0x00000009, 0x0000002c,
+        0x00000028, 0x00000000, 0x00050051, 0x00000009, 0x0000002d, 0x00000028, 0x00000001,
+        0x00050051, 0x00000009, 0x0000002e, 0x0000002b, 0x00000000, 0x00050051, 0x00000009,
+        0x0000002f, 0x0000002b, 0x00000001, 0x00050051, 0x00000009, 0x00000030, 0x0000001e,
+        0x00000002, 0x0004003d, 0x00000010, 0x00000031, 0x00000003, 0x00060050, 0x0000000d,
+        0x00000032, 0x0000002c, 0x0000002d, 0x00000030, 0x0004007c, 0x0000000f, 0x00000033,
+        0x00000032, 0x0007005f, 0x0000000e, 0x00000034, 0x00000031, 0x00000033, 0x00000002,
+        0x00000017, 0x0004003d, 0x00000011, 0x00000035, 0x00000004, 0x0004007c, 0x0000000f,
+        0x00000036, 0x0000001e, 0x00040063, 0x00000035, 0x00000036, 0x00000034, 0x000200f9,
+        0x00000026, 0x000200f8, 0x00000026, 0x000100fd, 0x00010038,
+    ];
+
+    #[profiling::function]
+    pub(crate) fn create(device: &Device, ty: DownsampleType) -> Result<Self, DriverError> {
+        trace!("create downsample pipeline: {ty:?}");
+
+        let (descriptor_set_layout, layout, pipeline) = create_internal_pipeline(
+            device,
+            &[
+                vk::DescriptorSetLayoutBinding {
+                    binding: 0,
+                    descriptor_type: vk::DescriptorType::SAMPLED_IMAGE,
+                    descriptor_count: 1,
+                    stage_flags: vk::ShaderStageFlags::COMPUTE,
+                    ..Default::default()
+                },
+                vk::DescriptorSetLayoutBinding {
+                    binding: 1,
+                    descriptor_type: vk::DescriptorType::STORAGE_IMAGE,
+                    descriptor_count: 1,
+                    stage_flags: vk::ShaderStageFlags::COMPUTE,
+                    ..Default::default()
+                },
+            ],
+            vk::DescriptorSetLayoutCreateFlags::PUSH_DESCRIPTOR_KHR,
+            Some(vk::PushConstantRange {
+                stage_flags: vk::ShaderStageFlags::COMPUTE,
+                offset: 0,
+                size: 16,
+            }),
+            vk::PipelineCreateFlags::empty(),
+            match ty {
+                DownsampleType::Float => Self::SPIRV_FLOAT,
+                DownsampleType::SignedInteger => Self::SPIRV_SINT,
+                DownsampleType::UnsignedInteger => Self::SPIRV_UINT,
+            },
+            "downsample",
+        )?;
+
+        Ok(Self {
+            descriptor_set_layout,
+            layout,
+            pipeline,
+        })
+    }
+
+    pub(crate) fn destroy(this: &Self, device: &Device) {
+        unsafe {
+            device.destroy_pipeline(this.pipeline, None);
+            device.destroy_pipeline_layout(this.layout, None);
+            device.destroy_descriptor_set_layout(this.descriptor_set_layout, None);
+        }
+    }
+
+    /// Returns the pipeline which writes the mip levels of images with the given information, or
+    /// an error if the device, format, type or usage of the image is not supported.
+    #[profiling::function]
+    pub(crate) fn pipeline(device: &Device, info: ImageInfo) -> Result<&Self, DriverError> {
+        if device.push_descriptor_ext.is_none() {
+            warn!(
+                "downsampling {:?} images requires VK_KHR_push_descriptor",
+                info.fmt
+            );
+
+            return Err(DriverError::Unsupported);
+        }
+
+        if !matches!(
+            info.ty,
+            ImageType::Texture2D
+                | ImageType::TextureArray2D
+                | ImageType::Cube
+                | ImageType::CubeArray
+        ) {
+            warn!("downsampling {:?} images is not supported", info.ty);
+
+            return Err(DriverError::Unsupported);
+        }
+
+        if !info
+            .usage
+            .contains(vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::STORAGE)
+        {
+            warn!("downsampling images requires sampled and storage usage");
+
+            return Err(DriverError::Unsupported);
+        }
+
+        if format_aspect_mask(info.fmt) != vk::ImageAspectFlags::COLOR {
+            warn!("downsampling {:?} images is not supported", info.fmt);
+
+            return Err(DriverError::Unsupported);
+        }
+
+        let features = Device::format_properties(device, info.fmt);
+        let features = if info.tiling == vk::ImageTiling::LINEAR {
+            features.linear_tiling_features
+        } else {
+            features.optimal_tiling_features
+        };
+
+        if !features
+            .contains(vk::FormatFeatureFlags::SAMPLED_IMAGE | vk::FormatFeatureFlags::STORAGE_IMAGE)
+        {
+            warn!(
+                "downsampling {:?} images requires sampled and storage image format features",
+                info.fmt
+            );
+
+            return Err(DriverError::Unsupported);
+        }
+
+        // The downsample shaders write storage images without declaring their format
+        if !device
+            .physical_device
+            .features_v1_0
+            .shader_storage_image_write_without_format
+        {
+            warn!("downsampling images requires shader storage image write without format");
+
+            return Err(DriverError::Unsupported);
+        }
+
+        let ty = if is_signed_integer_format(info.fmt) {
+            DownsampleType::SignedInteger
+        } else if is_integer_format(info.fmt) {
+            DownsampleType::UnsignedInteger
+        } else {
+            DownsampleType::Float
+        };
+
+        Device::downsample_pipeline(device, ty)
+    }
+
+    /// Records commands which write `mip_level` of `image` from the previous mip level.
+    ///
+    /// The previous mip level must be in `TRANSFER_SRC_OPTIMAL` layout and `mip_level` must be in
+    /// `TRANSFER_DST_OPTIMAL` layout following transfer commands, as they would be for a blit. Both
+    /// are returned to those layouts and the written mip level is made available to transfer
+    /// commands.
+    #[profiling::function]
+    pub(crate) fn record(
+        device: &Device,
+        cmd_buf: vk::CommandBuffer,
+        image: &Image,
+        mip_level: u32,
+    ) -> Result<(), DriverError> {
+        let info = image.info;
+        let pipeline = Self::pipeline(device, info)?;
+        let push_descriptor_ext = device.push_descriptor_ext.as_ref().unwrap();
+
+        let layer_count = info.array_layer_count();
+        let view = |mip_level| {
+            Image::view(
+                image,
+                ImageViewInfo {
+                    array_layer_count: Some(layer_count),
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    base_array_layer: 0,
+                    base_mip_level: mip_level,
+                    fmt: info.fmt,
+                    mip_level_count: Some(1),
+                    ty: ImageType::TextureArray2D,
+                },
+            )
+        };
+        let src_view = view(mip_level - 1)?;
+        let dst_view = view(mip_level)?;
+        let barrier = |mip_level, old_layout, new_layout, src_access_mask, dst_access_mask| {
+            vk::ImageMemoryBarrier::builder()
+                .src_access_mask(src_access_mask)
+                .dst_access_mask(dst_access_mask)
+                .old_layout(old_layout)
+                .new_layout(new_layout)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(**image)
+                .subresource_range(vk::ImageSubresourceRange {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    base_mip_level: mip_level,
+                    level_count: 1,
+                    base_array_layer: 0,
+                    layer_count,
+                })
+                .build()
+        };
+
+        let src_width = (info.width >> (mip_level - 1)).max(1);
+        let src_height = (info.height >> (mip_level - 1)).max(1);
+        let dst_width = (info.width >> mip_level).max(1);
+        let dst_height = (info.height >> mip_level).max(1);
+        let push_constants = [dst_width, dst_height, src_width - 1, src_height - 1]
+            .into_iter()
+            .flat_map(u32::to_ne_bytes)
+            .collect::<Vec<_>>();
+
+        unsafe {
+            device.cmd_pipeline_barrier(
+                cmd_buf,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &[
+                    barrier(
+                        mip_level - 1,
+                        vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                        vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                        vk::AccessFlags::empty(),
+                        vk::AccessFlags::SHADER_READ,
+                    ),
+                    barrier(
+                        mip_level,
+                        vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                        vk::ImageLayout::GENERAL,
+                        vk::AccessFlags::empty(),
+                        vk::AccessFlags::SHADER_WRITE,
+                    ),
+                ],
+            );
+            device.cmd_bind_pipeline(cmd_buf, vk::PipelineBindPoint::COMPUTE, pipeline.pipeline);
+            push_descriptor_ext.cmd_push_descriptor_set(
+                cmd_buf,
+                vk::PipelineBindPoint::COMPUTE,
+                pipeline.layout,
+                0,
+                &[
+                    vk::WriteDescriptorSet::builder()
+                        .dst_binding(0)
+                        .descriptor_type(vk::DescriptorType::SAMPLED_IMAGE)
+                        .image_info(&[vk::DescriptorImageInfo {
+                            sampler: vk::Sampler::null(),
+                            image_view: src_view,
+                            image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                        }])
+                        .build(),
+                    vk::WriteDescriptorSet::builder()
+                        .dst_binding(1)
+                        .descriptor_type(vk::DescriptorType::STORAGE_IMAGE)
+                        .image_info(&[vk::DescriptorImageInfo {
+                            sampler: vk::Sampler::null(),
+                            image_view: dst_view,
+                            image_layout: vk::ImageLayout::GENERAL,
+                        }])
+                        .build(),
+                ],
+            );
+            device.cmd_push_constants(
+                cmd_buf,
+                pipeline.layout,
+                vk::ShaderStageFlags::COMPUTE,
+                0,
+                &push_constants,
+            );
+            device.cmd_dispatch(
+                cmd_buf,
+                dst_width.div_ceil(Self::GROUP_SIZE),
+                dst_height.div_ceil(Self::GROUP_SIZE),
+                layer_count,
+            );
+            device.cmd_pipeline_barrier(
+                cmd_buf,
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &[
+                    barrier(
+                        mip_level - 1,
+                        vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                        vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                        vk::AccessFlags::empty(),
+                        vk::AccessFlags::TRANSFER_READ,
+                    ),
+                    barrier(
+                        mip_level,
+                        vk::ImageLayout::GENERAL,
+                        vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                        vk::AccessFlags::SHADER_WRITE,
+                        vk::AccessFlags::TRANSFER_READ | vk::AccessFlags::TRANSFER_WRITE,
+                    ),
+                ],
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The kind of values which are read and written by a [`DownsamplePipeline`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum DownsampleType {
+    Float = 0,
+    SignedInteger = 1,
+    UnsignedInteger = 2,
+}
+
+// Creates the objects of a pipeline used internally, which has a single descriptor set
+fn create_internal_pipeline(
+    device: &Device,
+    bindings: &[vk::DescriptorSetLayoutBinding],
+    descriptor_set_layout_flags: vk::DescriptorSetLayoutCreateFlags,
+    push_constant_range: Option<vk::PushConstantRange>,
+    flags: vk::PipelineCreateFlags,
+    code: &[u32],
+    name: &str,
+) -> Result<(vk::DescriptorSetLayout, vk::PipelineLayout, vk::Pipeline), DriverError> {
+    use std::slice::from_ref;
+
+    unsafe {
+        let descriptor_set_layout = device
+            .create_descriptor_set_layout(
+                &vk::DescriptorSetLayoutCreateInfo::builder()
+                    .flags(descriptor_set_layout_flags)
+                    .bindings(bindings),
+                None,
+            )
+            .map_err(|err| {
+                warn!("{err}");
+
+                DriverError::Unsupported
+            })?;
+        let layout = device
+            .create_pipeline_layout(
+                &vk::PipelineLayoutCreateInfo::builder()
+                    .set_layouts(from_ref(&descriptor_set_layout))
+                    .push_constant_ranges(push_constant_range.as_slice()),
+                None,
+            )
+            .map_err(|err| {
+                warn!("{err}");
+
+                DriverError::Unsupported
+            })?;
+        let shader_module = device
+            .create_shader_module(&vk::ShaderModuleCreateInfo::builder().code(code), None)
+            .map_err(|err| {
+                warn!("{err}");
+
+                DriverError::Unsupported
+            })?;
+        let entry_name = CString::new("main").unwrap();
+        let pipeline_info = vk::ComputePipelineCreateInfo::builder()
+            .flags(flags)
+            .stage(
+                vk::PipelineShaderStageCreateInfo::builder()
+                    .module(shader_module)
+                    .stage(vk::ShaderStageFlags::COMPUTE)
+                    .name(&entry_name)
+                    .build(),
+            )
+            .layout(layout);
+        let pipeline = device
+            .create_compute_pipelines(
+                vk::PipelineCache::null(),
+                from_ref(&pipeline_info.build()),
+                None,
+            )
+            .map_err(|(_, err)| {
+                warn!("{err}");
+
+                DriverError::Unsupported
+            })?[0];
+
+        device.destroy_shader_module(shader_module, None);
+
+        Device::set_debug_name(device, pipeline, name);
+
+        Ok((descriptor_set_layout, layout, pipeline))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,5 +1,5 @@
 use {
-    super::{device::Device, DescriptorSetLayout, DriverError},
+    super::{device::Device, DriverError},
     ash::vk,
     log::warn,
     std::{ops::Deref, sync::Arc, thread::panicking},
@@ -147,7 +147,7 @@ impl DescriptorPool {
 
     pub fn allocate_descriptor_set(
         this: &Self,
-        layout: &DescriptorSetLayout,
+        layout: &vk::DescriptorSetLayout,
     ) -> Result<DescriptorSet, DriverError> {
         Ok(Self::allocate_descriptor_sets(this, layout, 1)?
             .next()
@@ -157,7 +157,7 @@ impl DescriptorPool {
     #[profiling::function]
     pub fn allocate_descriptor_sets<'a>(
         this: &'a Self,
-        layout: &DescriptorSetLayout,
+        layout: &vk::DescriptorSetLayout,
         count: u32,
     ) -> Result<impl Iterator<Item = DescriptorSet> + 'a, DriverError> {
         use std::slice::from_ref;
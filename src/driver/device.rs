@@ -1,8 +1,16 @@
 //! Logical device resource types
 
 use {
-    super::{physical_device::PhysicalDevice, DriverError, Instance},
-    ash::{extensions::khr, vk},
+    super::{
+        compute::{ConditionalDispatchPipeline, DownsamplePipeline, DownsampleType},
+        physical_device::PhysicalDevice,
+        staging::{StagingRegion, StagingRing},
+        DriverError, Instance,
+    },
+    ash::{
+        extensions::{ext, khr},
+        vk,
+    },
     ash_window::enumerate_required_extensions,
     derive_builder::{Builder, UninitializedFieldError},
     gpu_allocator::{
@@ -13,11 +21,12 @@ use {
     raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle},
     std::{
         cmp::Ordering,
-        ffi::CStr,
+        ffi::{CStr, CString},
         fmt::{Debug, Formatter},
         iter::{empty, repeat},
-        mem::{forget, ManuallyDrop},
+        mem::{forget, take, transmute, ManuallyDrop},
         ops::Deref,
+        sync::OnceLock,
         thread::panicking,
         time::Instant,
     },
@@ -38,21 +47,38 @@ pub struct Device {
 
     pub(super) allocator: ManuallyDrop<Mutex<Allocator>>,
 
+    conditional_dispatch_pipeline: OnceLock<ConditionalDispatchPipeline>,
+
+    pub(crate) conditional_rendering_ext: Option<vk::ExtConditionalRenderingFn>,
+
+    pub(crate) dynamic_rendering_ext: Option<khr::DynamicRendering>,
+
+    pub(crate) extended_dynamic_state_ext: Option<ext::ExtendedDynamicState>,
+
     device: ash::Device,
 
+    downsample_pipelines: [OnceLock<DownsamplePipeline>; 3],
+
     /// Vulkan instance pointer, which includes useful functions.
     instance: Instance,
 
+    pub(crate) mesh_shader_ext: Option<ext::MeshShader>,
+
     /// The physical device, which contains useful data about features, properties, and limits.
     pub physical_device: PhysicalDevice,
 
+    pub(crate) push_descriptor_ext: Option<khr::PushDescriptor>,
+
     /// The physical execution queues which all work will be submitted to.
     pub(crate) queues: Vec<Vec<vk::Queue>>,
 
     pub(crate) ray_trace_ext: Option<khr::RayTracingPipeline>,
 
+    staging: Mutex<StagingRing>,
+
     pub(super) surface_ext: Option<khr::Surface>,
     pub(super) swapchain_ext: Option<khr::Swapchain>,
+    pub(crate) synchronization2_ext: Option<khr::Synchronization2>,
 }
 
 impl Device {
@@ -73,7 +99,7 @@ impl Device {
     where
         F: FnOnce(vk::DeviceCreateInfo) -> ash::prelude::VkResult<ash::Device>,
     {
-        let mut enabled_ext_names = Vec::with_capacity(5);
+        let mut enabled_ext_names = Vec::with_capacity(11);
 
         if display_window {
             enabled_ext_names.push(vk::KhrSwapchainFn::name().as_ptr());
@@ -84,6 +110,32 @@ impl Device {
             enabled_ext_names.push(vk::KhrDeferredHostOperationsFn::name().as_ptr());
         }
 
+        if physical_device
+            .conditional_rendering_features
+            .conditional_rendering
+        {
+            enabled_ext_names.push(vk::ExtConditionalRenderingFn::name().as_ptr());
+        }
+
+        if physical_device.dynamic_rendering_features.dynamic_rendering {
+            enabled_ext_names.push(vk::KhrDynamicRenderingFn::name().as_ptr());
+        }
+
+        if physical_device
+            .extended_dynamic_state_features
+            .extended_dynamic_state
+        {
+            enabled_ext_names.push(vk::ExtExtendedDynamicStateFn::name().as_ptr());
+        }
+
+        if physical_device.mesh_shader_features.mesh_shader {
+            enabled_ext_names.push(vk::ExtMeshShaderFn::name().as_ptr());
+        }
+
+        if physical_device.push_descriptor_properties.is_some() {
+            enabled_ext_names.push(vk::KhrPushDescriptorFn::name().as_ptr());
+        }
+
         if physical_device.ray_query_features.ray_query {
             enabled_ext_names.push(vk::KhrRayQueryFn::name().as_ptr());
         }
@@ -92,6 +144,10 @@ impl Device {
             enabled_ext_names.push(vk::KhrRayTracingPipelineFn::name().as_ptr());
         }
 
+        if physical_device.synchronization2_features.synchronization2 {
+            enabled_ext_names.push(vk::KhrSynchronization2Fn::name().as_ptr());
+        }
+
         let priorities = repeat(1.0)
             .take(
                 physical_device
@@ -126,24 +182,66 @@ impl Device {
         let mut features_v1_2 = vk::PhysicalDeviceVulkan12Features::default();
         let mut acceleration_structure_features =
             vk::PhysicalDeviceAccelerationStructureFeaturesKHR::default();
+        let mut conditional_rendering_features =
+            vk::PhysicalDeviceConditionalRenderingFeaturesEXT::default();
+        let mut dynamic_rendering_features = vk::PhysicalDeviceDynamicRenderingFeatures::default();
+        let mut extended_dynamic_state_features =
+            vk::PhysicalDeviceExtendedDynamicStateFeaturesEXT::default();
         let mut index_type_uin8_feautres = vk::PhysicalDeviceIndexTypeUint8FeaturesEXT::default();
         let mut ray_query_features = vk::PhysicalDeviceRayQueryFeaturesKHR::default();
         let mut ray_trace_features = vk::PhysicalDeviceRayTracingPipelineFeaturesKHR::default();
+        let mut synchronization2_features = vk::PhysicalDeviceSynchronization2Features::default();
         let mut features = vk::PhysicalDeviceFeatures2::builder()
             .push_next(&mut features_v1_1)
             .push_next(&mut features_v1_2)
             .push_next(&mut acceleration_structure_features)
             .push_next(&mut index_type_uin8_feautres)
             .push_next(&mut ray_query_features)
-            .push_next(&mut ray_trace_features)
-            .build();
+            .push_next(&mut ray_trace_features);
+
+        if physical_device
+            .conditional_rendering_features
+            .conditional_rendering
+        {
+            features = features.push_next(&mut conditional_rendering_features);
+        }
+
+        if physical_device.dynamic_rendering_features.dynamic_rendering {
+            features = features.push_next(&mut dynamic_rendering_features);
+        }
+
+        if physical_device
+            .extended_dynamic_state_features
+            .extended_dynamic_state
+        {
+            features = features.push_next(&mut extended_dynamic_state_features);
+        }
+
+        if physical_device.synchronization2_features.synchronization2 {
+            features = features.push_next(&mut synchronization2_features);
+        }
+
+        let mut features = features.build();
         unsafe { get_physical_device_features2(**physical_device, &mut features) };
 
-        let device_create_info = vk::DeviceCreateInfo::builder()
+        // Per-primitive shading rates are not enabled because they require
+        // VK_KHR_fragment_shading_rate
+        let mut mesh_shader_features = vk::PhysicalDeviceMeshShaderFeaturesEXT::builder()
+            .task_shader(physical_device.mesh_shader_features.task_shader)
+            .mesh_shader(physical_device.mesh_shader_features.mesh_shader)
+            .multiview_mesh_shader(physical_device.mesh_shader_features.multiview_mesh_shader)
+            .mesh_shader_queries(physical_device.mesh_shader_features.mesh_shader_queries);
+
+        let mut device_create_info = vk::DeviceCreateInfo::builder()
             .queue_create_infos(&queue_infos)
             .enabled_extension_names(&enabled_ext_names)
-            .push_next(&mut features)
-            .build();
+            .push_next(&mut features);
+
+        if physical_device.mesh_shader_features.mesh_shader {
+            device_create_info = device_create_info.push_next(&mut mesh_shader_features);
+        }
+
+        let device_create_info = device_create_info.build();
 
         create_fn(device_create_info)
     }
@@ -303,24 +401,101 @@ impl Device {
             .accel_struct_properties
             .is_some()
             .then(|| khr::AccelerationStructure::new(&instance, &device));
+        let conditional_rendering_ext = physical_device
+            .conditional_rendering_features
+            .conditional_rendering
+            .then(|| {
+                vk::ExtConditionalRenderingFn::load(|name| unsafe {
+                    transmute(instance.get_device_proc_addr(device.handle(), name.as_ptr()))
+                })
+            });
+        let dynamic_rendering_ext = physical_device
+            .dynamic_rendering_features
+            .dynamic_rendering
+            .then(|| khr::DynamicRendering::new(&instance, &device));
+        let extended_dynamic_state_ext = physical_device
+            .extended_dynamic_state_features
+            .extended_dynamic_state
+            .then(|| ext::ExtendedDynamicState::new(&instance, &device));
+        let mesh_shader_ext = physical_device
+            .mesh_shader_features
+            .mesh_shader
+            .then(|| ext::MeshShader::new(&instance, &device));
+        let push_descriptor_ext = physical_device
+            .push_descriptor_properties
+            .is_some()
+            .then(|| khr::PushDescriptor::new(&instance, &device));
         let ray_trace_ext = physical_device
             .ray_trace_features
             .ray_tracing_pipeline
             .then(|| khr::RayTracingPipeline::new(&instance, &device));
+        let synchronization2_ext = physical_device
+            .synchronization2_features
+            .synchronization2
+            .then(|| khr::Synchronization2::new(&instance, &device));
 
         Ok(Self {
             accel_struct_ext,
             allocator: ManuallyDrop::new(Mutex::new(allocator)),
+            conditional_dispatch_pipeline: OnceLock::new(),
+            conditional_rendering_ext,
             device,
+            downsample_pipelines: Default::default(),
+            dynamic_rendering_ext,
+            extended_dynamic_state_ext,
             instance,
+            mesh_shader_ext,
             physical_device,
+            push_descriptor_ext,
             queues,
             ray_trace_ext,
+            staging: Default::default(),
             surface_ext,
             swapchain_ext,
+            synchronization2_ext,
         })
     }
 
+    /// Returns the internal pipeline which skips the dispatches of conditional passes, creating it
+    /// on first use.
+    pub(crate) fn conditional_dispatch_pipeline(
+        this: &Self,
+    ) -> Result<&ConditionalDispatchPipeline, DriverError> {
+        if let Some(pipeline) = this.conditional_dispatch_pipeline.get() {
+            return Ok(pipeline);
+        }
+
+        let pipeline = ConditionalDispatchPipeline::create(this)?;
+
+        // Another thread may have created the pipeline in the meantime
+        if let Err(pipeline) = this.conditional_dispatch_pipeline.set(pipeline) {
+            ConditionalDispatchPipeline::destroy(&pipeline, this);
+        }
+
+        Ok(this.conditional_dispatch_pipeline.get().unwrap())
+    }
+
+    /// Returns the internal pipeline which generates mip levels of images which cannot be blitted,
+    /// creating it on first use.
+    pub(crate) fn downsample_pipeline(
+        this: &Self,
+        ty: DownsampleType,
+    ) -> Result<&DownsamplePipeline, DriverError> {
+        let cell = &this.downsample_pipelines[ty as usize];
+        if let Some(pipeline) = cell.get() {
+            return Ok(pipeline);
+        }
+
+        let pipeline = DownsamplePipeline::create(this, ty)?;
+
+        // Another thread may have created the pipeline in the meantime
+        if let Err(pipeline) = cell.set(pipeline) {
+            DownsamplePipeline::destroy(&pipeline, this);
+        }
+
+        Ok(cell.get().unwrap())
+    }
+
     /// Lists the physical device's format capabilities.
     #[profiling::function]
     pub fn format_properties(this: &Self, format: vk::Format) -> vk::FormatProperties {
@@ -369,6 +544,66 @@ impl Device {
         &this.instance
     }
 
+    /// Opens a debug label region named `label` in the given command buffer.
+    ///
+    /// Has no effect unless the device was created with debug layers enabled.
+    pub(crate) fn begin_debug_label(this: &Self, cmd_buf: vk::CommandBuffer, label: &str) {
+        if let Some(debug_utils) = Instance::debug_utils(&this.instance) {
+            let label = CString::new(label).unwrap_or_default();
+            let label_info = vk::DebugUtilsLabelEXT::builder().label_name(&label);
+
+            unsafe {
+                debug_utils.cmd_begin_debug_utils_label(cmd_buf, &label_info);
+            }
+        }
+    }
+
+    /// Closes the debug label region most recently opened by [`Device::begin_debug_label`].
+    pub(crate) fn end_debug_label(this: &Self, cmd_buf: vk::CommandBuffer) {
+        if let Some(debug_utils) = Instance::debug_utils(&this.instance) {
+            unsafe {
+                debug_utils.cmd_end_debug_utils_label(cmd_buf);
+            }
+        }
+    }
+
+    /// Gives the Vulkan object `handle` a name which is displayed by debugging tools.
+    ///
+    /// Has no effect unless the device was created with debug layers enabled.
+    pub(crate) fn set_debug_name<H: vk::Handle>(this: &Self, handle: H, name: &str) {
+        if let Some(debug_utils) = Instance::debug_utils(&this.instance) {
+            let name = CString::new(name).unwrap_or_default();
+            let name_info = vk::DebugUtilsObjectNameInfoEXT::builder()
+                .object_type(H::TYPE)
+                .object_handle(handle.as_raw())
+                .object_name(&name);
+
+            if let Err(err) =
+                unsafe { debug_utils.set_debug_utils_object_name(this.device.handle(), &name_info) }
+            {
+                warn!("unable to set object name: {err}");
+            }
+        }
+    }
+
+    /// Copies `data` into the staging ring of this device, at an offset which is a multiple of
+    /// `alignment`.
+    ///
+    /// The returned region may be used as the source of transfer commands until it is dropped.
+    pub(crate) fn stage(
+        this: &Self,
+        data: &[u8],
+        alignment: vk::DeviceSize,
+    ) -> Result<StagingRegion, DriverError> {
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let mut staging = this.staging.lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let mut staging = staging.unwrap();
+
+        StagingRing::stage(&mut staging, this, data, alignment)
+    }
+
     #[profiling::function]
     pub(crate) fn wait_for_fence(this: &Self, fence: &vk::Fence) -> Result<(), DriverError> {
         use std::slice::from_ref;
@@ -448,6 +683,27 @@ impl Drop for Device {
             warn!("device_wait_idle() failed: {err}");
         }
 
+        if let Some(pipeline) = self.conditional_dispatch_pipeline.take() {
+            ConditionalDispatchPipeline::destroy(&pipeline, self);
+        }
+
+        for pipeline in take(&mut self.downsample_pipelines)
+            .into_iter()
+            .flat_map(OnceLock::into_inner)
+        {
+            DownsamplePipeline::destroy(&pipeline, self);
+        }
+
+        {
+            #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+            let mut staging = self.staging.lock();
+
+            #[cfg(not(feature = "parking_lot"))]
+            let mut staging = staging.unwrap();
+
+            StagingRing::destroy(&mut staging, self);
+        }
+
         unsafe {
             ManuallyDrop::drop(&mut self.allocator);
         }
@@ -15,9 +15,20 @@ use {
     derive_builder::{Builder, UninitializedFieldError},
     log::{trace, warn},
     ordered_float::OrderedFloat,
-    std::{collections::HashSet, ffi::CString, sync::Arc, thread::panicking},
+    std::{
+        collections::{HashMap, HashSet},
+        ffi::CString,
+        sync::Arc,
+        thread::panicking,
+    },
 };
 
+#[cfg(feature = "parking_lot")]
+use parking_lot::Mutex;
+
+#[cfg(not(feature = "parking_lot"))]
+use std::sync::Mutex;
+
 const RGBA_COLOR_COMPONENTS: vk::ColorComponentFlags = vk::ColorComponentFlags::from_raw(
     vk::ColorComponentFlags::R.as_raw()
         | vk::ColorComponentFlags::G.as_raw()
@@ -366,17 +377,50 @@ pub struct GraphicPipeline {
     pub name: Option<String>,
 
     pub(crate) push_constants: Vec<vk::PushConstantRange>,
+    rendering_pipelines: Mutex<HashMap<(Option<DepthStencilMode>, RenderingFormats), vk::Pipeline>>,
     pub(crate) separate_samplers: Box<[DescriptorBinding]>,
     pub(crate) shader_modules: Vec<vk::ShaderModule>,
     pub(super) state: GraphicPipelineState,
 }
 
 impl GraphicPipeline {
+    /// States which are set by the resolver when the pipeline is bound and which may then be set
+    /// using [`Draw`](crate::graph::pass_ref::Draw) commands.
+    const DYNAMIC_STATES: [vk::DynamicState; 9] = [
+        vk::DynamicState::VIEWPORT,
+        vk::DynamicState::SCISSOR,
+        vk::DynamicState::BLEND_CONSTANTS,
+        vk::DynamicState::DEPTH_BIAS,
+        vk::DynamicState::DEPTH_BOUNDS,
+        vk::DynamicState::LINE_WIDTH,
+        vk::DynamicState::STENCIL_COMPARE_MASK,
+        vk::DynamicState::STENCIL_REFERENCE,
+        vk::DynamicState::STENCIL_WRITE_MASK,
+    ];
+
+    /// Additional dynamic states of devices which support `VK_EXT_extended_dynamic_state`.
+    const EXTENDED_DYNAMIC_STATES: [vk::DynamicState; 8] = [
+        vk::DynamicState::CULL_MODE_EXT,
+        vk::DynamicState::FRONT_FACE_EXT,
+        vk::DynamicState::DEPTH_TEST_ENABLE_EXT,
+        vk::DynamicState::DEPTH_WRITE_ENABLE_EXT,
+        vk::DynamicState::DEPTH_COMPARE_OP_EXT,
+        vk::DynamicState::DEPTH_BOUNDS_TEST_ENABLE_EXT,
+        vk::DynamicState::STENCIL_TEST_ENABLE_EXT,
+        vk::DynamicState::STENCIL_OP_EXT,
+    ];
+
     /// Creates a new graphic pipeline on the given device.
     ///
     /// The correct pipeline stages will be enabled based on the provided shaders. See [Shader] for
     /// details on all available stages.
     ///
+    /// Pipelines with a mesh shader, and optionally a task shader, do not use vertex input or input
+    /// assembly state and are drawn using [`Draw::draw_mesh_tasks`] and similar functions. These
+    /// pipelines require a device which supports `VK_EXT_mesh_shader` (see
+    /// [`PhysicalDevice::mesh_shader_features`]); otherwise [`DriverError::Unsupported`] is
+    /// returned.
+    ///
     /// # Panics
     ///
     /// If shader code is not a multiple of four bytes.
@@ -405,6 +449,9 @@ impl GraphicPipeline {
     /// assert_eq!(pipeline.info.front_face, vk::FrontFace::COUNTER_CLOCKWISE);
     /// # Ok(()) }
     /// ```
+    ///
+    /// [`Draw::draw_mesh_tasks`]: crate::graph::pass_ref::Draw::draw_mesh_tasks
+    /// [`PhysicalDevice::mesh_shader_features`]: super::physical_device::PhysicalDevice::mesh_shader_features
     #[profiling::function]
     pub fn create<S>(
         device: &Arc<Device>,
@@ -423,11 +470,17 @@ impl GraphicPipeline {
             .map(|shader| shader.into())
             .collect::<Vec<Shader>>();
 
-        let vertex_input = shaders
+        // Mesh shader pipelines generate primitives without vertex input or input assembly
+        let has_mesh_stage = shaders
             .iter()
-            .find(|shader| shader.stage == vk::ShaderStageFlags::VERTEX)
-            .expect("vertex shader not found")
-            .vertex_input();
+            .any(|shader| shader.stage.contains(vk::ShaderStageFlags::MESH_EXT));
+        let vertex_input = (!has_mesh_stage).then(|| {
+            shaders
+                .iter()
+                .find(|shader| shader.stage == vk::ShaderStageFlags::VERTEX)
+                .expect("vertex or mesh shader not found")
+                .vertex_input()
+        });
 
         // Check for proper stages because vulkan may not complain but this is bad
         let has_fragment_stage = shaders
@@ -450,6 +503,24 @@ impl GraphicPipeline {
             has_fragment_stage || has_tesselation_stage || has_geometry_stage,
             "invalid shader stage combination"
         );
+        debug_assert!(
+            !has_mesh_stage
+                || !shaders.iter().any(|shader| {
+                    shader.stage.intersects(
+                        vk::ShaderStageFlags::VERTEX
+                            | vk::ShaderStageFlags::TESSELLATION_CONTROL
+                            | vk::ShaderStageFlags::TESSELLATION_EVALUATION
+                            | vk::ShaderStageFlags::GEOMETRY,
+                    )
+                }),
+            "mesh shaders may not be combined with vertex processing stages"
+        );
+
+        if has_mesh_stage && device.mesh_shader_ext.is_none() {
+            warn!("mesh shaders are not supported");
+
+            return Err(DriverError::Unsupported);
+        }
 
         let mut descriptor_bindings = Shader::merge_descriptor_bindings(
             shaders
@@ -571,6 +642,7 @@ impl GraphicPipeline {
                 layout,
                 name: None,
                 push_constants,
+                rendering_pipelines: Default::default(),
                 separate_samplers,
                 shader_modules,
                 state: GraphicPipelineState {
@@ -583,7 +655,192 @@ impl GraphicPipeline {
         }
     }
 
+    /// Creates a Vulkan pipeline for use in the given subpass of a render pass, or with dynamic
+    /// rendering when `rendering_formats` is provided (in which case `render_pass` is null).
+    #[profiling::function]
+    pub(super) fn create_vk_pipeline(
+        this: &Self,
+        depth_stencil: Option<DepthStencilMode>,
+        color_attachment_count: usize,
+        render_pass: vk::RenderPass,
+        subpass_idx: u32,
+        rendering_formats: Option<&RenderingFormats>,
+    ) -> Result<vk::Pipeline, DriverError> {
+        use std::slice::from_ref;
+
+        let color_blend_attachment_states = (0..color_attachment_count)
+            .map(|_| this.info.blend.into_vk())
+            .collect::<Box<[_]>>();
+        let color_blend_state = vk::PipelineColorBlendStateCreateInfo::builder()
+            .attachments(&color_blend_attachment_states);
+        let mut dynamic_states = Vec::with_capacity(
+            Self::DYNAMIC_STATES.len() + Self::EXTENDED_DYNAMIC_STATES.len() + 1,
+        );
+        dynamic_states.extend_from_slice(&Self::DYNAMIC_STATES);
+
+        if this.device.extended_dynamic_state_ext.is_some() {
+            dynamic_states.extend_from_slice(&Self::EXTENDED_DYNAMIC_STATES);
+
+            // Mesh shader pipelines have no input assembly state
+            if this.state.vertex_input.is_some() {
+                dynamic_states.push(vk::DynamicState::PRIMITIVE_TOPOLOGY_EXT);
+            }
+        }
+
+        let dynamic_state =
+            vk::PipelineDynamicStateCreateInfo::builder().dynamic_states(&dynamic_states);
+        let multisample_state = vk::PipelineMultisampleStateCreateInfo::builder()
+            .alpha_to_coverage_enable(this.state.multisample.alpha_to_coverage_enable)
+            .alpha_to_one_enable(this.state.multisample.alpha_to_one_enable)
+            .flags(this.state.multisample.flags)
+            .min_sample_shading(this.state.multisample.min_sample_shading)
+            .rasterization_samples(this.state.multisample.rasterization_samples.into_vk())
+            .sample_shading_enable(this.state.multisample.sample_shading_enable)
+            .sample_mask(&this.state.multisample.sample_mask);
+        let mut specializations = Vec::with_capacity(this.state.stages.len());
+        let stages = this
+            .state
+            .stages
+            .iter()
+            .map(|stage| {
+                let mut info = vk::PipelineShaderStageCreateInfo::builder()
+                    .module(stage.module)
+                    .name(&stage.name)
+                    .stage(stage.flags);
+
+                if let Some(specialization_info) = &stage.specialization_info {
+                    specializations.push(
+                        vk::SpecializationInfo::builder()
+                            .map_entries(&specialization_info.map_entries)
+                            .data(&specialization_info.data)
+                            .build(),
+                    );
+
+                    info = info.specialization_info(specializations.last().unwrap());
+                }
+
+                info.build()
+            })
+            .collect::<Box<[_]>>();
+        let vertex_input_state = this.state.vertex_input.as_ref().map(|vertex_input| {
+            vk::PipelineVertexInputStateCreateInfo::builder()
+                .vertex_attribute_descriptions(&vertex_input.vertex_attribute_descriptions)
+                .vertex_binding_descriptions(&vertex_input.vertex_binding_descriptions)
+        });
+        let viewport_state = vk::PipelineViewportStateCreateInfo::builder()
+            .viewport_count(1)
+            .scissor_count(1);
+        let input_assembly_state = vk::PipelineInputAssemblyStateCreateInfo {
+            topology: this.info.topology,
+            ..Default::default()
+        };
+        let depth_stencil = depth_stencil
+            .map(|depth_stencil| depth_stencil.into_vk())
+            .unwrap_or_default();
+        let rasterization_state = vk::PipelineRasterizationStateCreateInfo {
+            depth_bias_enable: this.info.depth_bias as _,
+            front_face: this.info.front_face,
+            line_width: 1.0,
+            polygon_mode: this.info.polygon_mode,
+            cull_mode: this.info.cull_mode,
+            ..Default::default()
+        };
+        let mut rendering_info = rendering_formats.map(|rendering_formats| {
+            vk::PipelineRenderingCreateInfo::builder()
+                .color_attachment_formats(&rendering_formats.color_formats)
+                .depth_attachment_format(rendering_formats.depth_format)
+                .stencil_attachment_format(rendering_formats.stencil_format)
+                .view_mask(rendering_formats.view_mask)
+        });
+        let mut graphic_pipeline_info = vk::GraphicsPipelineCreateInfo::builder()
+            .color_blend_state(&color_blend_state)
+            .depth_stencil_state(&depth_stencil)
+            .dynamic_state(&dynamic_state)
+            .layout(this.state.layout)
+            .multisample_state(&multisample_state)
+            .rasterization_state(&rasterization_state)
+            .render_pass(render_pass)
+            .stages(&stages)
+            .subpass(subpass_idx)
+            .viewport_state(&viewport_state);
+
+        if let Some(vertex_input_state) = vertex_input_state.as_ref() {
+            graphic_pipeline_info = graphic_pipeline_info
+                .input_assembly_state(&input_assembly_state)
+                .vertex_input_state(vertex_input_state);
+        }
+
+        if let Some(rendering_info) = rendering_info.as_mut() {
+            graphic_pipeline_info = graphic_pipeline_info.push_next(rendering_info);
+        }
+
+        let name = this.name.as_deref();
+
+        let pipeline = unsafe {
+            this.device.create_graphics_pipelines(
+                vk::PipelineCache::null(),
+                from_ref(&graphic_pipeline_info),
+                None,
+            )
+        }
+        .map_err(|(_, err)| {
+            warn!(
+                "create_graphics_pipelines: {err}\n{:#?}",
+                graphic_pipeline_info.build()
+            );
+
+            DriverError::Unsupported
+        })?[0];
+
+        if let Some(name) = name {
+            Device::set_debug_name(&this.device, pipeline, name);
+        }
+
+        Ok(pipeline)
+    }
+
+    /// Returns `true` if this pipeline uses vertex input and input assembly state, which mesh
+    /// shader pipelines do not.
+    pub(crate) fn has_vertex_input(this: &Self) -> bool {
+        this.state.vertex_input.is_some()
+    }
+
+    /// Returns a Vulkan pipeline for use with dynamic rendering using the given attachment formats,
+    /// creating it if required.
+    #[profiling::function]
+    pub(crate) fn rendering_pipeline(
+        this: &Self,
+        depth_stencil: Option<DepthStencilMode>,
+        rendering_formats: &RenderingFormats,
+    ) -> Result<vk::Pipeline, DriverError> {
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let mut rendering_pipelines = this.rendering_pipelines.lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let mut rendering_pipelines = rendering_pipelines.unwrap();
+
+        let key = (depth_stencil, rendering_formats.clone());
+        if let Some(pipeline) = rendering_pipelines.get(&key) {
+            return Ok(*pipeline);
+        }
+
+        let pipeline = Self::create_vk_pipeline(
+            this,
+            depth_stencil,
+            rendering_formats.color_formats.len(),
+            vk::RenderPass::null(),
+            0,
+            Some(rendering_formats),
+        )?;
+        rendering_pipelines.insert(key, pipeline);
+
+        Ok(pipeline)
+    }
+
     /// Sets the debugging name assigned to this pipeline.
+    ///
+    /// The name is given to each Vulkan pipeline object as it is created for a render pass or for
+    /// dynamic rendering.
     pub fn with_name(mut this: Self, name: impl Into<String>) -> Self {
         this.name = Some(name.into());
         this
@@ -597,6 +854,20 @@ impl Drop for GraphicPipeline {
             return;
         }
 
+        {
+            #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+            let mut rendering_pipelines = self.rendering_pipelines.lock();
+
+            #[cfg(not(feature = "parking_lot"))]
+            let mut rendering_pipelines = rendering_pipelines.unwrap();
+
+            for (_, pipeline) in rendering_pipelines.drain() {
+                unsafe {
+                    self.device.destroy_pipeline(pipeline, None);
+                }
+            }
+        }
+
         unsafe {
             self.device.destroy_pipeline_layout(self.layout, None);
         }
@@ -660,6 +931,15 @@ pub struct GraphicPipelineInfo {
     #[builder(default = "vk::CullModeFlags::BACK")]
     pub cull_mode: vk::CullModeFlags,
 
+    /// Controls whether to bias fragment depth values.
+    ///
+    /// The bias factors are zero when the pipeline is bound and may be set using
+    /// [`Draw::set_depth_bias`](crate::graph::pass_ref::Draw::set_depth_bias).
+    ///
+    /// The default value is `false`.
+    #[builder(default)]
+    pub depth_bias: bool,
+
     /// Interpret polygon front-facing orientation.
     ///
     /// The default value is `vk::FrontFace::COUNTER_CLOCKWISE`.
@@ -703,6 +983,7 @@ impl GraphicPipelineInfo {
             bindless_descriptor_count: Some(self.bindless_descriptor_count),
             blend: Some(self.blend),
             cull_mode: Some(self.cull_mode),
+            depth_bias: Some(self.depth_bias),
             front_face: Some(self.front_face),
             polygon_mode: Some(self.polygon_mode),
             topology: Some(self.topology),
@@ -717,6 +998,7 @@ impl Default for GraphicPipelineInfo {
             bindless_descriptor_count: 8192,
             blend: BlendMode::REPLACE,
             cull_mode: vk::CullModeFlags::BACK,
+            depth_bias: false,
             front_face: vk::FrontFace::COUNTER_CLOCKWISE,
             polygon_mode: vk::PolygonMode::FILL,
             topology: vk::PrimitiveTopology::TRIANGLE_LIST,
@@ -761,7 +1043,7 @@ pub(super) struct GraphicPipelineState {
     pub layout: vk::PipelineLayout,
     pub multisample: MultisampleState,
     pub stages: Vec<Stage>,
-    pub vertex_input: VertexInputState,
+    pub vertex_input: Option<VertexInputState>,
 }
 
 #[derive(Debug, Default)]
@@ -783,6 +1065,15 @@ pub(super) struct Stage {
     pub specialization_info: Option<SpecializationInfo>,
 }
 
+/// Attachment formats of a graphic pipeline which is used with dynamic rendering.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub(crate) struct RenderingFormats {
+    pub color_formats: Vec<vk::Format>,
+    pub depth_format: vk::Format,
+    pub stencil_format: vk::Format,
+    pub view_mask: u32,
+}
+
 /// Specifies stencil mode during rasterization.
 ///
 /// See
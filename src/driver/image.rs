@@ -3,6 +3,7 @@
 use {
     super::{
         access_type_from_u8, access_type_into_u8, device::Device, format_aspect_mask, DriverError,
+        MemoryBlock,
     },
     ash::vk,
     derive_builder::{Builder, UninitializedFieldError},
@@ -12,13 +13,17 @@ use {
     },
     log::{trace, warn},
     std::{
-        collections::{hash_map::Entry, HashMap},
+        collections::{
+            hash_map::{DefaultHasher, Entry},
+            HashMap,
+        },
         fmt::{Debug, Formatter},
+        hash::{Hash, Hasher},
         mem::take,
         ops::Deref,
         ptr::null,
         sync::{
-            atomic::{AtomicU8, Ordering},
+            atomic::{AtomicU64, AtomicU8, Ordering},
             Arc,
         },
         thread::panicking,
@@ -70,9 +75,17 @@ pub struct Image {
     /// Information used to create this object.
     pub info: ImageInfo,
 
+    memory_block: Option<Arc<MemoryBlock>>, // Some when the memory is shared with other images
+
     /// A name for debugging purposes.
+    ///
+    /// The name is given to the Vulkan image object when debug layers are enabled and the image
+    /// is used by a render graph, or by [`Self::with_name`].
     pub name: Option<String>,
 
+    /// A hash of the name which was last given to the Vulkan object, or zero.
+    named: AtomicU64,
+
     prev_access: AtomicU8,
 }
 
@@ -106,23 +119,8 @@ impl Image {
         //trace!("create: {:?}", &info);
         trace!("create");
 
-        assert!(
-            !info.usage.is_empty(),
-            "Unspecified image usage {:?}",
-            info.usage
-        );
-
         let device = Arc::clone(device);
-        let create_info = info
-            .image_create_info()
-            .queue_family_indices(&device.physical_device.queue_family_indices);
-        let image = unsafe {
-            device.create_image(&create_info, None).map_err(|err| {
-                warn!("{err}");
-
-                DriverError::Unsupported
-            })?
-        };
+        let image = Self::create_unbound(&device, info)?;
         let requirements = unsafe { device.get_image_memory_requirements(image) };
         let allocation = {
             profiling::scope!("allocate");
@@ -164,11 +162,77 @@ impl Image {
             image,
             image_view_cache: Mutex::new(Default::default()),
             info,
+            memory_block: None,
             name: None,
+            named: AtomicU64::new(0),
             prev_access: AtomicU8::new(access_type_into_u8(AccessType::Nothing)),
         })
     }
 
+    /// Creates a new image on the given device which is bound to `memory_block` at `offset`.
+    ///
+    /// The memory is not owned by the image and may be shared with other resources.
+    #[profiling::function]
+    pub(crate) fn create_aliased(
+        device: &Arc<Device>,
+        info: ImageInfo,
+        memory_block: &Arc<MemoryBlock>,
+        offset: vk::DeviceSize,
+    ) -> Result<Self, DriverError> {
+        trace!("create_aliased");
+
+        let device = Arc::clone(device);
+        let image = Self::create_unbound(&device, info)?;
+
+        unsafe {
+            device
+                .bind_image_memory(
+                    image,
+                    MemoryBlock::memory(memory_block),
+                    MemoryBlock::offset(memory_block) + offset,
+                )
+                .map_err(|err| {
+                    warn!("{err}");
+
+                    device.destroy_image(image, None);
+
+                    DriverError::Unsupported
+                })?;
+        }
+
+        Ok(Self {
+            allocation: None,
+            device,
+            image,
+            image_view_cache: Mutex::new(Default::default()),
+            info,
+            memory_block: Some(Arc::clone(memory_block)),
+            name: None,
+            named: AtomicU64::new(0),
+            prev_access: AtomicU8::new(access_type_into_u8(AccessType::Nothing)),
+        })
+    }
+
+    fn create_unbound(device: &Device, info: ImageInfo) -> Result<vk::Image, DriverError> {
+        assert!(
+            !info.usage.is_empty(),
+            "Unspecified image usage {:?}",
+            info.usage
+        );
+
+        let create_info = info
+            .image_create_info()
+            .queue_family_indices(&device.physical_device.queue_family_indices);
+
+        unsafe {
+            device.create_image(&create_info, None).map_err(|err| {
+                warn!("{err}");
+
+                DriverError::Unsupported
+            })
+        }
+    }
+
     /// Keeps track of some `next_access` which affects this object.
     ///
     /// Returns the previous access for which a pipeline barrier should be used to prevent data
@@ -219,6 +283,11 @@ impl Image {
         )
     }
 
+    /// Returns the most recent access of this object without modifying it.
+    pub(crate) fn peek_access(this: &Self) -> AccessType {
+        access_type_from_u8(this.prev_access.load(Ordering::Relaxed))
+    }
+
     #[profiling::function]
     pub(super) fn clone_raw(this: &Self) -> Self {
         // Moves the image view cache from the current instance to the clone!
@@ -237,41 +306,47 @@ impl Image {
             image,
             image_view_cache: Mutex::new(image_view_cache),
             info,
+            memory_block: None,
             name: this.name.clone(),
+            named: AtomicU64::new(0),
             prev_access: AtomicU8::new(access_type_into_u8(AccessType::Nothing)),
         }
     }
 
     #[profiling::function]
     fn drop_allocation(this: &Self, allocation: Allocation) {
+        Self::drop_image(this);
+
         {
-            profiling::scope!("views");
+            profiling::scope!("deallocate");
 
             #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
-            let mut image_view_cache = this.image_view_cache.lock();
+            let mut allocator = this.device.allocator.lock();
 
             #[cfg(not(feature = "parking_lot"))]
-            let mut image_view_cache = image_view_cache.unwrap();
-
-            image_view_cache.clear();
-        }
+            let mut allocator = allocator.unwrap();
 
-        unsafe {
-            this.device.destroy_image(this.image, None);
+            allocator.free(allocation)
         }
+        .unwrap_or_else(|_| warn!("Unable to free image allocation"));
+    }
 
+    fn drop_image(this: &Self) {
         {
-            profiling::scope!("deallocate");
+            profiling::scope!("views");
 
             #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
-            let mut allocator = this.device.allocator.lock();
+            let mut image_view_cache = this.image_view_cache.lock();
 
             #[cfg(not(feature = "parking_lot"))]
-            let mut allocator = allocator.unwrap();
+            let mut image_view_cache = image_view_cache.unwrap();
 
-            allocator.free(allocation)
+            image_view_cache.clear();
+        }
+
+        unsafe {
+            this.device.destroy_image(this.image, None);
         }
-        .unwrap_or_else(|_| warn!("Unable to free image allocation"));
     }
 
     /// Consumes a Vulkan image created by some other library.
@@ -289,7 +364,9 @@ impl Image {
             image,
             image_view_cache: Mutex::new(Default::default()),
             info,
+            memory_block: None,
             name: None,
+            named: AtomicU64::new(0),
             prev_access: AtomicU8::new(access_type_into_u8(AccessType::Nothing)),
         }
     }
@@ -311,6 +388,48 @@ impl Image {
             }
         })
     }
+
+    /// Returns the memory requirements of an image created using the given information.
+    #[profiling::function]
+    pub(crate) fn memory_requirements(
+        device: &Device,
+        info: ImageInfo,
+    ) -> Result<vk::MemoryRequirements, DriverError> {
+        // Vulkan 1.2 requires an image object in order to query the requirements
+        let image = Self::create_unbound(device, info)?;
+
+        unsafe {
+            let requirements = device.get_image_memory_requirements(image);
+            device.destroy_image(image, None);
+
+            Ok(requirements)
+        }
+    }
+
+    /// Gives the name of this image to the Vulkan object if it has changed since it was last given.
+    pub(crate) fn name_object(this: &Self) {
+        let Some(name) = &this.name else {
+            return;
+        };
+
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        let hash = hasher.finish().max(1);
+
+        if this.named.swap(hash, Ordering::Relaxed) != hash {
+            Device::set_debug_name(&this.device, this.image, name);
+        }
+    }
+
+    /// Sets the debugging name assigned to this image.
+    ///
+    /// The name is also given to the Vulkan image object when debug layers are enabled, so that
+    /// it appears in validation messages and graphics debugging tools.
+    pub fn with_name(mut this: Self, name: impl Into<String>) -> Self {
+        this.name = Some(name.into());
+        Self::name_object(&this);
+        this
+    }
 }
 
 impl Debug for Image {
@@ -338,10 +457,13 @@ impl Drop for Image {
             return;
         }
 
-        // When our allocation is some we allocated ourself; otherwise somebody
-        // else owns this image and we should not destroy it. Usually it's the swapchain...
+        // When our allocation is some we allocated ourself and when our memory block is some we
+        // created the image using shared memory; otherwise somebody else owns this image and we
+        // should not destroy it. Usually it's the swapchain...
         if let Some(allocation) = self.allocation.take() {
             Self::drop_allocation(self, allocation);
+        } else if self.memory_block.is_some() {
+            Self::drop_image(self);
         }
     }
 }
@@ -544,6 +666,21 @@ impl ImageInfo {
         self.into()
     }
 
+    /// Returns the number of array layers of an image created using this information.
+    pub(crate) fn array_layer_count(self) -> u32 {
+        match self.ty {
+            ImageType::Texture1D | ImageType::Texture3D => 1,
+            ImageType::Texture2D if !self.flags.contains(vk::ImageCreateFlags::CUBE_COMPATIBLE) => {
+                1
+            }
+            ImageType::Texture2D | ImageType::TextureArray1D | ImageType::TextureArray2D => {
+                self.array_elements
+            }
+            ImageType::Cube => 6,
+            ImageType::CubeArray => 6 * self.array_elements,
+        }
+    }
+
     fn image_create_info<'a>(self) -> vk::ImageCreateInfoBuilder<'a> {
         let (ty, extent, array_layers) = match self.ty {
             ImageType::Texture1D => (
@@ -685,7 +822,7 @@ impl From<UninitializedFieldError> for ImageInfoBuilderError {
 }
 
 /// Describes a subset of an image.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct ImageSubresource {
     /// The number of layers for which this subset applies.
     ///
@@ -1028,7 +1165,7 @@ pub enum SampleCount {
 }
 
 impl SampleCount {
-    pub(super) fn into_vk(self) -> vk::SampleCountFlags {
+    pub(crate) fn into_vk(self) -> vk::SampleCountFlags {
         #[allow(deprecated)]
         match self {
             Self::Type1 | Self::X1 => vk::SampleCountFlags::TYPE_1,
@@ -211,6 +211,12 @@ impl Instance {
         })
     }
 
+    /// Returns the `VK_EXT_debug_utils` loader if this instance was created with debug layers
+    /// enabled.
+    pub(crate) fn debug_utils(this: &Self) -> Option<&ext::DebugUtils> {
+        this.debug_utils.as_ref()
+    }
+
     /// Returns the `ash` entrypoint for Vulkan functions.
     pub fn entry(this: &Self) -> &Entry {
         &this.entry
@@ -0,0 +1,106 @@
+//! Shared device memory types.
+
+use {
+    super::{device::Device, DriverError},
+    ash::vk,
+    gpu_allocator::{
+        vulkan::{Allocation, AllocationCreateDesc, AllocationScheme},
+        MemoryLocation,
+    },
+    log::{trace, warn},
+    std::{
+        fmt::{Debug, Formatter},
+        mem::ManuallyDrop,
+        sync::Arc,
+        thread::panicking,
+    },
+};
+
+/// A block of device-local memory which is bound to more than one resource.
+///
+/// Resources which are bound to the same range of a memory block alias each other and must not be
+/// used at the same time. Each resource keeps the block alive until it has been dropped.
+pub(crate) struct MemoryBlock {
+    allocation: ManuallyDrop<Allocation>,
+    device: Arc<Device>,
+}
+
+impl MemoryBlock {
+    /// Allocates a new block of memory which satisfies the given requirements.
+    ///
+    /// Set `linear` when the block will only be bound to buffers or linearly tiled images.
+    #[profiling::function]
+    pub(crate) fn create(
+        device: &Arc<Device>,
+        requirements: vk::MemoryRequirements,
+        linear: bool,
+    ) -> Result<Self, DriverError> {
+        trace!("create: {:?}", requirements);
+
+        let device = Arc::clone(device);
+        let allocation = {
+            profiling::scope!("allocate");
+
+            #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+            let mut allocator = device.allocator.lock();
+
+            #[cfg(not(feature = "parking_lot"))]
+            let mut allocator = allocator.unwrap();
+
+            allocator
+                .allocate(&AllocationCreateDesc {
+                    name: "memory block",
+                    requirements,
+                    location: MemoryLocation::GpuOnly,
+                    linear,
+                    allocation_scheme: AllocationScheme::GpuAllocatorManaged,
+                })
+                .map_err(|err| {
+                    warn!("{err}");
+
+                    DriverError::Unsupported
+                })
+        }?;
+
+        Ok(Self {
+            allocation: ManuallyDrop::new(allocation),
+            device,
+        })
+    }
+
+    /// Returns the Vulkan memory object which contains this block.
+    pub(crate) fn memory(this: &Self) -> vk::DeviceMemory {
+        unsafe { this.allocation.memory() }
+    }
+
+    /// Returns the offset of this block within its Vulkan memory object.
+    pub(crate) fn offset(this: &Self) -> vk::DeviceSize {
+        this.allocation.offset()
+    }
+}
+
+impl Debug for MemoryBlock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MemoryBlock ({} bytes)", self.allocation.size())
+    }
+}
+
+impl Drop for MemoryBlock {
+    #[profiling::function]
+    fn drop(&mut self) {
+        if panicking() {
+            return;
+        }
+
+        {
+            #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+            let mut allocator = self.device.allocator.lock();
+
+            #[cfg(not(feature = "parking_lot"))]
+            let mut allocator = allocator.unwrap();
+
+            allocator.free(unsafe { ManuallyDrop::take(&mut self.allocation) })
+        }
+        .unwrap_or_else(|_| warn!("Unable to free memory block allocation"));
+    }
+}
@@ -20,6 +20,50 @@ fn vk_cstr_to_string_lossy(cstr: &[i8]) -> String {
         .to_string()
 }
 
+/// Features of the physical device for acceleration structures.
+///
+/// See
+/// [`VkPhysicalDeviceAccelerationStructureFeaturesKHR`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceAccelerationStructureFeaturesKHR.html)
+/// manual page.
+#[derive(Debug, Default)]
+pub struct AccelerationStructureFeatures {
+    /// Indicates whether the implementation supports the acceleration structure functionality.
+    pub acceleration_structure: bool,
+
+    /// Indicates whether the implementation supports saving and reusing acceleration structure
+    /// device addresses, e.g. for trace capture and replay.
+    pub acceleration_structure_capture_replay: bool,
+
+    /// Indicates whether the implementation supports host side acceleration structure commands,
+    /// such as [`AccelerationStructure::build_host`](super::accel_struct::AccelerationStructure::build_host).
+    pub acceleration_structure_host_commands: bool,
+
+    /// Indicates whether the implementation supports indirect acceleration structure build
+    /// commands.
+    pub acceleration_structure_indirect_build: bool,
+
+    /// Indicates whether the implementation supports updating acceleration structure descriptors
+    /// after a set is bound.
+    pub descriptor_binding_acceleration_structure_update_after_bind: bool,
+}
+
+impl From<vk::PhysicalDeviceAccelerationStructureFeaturesKHR> for AccelerationStructureFeatures {
+    fn from(features: vk::PhysicalDeviceAccelerationStructureFeaturesKHR) -> Self {
+        Self {
+            acceleration_structure: features.acceleration_structure == vk::TRUE,
+            acceleration_structure_capture_replay: features.acceleration_structure_capture_replay
+                == vk::TRUE,
+            acceleration_structure_host_commands: features.acceleration_structure_host_commands
+                == vk::TRUE,
+            acceleration_structure_indirect_build: features.acceleration_structure_indirect_build
+                == vk::TRUE,
+            descriptor_binding_acceleration_structure_update_after_bind: features
+                .descriptor_binding_acceleration_structure_update_after_bind
+                == vk::TRUE,
+        }
+    }
+}
+
 /// Properties of the physical device for acceleration structures.
 ///
 /// See
@@ -118,6 +162,71 @@ impl From<vk::PhysicalDeviceDepthStencilResolveProperties> for DepthStencilResol
     }
 }
 
+/// Features of the physical device for predicating draw and dispatch commands on the contents of a
+/// buffer.
+///
+/// See
+/// [`VkPhysicalDeviceConditionalRenderingFeaturesEXT`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceConditionalRenderingFeaturesEXT.html)
+/// manual page.
+#[derive(Debug, Default)]
+pub struct ConditionalRenderingFeatures {
+    /// Indicates that the implementation supports conditional rendering.
+    pub conditional_rendering: bool,
+
+    /// Indicates that secondary command buffers may be executed while conditional rendering is
+    /// active.
+    pub inherited_conditional_rendering: bool,
+}
+
+impl From<vk::PhysicalDeviceConditionalRenderingFeaturesEXT> for ConditionalRenderingFeatures {
+    fn from(features: vk::PhysicalDeviceConditionalRenderingFeaturesEXT) -> Self {
+        Self {
+            conditional_rendering: features.conditional_rendering == vk::TRUE,
+            inherited_conditional_rendering: features.inherited_conditional_rendering == vk::TRUE,
+        }
+    }
+}
+
+/// Features of the physical device for rendering without render pass objects.
+///
+/// See
+/// [`VkPhysicalDeviceDynamicRenderingFeatures`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceDynamicRenderingFeatures.html)
+/// manual page.
+#[derive(Debug, Default)]
+pub struct DynamicRenderingFeatures {
+    /// Indicates that the implementation supports dynamic render pass instances using the
+    /// `vkCmdBeginRendering` command.
+    pub dynamic_rendering: bool,
+}
+
+impl From<vk::PhysicalDeviceDynamicRenderingFeatures> for DynamicRenderingFeatures {
+    fn from(features: vk::PhysicalDeviceDynamicRenderingFeatures) -> Self {
+        Self {
+            dynamic_rendering: features.dynamic_rendering == vk::TRUE,
+        }
+    }
+}
+
+/// Features of the physical device for setting additional pipeline state dynamically.
+///
+/// See
+/// [`VkPhysicalDeviceExtendedDynamicStateFeaturesEXT`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceExtendedDynamicStateFeaturesEXT.html)
+/// manual page.
+#[derive(Debug, Default)]
+pub struct ExtendedDynamicStateFeatures {
+    /// Indicates that the implementation supports setting the cull mode, front face, primitive
+    /// topology, depth test and stencil test state of graphic pipelines dynamically.
+    pub extended_dynamic_state: bool,
+}
+
+impl From<vk::PhysicalDeviceExtendedDynamicStateFeaturesEXT> for ExtendedDynamicStateFeatures {
+    fn from(features: vk::PhysicalDeviceExtendedDynamicStateFeaturesEXT) -> Self {
+        Self {
+            extended_dynamic_state: features.extended_dynamic_state == vk::TRUE,
+        }
+    }
+}
+
 /// Features of the physical device for vertex indexing.
 ///
 /// See
@@ -138,15 +247,212 @@ impl From<vk::PhysicalDeviceIndexTypeUint8FeaturesEXT> for IndexTypeUint8Feature
     }
 }
 
+/// Features of the physical device for mesh and task shaders.
+///
+/// See
+/// [`VkPhysicalDeviceMeshShaderFeaturesEXT`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceMeshShaderFeaturesEXT.html)
+/// manual page.
+#[derive(Debug, Default)]
+pub struct MeshShaderFeatures {
+    /// Indicates whether the task shader stage is supported.
+    pub task_shader: bool,
+
+    /// Indicates whether the mesh shader stage is supported.
+    pub mesh_shader: bool,
+
+    /// Indicates whether the implementation supports a view mask other than zero in render passes
+    /// which use mesh shader pipelines.
+    pub multiview_mesh_shader: bool,
+
+    /// Indicates whether the implementation supports the `PrimitiveShadingRateKHR` built-in in
+    /// mesh shaders.
+    pub primitive_fragment_shading_rate_mesh_shader: bool,
+
+    /// Indicates whether the implementation supports the mesh primitives generated query and the
+    /// task and mesh shader invocation pipeline statistics.
+    pub mesh_shader_queries: bool,
+}
+
+impl From<vk::PhysicalDeviceMeshShaderFeaturesEXT> for MeshShaderFeatures {
+    fn from(features: vk::PhysicalDeviceMeshShaderFeaturesEXT) -> Self {
+        Self {
+            task_shader: features.task_shader == vk::TRUE,
+            mesh_shader: features.mesh_shader == vk::TRUE,
+            multiview_mesh_shader: features.multiview_mesh_shader == vk::TRUE,
+            primitive_fragment_shading_rate_mesh_shader: features
+                .primitive_fragment_shading_rate_mesh_shader
+                == vk::TRUE,
+            mesh_shader_queries: features.mesh_shader_queries == vk::TRUE,
+        }
+    }
+}
+
+/// Properties of the physical device for mesh and task shaders.
+///
+/// See
+/// [`VkPhysicalDeviceMeshShaderPropertiesEXT`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceMeshShaderPropertiesEXT.html)
+/// manual page.
+#[derive(Debug)]
+pub struct MeshShaderProperties {
+    /// The maximum total number of task shader workgroups which may be launched by a single mesh
+    /// tasks drawing command.
+    pub max_task_work_group_total_count: u32,
+
+    /// The maximum number of task shader workgroups which may be launched by a single mesh tasks
+    /// drawing command, in each dimension.
+    pub max_task_work_group_count: [u32; 3],
+
+    /// The maximum total number of task shader invocations in a single local workgroup.
+    pub max_task_work_group_invocations: u32,
+
+    /// The maximum size of a local task workgroup, in each dimension.
+    pub max_task_work_group_size: [u32; 3],
+
+    /// The maximum number of bytes that the task shader may use for its payload.
+    pub max_task_payload_size: u32,
+
+    /// The maximum number of bytes that the task shader may use for shared memory.
+    pub max_task_shared_memory_size: u32,
+
+    /// The maximum number of bytes that the task shader may use for its payload and shared memory
+    /// combined.
+    pub max_task_payload_and_shared_memory_size: u32,
+
+    /// The maximum total number of mesh shader workgroups which may be launched by a single mesh
+    /// tasks drawing command.
+    pub max_mesh_work_group_total_count: u32,
+
+    /// The maximum number of mesh shader workgroups which may be launched by a single mesh tasks
+    /// drawing command, in each dimension.
+    pub max_mesh_work_group_count: [u32; 3],
+
+    /// The maximum total number of mesh shader invocations in a single local workgroup.
+    pub max_mesh_work_group_invocations: u32,
+
+    /// The maximum size of a local mesh workgroup, in each dimension.
+    pub max_mesh_work_group_size: [u32; 3],
+
+    /// The maximum number of bytes that the mesh shader may use for shared memory.
+    pub max_mesh_shared_memory_size: u32,
+
+    /// The maximum number of bytes that the mesh shader may use for the task payload and shared
+    /// memory combined.
+    pub max_mesh_payload_and_shared_memory_size: u32,
+
+    /// The maximum number of bytes that the mesh shader may use for output.
+    pub max_mesh_output_memory_size: u32,
+
+    /// The maximum number of bytes that the mesh shader may use for the task payload and output
+    /// combined.
+    pub max_mesh_payload_and_output_memory_size: u32,
+
+    /// The maximum number of components of output variables which may be output from the mesh
+    /// shader.
+    pub max_mesh_output_components: u32,
+
+    /// The maximum number of vertices which may be emitted by a single mesh shader workgroup.
+    pub max_mesh_output_vertices: u32,
+
+    /// The maximum number of primitives which may be emitted by a single mesh shader workgroup.
+    pub max_mesh_output_primitives: u32,
+
+    /// One greater than the maximum layer index that may be output by the mesh shader.
+    pub max_mesh_output_layers: u32,
+
+    /// The maximum number of views which may be used by a mesh shader.
+    pub max_mesh_multiview_view_count: u32,
+
+    /// The granularity in bytes of per-vertex mesh shader output allocations.
+    pub mesh_output_per_vertex_granularity: u32,
+
+    /// The granularity in bytes of per-primitive mesh shader output allocations.
+    pub mesh_output_per_primitive_granularity: u32,
+
+    /// The maximum number of task shader invocations in a workgroup which the implementation
+    /// prefers for optimal performance.
+    pub max_preferred_task_work_group_invocations: u32,
+
+    /// The maximum number of mesh shader invocations in a workgroup which the implementation
+    /// prefers for optimal performance.
+    pub max_preferred_mesh_work_group_invocations: u32,
+
+    /// Indicates that the implementation prefers each vertex to be written by the invocation whose
+    /// local invocation index matches the vertex index.
+    pub prefers_local_invocation_vertex_output: bool,
+
+    /// Indicates that the implementation prefers each primitive to be written by the invocation
+    /// whose local invocation index matches the primitive index.
+    pub prefers_local_invocation_primitive_output: bool,
+
+    /// Indicates that the implementation prefers that unused vertices are not output.
+    pub prefers_compact_vertex_output: bool,
+
+    /// Indicates that the implementation prefers that unused primitives are not output.
+    pub prefers_compact_primitive_output: bool,
+}
+
+impl From<vk::PhysicalDeviceMeshShaderPropertiesEXT> for MeshShaderProperties {
+    fn from(props: vk::PhysicalDeviceMeshShaderPropertiesEXT) -> Self {
+        Self {
+            max_task_work_group_total_count: props.max_task_work_group_total_count,
+            max_task_work_group_count: props.max_task_work_group_count,
+            max_task_work_group_invocations: props.max_task_work_group_invocations,
+            max_task_work_group_size: props.max_task_work_group_size,
+            max_task_payload_size: props.max_task_payload_size,
+            max_task_shared_memory_size: props.max_task_shared_memory_size,
+            max_task_payload_and_shared_memory_size: props.max_task_payload_and_shared_memory_size,
+            max_mesh_work_group_total_count: props.max_mesh_work_group_total_count,
+            max_mesh_work_group_count: props.max_mesh_work_group_count,
+            max_mesh_work_group_invocations: props.max_mesh_work_group_invocations,
+            max_mesh_work_group_size: props.max_mesh_work_group_size,
+            max_mesh_shared_memory_size: props.max_mesh_shared_memory_size,
+            max_mesh_payload_and_shared_memory_size: props.max_mesh_payload_and_shared_memory_size,
+            max_mesh_output_memory_size: props.max_mesh_output_memory_size,
+            max_mesh_payload_and_output_memory_size: props.max_mesh_payload_and_output_memory_size,
+            max_mesh_output_components: props.max_mesh_output_components,
+            max_mesh_output_vertices: props.max_mesh_output_vertices,
+            max_mesh_output_primitives: props.max_mesh_output_primitives,
+            max_mesh_output_layers: props.max_mesh_output_layers,
+            max_mesh_multiview_view_count: props.max_mesh_multiview_view_count,
+            mesh_output_per_vertex_granularity: props.mesh_output_per_vertex_granularity,
+            mesh_output_per_primitive_granularity: props.mesh_output_per_primitive_granularity,
+            max_preferred_task_work_group_invocations: props
+                .max_preferred_task_work_group_invocations,
+            max_preferred_mesh_work_group_invocations: props
+                .max_preferred_mesh_work_group_invocations,
+            prefers_local_invocation_vertex_output: props.prefers_local_invocation_vertex_output
+                == vk::TRUE,
+            prefers_local_invocation_primitive_output: props
+                .prefers_local_invocation_primitive_output
+                == vk::TRUE,
+            prefers_compact_vertex_output: props.prefers_compact_vertex_output == vk::TRUE,
+            prefers_compact_primitive_output: props.prefers_compact_primitive_output == vk::TRUE,
+        }
+    }
+}
+
 /// Structure which holds data about the physical hardware selected by the current device.
 pub struct PhysicalDevice {
+    /// Describes the features of the device which relate to acceleration structures, if
+    /// available.
+    pub accel_struct_features: AccelerationStructureFeatures,
+
     /// Describes the properties of the device which relate to acceleration structures, if
     /// available.
     pub accel_struct_properties: Option<AccelerationStructureProperties>,
 
+    /// Describes the features of the device which relate to conditional rendering, if available.
+    pub conditional_rendering_features: ConditionalRenderingFeatures,
+
     /// Describes the properties of the device which relate to depth/stencil resolve operations.
     pub depth_stencil_resolve_properties: DepthStencilResolveProperties,
 
+    /// Describes the features of the device which relate to dynamic rendering, if available.
+    pub dynamic_rendering_features: DynamicRenderingFeatures,
+
+    /// Describes the features of the device which relate to extended dynamic state, if available.
+    pub extended_dynamic_state_features: ExtendedDynamicStateFeatures,
+
     /// Describes the features of the physical device which are part of the Vulkan 1.0 base feature set.
     pub features_v1_0: Vulkan10Features,
 
@@ -162,6 +468,13 @@ pub struct PhysicalDevice {
     /// Memory properties of the physical device.
     pub memory_properties: vk::PhysicalDeviceMemoryProperties,
 
+    /// Describes the features of the device which relate to mesh and task shaders, if available.
+    pub mesh_shader_features: MeshShaderFeatures,
+
+    /// Describes the properties of the device which relate to mesh and task shaders, if
+    /// available.
+    pub mesh_shader_properties: Option<MeshShaderProperties>,
+
     /// Device properties of the physical device which are part of the Vulkan 1.0 base feature set.
     pub properties_v1_0: Vulkan10Properties,
 
@@ -175,6 +488,9 @@ pub struct PhysicalDevice {
 
     physical_device: vk::PhysicalDevice,
 
+    /// Describes the properties of the device which relate to push descriptors, if available.
+    pub push_descriptor_properties: Option<PushDescriptorProperties>,
+
     /// Describes the queues offered by this physical device.
     pub queue_families: Box<[vk::QueueFamilyProperties]>,
 
@@ -191,6 +507,10 @@ pub struct PhysicalDevice {
 
     /// Describes the properties of the device which relate to min/max sampler filtering.
     pub sampler_filter_minmax_properties: SamplerFilterMinmaxProperties,
+
+    /// Describes the features of the device which relate to synchronization commands, if
+    /// available.
+    pub synchronization2_features: Synchronization2Features,
 }
 
 impl PhysicalDevice {
@@ -230,16 +550,28 @@ impl PhysicalDevice {
         let mut features_v1_2 = vk::PhysicalDeviceVulkan12Features::default();
         let mut acceleration_structure_features =
             vk::PhysicalDeviceAccelerationStructureFeaturesKHR::default();
+        let mut conditional_rendering_features =
+            vk::PhysicalDeviceConditionalRenderingFeaturesEXT::default();
+        let mut dynamic_rendering_features = vk::PhysicalDeviceDynamicRenderingFeatures::default();
+        let mut extended_dynamic_state_features =
+            vk::PhysicalDeviceExtendedDynamicStateFeaturesEXT::default();
         let mut index_type_u8_features = vk::PhysicalDeviceIndexTypeUint8FeaturesEXT::default();
+        let mut mesh_shader_features = vk::PhysicalDeviceMeshShaderFeaturesEXT::default();
         let mut ray_query_features = vk::PhysicalDeviceRayQueryFeaturesKHR::default();
         let mut ray_trace_features = vk::PhysicalDeviceRayTracingPipelineFeaturesKHR::default();
+        let mut synchronization2_features = vk::PhysicalDeviceSynchronization2Features::default();
         let mut features = vk::PhysicalDeviceFeatures2::builder()
             .push_next(&mut features_v1_1)
             .push_next(&mut features_v1_2)
             .push_next(&mut acceleration_structure_features)
+            .push_next(&mut conditional_rendering_features)
+            .push_next(&mut dynamic_rendering_features)
+            .push_next(&mut extended_dynamic_state_features)
             .push_next(&mut index_type_u8_features)
+            .push_next(&mut mesh_shader_features)
             .push_next(&mut ray_query_features)
             .push_next(&mut ray_trace_features)
+            .push_next(&mut synchronization2_features)
             .build();
         unsafe {
             get_physical_device_features2(physical_device, &mut features);
@@ -255,6 +587,9 @@ impl PhysicalDevice {
             vk::PhysicalDeviceAccelerationStructurePropertiesKHR::default();
         let mut depth_stencil_resolve_properties =
             vk::PhysicalDeviceDepthStencilResolveProperties::default();
+        let mut mesh_shader_properties = vk::PhysicalDeviceMeshShaderPropertiesEXT::default();
+        let mut push_descriptor_properties =
+            vk::PhysicalDevicePushDescriptorPropertiesKHR::default();
         let mut ray_trace_properties = vk::PhysicalDeviceRayTracingPipelinePropertiesKHR::default();
         let mut sampler_filter_minmax_properties =
             vk::PhysicalDeviceSamplerFilterMinmaxProperties::default();
@@ -263,6 +598,8 @@ impl PhysicalDevice {
             .push_next(&mut properties_v1_2)
             .push_next(&mut accel_struct_properties)
             .push_next(&mut depth_stencil_resolve_properties)
+            .push_next(&mut mesh_shader_properties)
+            .push_next(&mut push_descriptor_properties)
             .push_next(&mut ray_trace_properties)
             .push_next(&mut sampler_filter_minmax_properties)
             .build();
@@ -308,41 +645,78 @@ impl PhysicalDevice {
             .collect::<HashSet<_>>();
         let supports_accel_struct = extensions.contains(vk::KhrAccelerationStructureFn::name())
             && extensions.contains(vk::KhrDeferredHostOperationsFn::name());
+        let supports_conditional_rendering =
+            extensions.contains(vk::ExtConditionalRenderingFn::name());
+        let supports_dynamic_rendering = extensions.contains(vk::KhrDynamicRenderingFn::name());
+        let supports_extended_dynamic_state =
+            extensions.contains(vk::ExtExtendedDynamicStateFn::name());
         let supports_index_type_uint8 = extensions.contains(vk::ExtIndexTypeUint8Fn::name());
+        let supports_mesh_shader = extensions.contains(vk::ExtMeshShaderFn::name());
+        let supports_push_descriptor = extensions.contains(vk::KhrPushDescriptorFn::name());
         let supports_ray_query = extensions.contains(vk::KhrRayQueryFn::name());
         let supports_ray_trace = extensions.contains(vk::KhrRayTracingPipelineFn::name());
+        let supports_synchronization2 = extensions.contains(vk::KhrSynchronization2Fn::name());
 
         // Gather optional features and properties of the physical device
+        let accel_struct_features = supports_accel_struct
+            .then(|| acceleration_structure_features.into())
+            .unwrap_or_default();
+        let conditional_rendering_features = supports_conditional_rendering
+            .then(|| conditional_rendering_features.into())
+            .unwrap_or_default();
+        let dynamic_rendering_features = supports_dynamic_rendering
+            .then(|| dynamic_rendering_features.into())
+            .unwrap_or_default();
+        let extended_dynamic_state_features = supports_extended_dynamic_state
+            .then(|| extended_dynamic_state_features.into())
+            .unwrap_or_default();
         let index_type_uint8_features = supports_index_type_uint8
             .then(|| index_type_u8_features.into())
             .unwrap_or_default();
+        let mesh_shader_features = supports_mesh_shader
+            .then(|| mesh_shader_features.into())
+            .unwrap_or_default();
         let ray_query_features = supports_ray_query
             .then(|| ray_query_features.into())
             .unwrap_or_default();
         let ray_trace_features = supports_ray_trace
             .then(|| ray_trace_features.into())
             .unwrap_or_default();
+        let synchronization2_features = supports_synchronization2
+            .then(|| synchronization2_features.into())
+            .unwrap_or_default();
         let accel_struct_properties = supports_accel_struct.then(|| accel_struct_properties.into());
+        let mesh_shader_properties = supports_mesh_shader.then(|| mesh_shader_properties.into());
+        let push_descriptor_properties =
+            supports_push_descriptor.then(|| push_descriptor_properties.into());
         let ray_trace_properties = supports_ray_trace.then(|| ray_trace_properties.into());
 
         Ok(Self {
+            accel_struct_features,
             accel_struct_properties,
+            conditional_rendering_features,
             depth_stencil_resolve_properties,
+            dynamic_rendering_features,
+            extended_dynamic_state_features,
             features_v1_0,
             features_v1_1,
             features_v1_2,
             index_type_uint8_features,
             memory_properties,
+            mesh_shader_features,
+            mesh_shader_properties,
             physical_device,
             properties_v1_0,
             properties_v1_1,
             properties_v1_2,
+            push_descriptor_properties,
             queue_families,
             queue_family_indices,
             ray_query_features,
             ray_trace_features,
             ray_trace_properties,
             sampler_filter_minmax_properties,
+            synchronization2_features,
         })
     }
 }
@@ -486,6 +860,26 @@ impl From<vk::PhysicalDeviceRayTracingPipelinePropertiesKHR> for RayTracePropert
     }
 }
 
+/// Properties of the physical device for push descriptors.
+///
+/// See
+/// [`VkPhysicalDevicePushDescriptorPropertiesKHR`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDevicePushDescriptorPropertiesKHR.html)
+/// manual page.
+#[derive(Debug)]
+pub struct PushDescriptorProperties {
+    /// The maximum number of descriptors that can be used in a descriptor set layout created with
+    /// `VK_DESCRIPTOR_SET_LAYOUT_CREATE_PUSH_DESCRIPTOR_BIT_KHR` set.
+    pub max_push_descriptors: u32,
+}
+
+impl From<vk::PhysicalDevicePushDescriptorPropertiesKHR> for PushDescriptorProperties {
+    fn from(props: vk::PhysicalDevicePushDescriptorPropertiesKHR) -> Self {
+        Self {
+            max_push_descriptors: props.max_push_descriptors,
+        }
+    }
+}
+
 /// Properties of the physical device for min/max sampler filtering.
 ///
 /// See
@@ -531,6 +925,26 @@ impl From<vk::PhysicalDeviceSamplerFilterMinmaxProperties> for SamplerFilterMinm
     }
 }
 
+/// Features of the physical device for synchronization commands.
+///
+/// See
+/// [`VkPhysicalDeviceSynchronization2Features`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceSynchronization2Features.html)
+/// manual page.
+#[derive(Debug, Default)]
+pub struct Synchronization2Features {
+    /// Indicates whether the implementation supports the new set of synchronization commands
+    /// introduced in `VK_KHR_synchronization2`, such as `vkCmdPipelineBarrier2`.
+    pub synchronization2: bool,
+}
+
+impl From<vk::PhysicalDeviceSynchronization2Features> for Synchronization2Features {
+    fn from(features: vk::PhysicalDeviceSynchronization2Features) -> Self {
+        Self {
+            synchronization2: features.synchronization2 == vk::TRUE,
+        }
+    }
+}
+
 /// Description of Vulkan features.
 ///
 /// See
@@ -0,0 +1,366 @@
+//! Query pool resource types
+
+use {
+    super::{access_type_from_u8, access_type_into_u8, device::Device, DriverError},
+    ash::vk,
+    derive_builder::{Builder, UninitializedFieldError},
+    log::{error, trace, warn},
+    std::{
+        ops::Deref,
+        sync::{
+            atomic::{AtomicU8, Ordering},
+            Arc,
+        },
+        thread::panicking,
+    },
+    vk_sync::AccessType,
+};
+
+/// Smart pointer handle to a [query pool] object.
+///
+/// Also contains information about the object.
+///
+/// Queries must be reset before use, either by the host using [`QueryPool::reset`] or by a render
+/// graph using [`RenderGraph::reset_queries`](crate::graph::RenderGraph::reset_queries).
+///
+/// ## `Deref` behavior
+///
+/// `QueryPool` automatically dereferences to [`vk::QueryPool`] (via the [`Deref`][deref] trait),
+/// so you can call `vk::QueryPool`'s methods on a value of type `QueryPool`.
+///
+/// [query pool]: https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkQueryPool.html
+/// [deref]: core::ops::Deref
+#[derive(Debug)]
+pub struct QueryPool {
+    device: Arc<Device>,
+
+    /// Information used to create this object.
+    pub info: QueryPoolInfo,
+
+    prev_access: AtomicU8,
+    query_pool: vk::QueryPool,
+}
+
+impl QueryPool {
+    /// Creates a new query pool on the given device.
+    #[profiling::function]
+    pub fn create(
+        device: &Arc<Device>,
+        info: impl Into<QueryPoolInfo>,
+    ) -> Result<Self, DriverError> {
+        let info = info.into();
+
+        trace!("create: {:?}", info);
+
+        debug_assert_ne!(info.query_count, 0, "Query count must be non-zero");
+
+        let device = Arc::clone(device);
+        let create_info = vk::QueryPoolCreateInfo::builder()
+            .query_type(info.ty)
+            .query_count(info.query_count)
+            .pipeline_statistics(info.pipeline_statistics);
+        let query_pool =
+            unsafe { device.create_query_pool(&create_info, None) }.map_err(|err| {
+                warn!("{err}");
+
+                DriverError::Unsupported
+            })?;
+
+        Ok(Self {
+            device,
+            info,
+            prev_access: AtomicU8::new(access_type_into_u8(AccessType::Nothing)),
+            query_pool,
+        })
+    }
+
+    /// Keeps track of some `next_access` which affects this object.
+    ///
+    /// Returns the previous access for which a pipeline barrier should be used to prevent data
+    /// corruption.
+    ///
+    /// See [`Buffer::access`](super::buffer::Buffer::access).
+    #[profiling::function]
+    pub fn access(this: &Self, next_access: AccessType) -> AccessType {
+        access_type_from_u8(
+            this.prev_access
+                .swap(access_type_into_u8(next_access), Ordering::Relaxed),
+        )
+    }
+
+    /// Returns the most recent access of this object without modifying it.
+    pub(crate) fn peek_access(this: &Self) -> AccessType {
+        access_type_from_u8(this.prev_access.load(Ordering::Relaxed))
+    }
+
+    /// Resets `query_count` queries, starting at `first_query`, from the host.
+    ///
+    /// Requires [`Vulkan12Features::host_query_reset`](super::physical_device::Vulkan12Features::host_query_reset);
+    /// use [`RenderGraph::reset_queries`](crate::graph::RenderGraph::reset_queries) otherwise.
+    #[profiling::function]
+    pub fn reset(this: &Self, first_query: u32, query_count: u32) -> Result<(), DriverError> {
+        debug_assert!(
+            first_query + query_count <= this.info.query_count,
+            "Queries must be within the range of the query pool"
+        );
+
+        if !this.device.physical_device.features_v1_2.host_query_reset {
+            warn!("host query reset is not supported");
+
+            return Err(DriverError::Unsupported);
+        }
+
+        unsafe {
+            this.device
+                .reset_query_pool(this.query_pool, first_query, query_count);
+        }
+
+        Ok(())
+    }
+
+    /// Reads the 64-bit results of `query_count` queries, starting at `first_query`, into
+    /// `results`.
+    ///
+    /// Returns `false` without blocking if the results of any of the queries are not yet available.
+    #[profiling::function]
+    pub fn results(
+        this: &Self,
+        first_query: u32,
+        query_count: u32,
+        results: &mut [u64],
+    ) -> Result<bool, DriverError> {
+        debug_assert!(
+            first_query + query_count <= this.info.query_count,
+            "Queries must be within the range of the query pool"
+        );
+
+        match unsafe {
+            this.device.get_query_pool_results(
+                this.query_pool,
+                first_query,
+                query_count,
+                results,
+                vk::QueryResultFlags::TYPE_64,
+            )
+        } {
+            Ok(_) => Ok(true),
+            Err(err) if err == vk::Result::NOT_READY => Ok(false),
+            Err(err) if err == vk::Result::ERROR_DEVICE_LOST => {
+                error!("Device lost");
+
+                Err(DriverError::InvalidData)
+            }
+            Err(err) => {
+                warn!("{err}");
+
+                Err(DriverError::OutOfMemory)
+            }
+        }
+    }
+}
+
+impl Deref for QueryPool {
+    type Target = vk::QueryPool;
+
+    fn deref(&self) -> &Self::Target {
+        &self.query_pool
+    }
+}
+
+impl Drop for QueryPool {
+    #[profiling::function]
+    fn drop(&mut self) {
+        if panicking() {
+            return;
+        }
+
+        unsafe {
+            self.device.destroy_query_pool(self.query_pool, None);
+        }
+    }
+}
+
+/// Information used to create a [`QueryPool`] instance.
+#[derive(Builder, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[builder(
+    build_fn(private, name = "fallible_build", error = "QueryPoolInfoBuilderError"),
+    derive(Clone, Copy, Debug),
+    pattern = "owned"
+)]
+#[non_exhaustive]
+pub struct QueryPoolInfo {
+    /// A bitmask specifying which counters will be returned in queries on the new pool.
+    ///
+    /// Ignored unless `ty` is [`vk::QueryType::PIPELINE_STATISTICS`].
+    #[builder(default)]
+    pub pipeline_statistics: vk::QueryPipelineStatisticFlags,
+
+    /// The number of queries managed by the pool.
+    pub query_count: u32,
+
+    /// Specifies the type of queries managed by the pool.
+    pub ty: vk::QueryType,
+}
+
+impl QueryPoolInfo {
+    /// Specifies a pool of `query_count` queries which hold the compacted size of acceleration
+    /// structures.
+    ///
+    /// See [`RenderGraph::write_accel_struct_compacted_size`](crate::graph::RenderGraph::write_accel_struct_compacted_size).
+    #[inline(always)]
+    pub const fn accel_struct_compacted_size(query_count: u32) -> QueryPoolInfo {
+        QueryPoolInfo {
+            pipeline_statistics: vk::QueryPipelineStatisticFlags::empty(),
+            query_count,
+            ty: vk::QueryType::ACCELERATION_STRUCTURE_COMPACTED_SIZE_KHR,
+        }
+    }
+
+    /// Specifies a pool of `query_count` queries which hold the serialized size of acceleration
+    /// structures.
+    ///
+    /// See [`RenderGraph::write_accel_struct_serialization_size`](crate::graph::RenderGraph::write_accel_struct_serialization_size).
+    #[inline(always)]
+    pub const fn accel_struct_serialization_size(query_count: u32) -> QueryPoolInfo {
+        QueryPoolInfo {
+            pipeline_statistics: vk::QueryPipelineStatisticFlags::empty(),
+            query_count,
+            ty: vk::QueryType::ACCELERATION_STRUCTURE_SERIALIZATION_SIZE_KHR,
+        }
+    }
+
+    /// Specifies a pool of `query_count` occlusion queries.
+    #[inline(always)]
+    pub const fn occlusion(query_count: u32) -> QueryPoolInfo {
+        QueryPoolInfo {
+            pipeline_statistics: vk::QueryPipelineStatisticFlags::empty(),
+            query_count,
+            ty: vk::QueryType::OCCLUSION,
+        }
+    }
+
+    /// Specifies a pool of `query_count` pipeline statistics queries which count the given
+    /// statistics.
+    #[inline(always)]
+    pub const fn pipeline_statistics(
+        query_count: u32,
+        pipeline_statistics: vk::QueryPipelineStatisticFlags,
+    ) -> QueryPoolInfo {
+        QueryPoolInfo {
+            pipeline_statistics,
+            query_count,
+            ty: vk::QueryType::PIPELINE_STATISTICS,
+        }
+    }
+
+    /// Returns the number of values written by each query of this pool, not including
+    /// availability.
+    ///
+    /// Pipeline statistics queries write one value per enabled statistic; other queries write a
+    /// single value.
+    pub fn query_result_count(&self) -> u32 {
+        if self.ty == vk::QueryType::PIPELINE_STATISTICS {
+            self.pipeline_statistics.as_raw().count_ones()
+        } else {
+            1
+        }
+    }
+
+    /// Specifies a pool of `query_count` timestamp queries.
+    #[inline(always)]
+    pub const fn timestamp(query_count: u32) -> QueryPoolInfo {
+        QueryPoolInfo {
+            pipeline_statistics: vk::QueryPipelineStatisticFlags::empty(),
+            query_count,
+            ty: vk::QueryType::TIMESTAMP,
+        }
+    }
+
+    /// Converts a `QueryPoolInfo` into a `QueryPoolInfoBuilder`.
+    #[inline(always)]
+    pub fn to_builder(self) -> QueryPoolInfoBuilder {
+        QueryPoolInfoBuilder {
+            pipeline_statistics: Some(self.pipeline_statistics),
+            query_count: Some(self.query_count),
+            ty: Some(self.ty),
+        }
+    }
+}
+
+impl From<QueryPoolInfoBuilder> for QueryPoolInfo {
+    fn from(info: QueryPoolInfoBuilder) -> Self {
+        info.build()
+    }
+}
+
+impl QueryPoolInfoBuilder {
+    /// Builds a new `QueryPoolInfo`.
+    ///
+    /// # Panics
+    ///
+    /// If any of the following values have not been set this function will panic:
+    ///
+    /// * `query_count`
+    /// * `ty`
+    #[inline(always)]
+    pub fn build(self) -> QueryPoolInfo {
+        match self.fallible_build() {
+            Err(QueryPoolInfoBuilderError(err)) => panic!("{err}"),
+            Ok(info) => info,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct QueryPoolInfoBuilderError(UninitializedFieldError);
+
+impl From<UninitializedFieldError> for QueryPoolInfoBuilderError {
+    fn from(err: UninitializedFieldError) -> Self {
+        Self(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Info = QueryPoolInfo;
+    type Builder = QueryPoolInfoBuilder;
+
+    #[test]
+    pub fn query_pool_info() {
+        let info = Info::timestamp(8);
+        let builder = info.to_builder().build();
+
+        assert_eq!(info, builder);
+    }
+
+    #[test]
+    pub fn query_pool_info_builder() {
+        let info = Info::timestamp(8);
+        let builder = Builder::default()
+            .query_count(8)
+            .ty(vk::QueryType::TIMESTAMP)
+            .build();
+
+        assert_eq!(info, builder);
+    }
+
+    #[test]
+    pub fn query_pool_info_result_count() {
+        let info = Info::pipeline_statistics(
+            1,
+            vk::QueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS
+                | vk::QueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS,
+        );
+
+        assert_eq!(info.query_result_count(), 2);
+        assert_eq!(Info::occlusion(1).query_result_count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Field not initialized: query_count")]
+    pub fn query_pool_info_builder_uninit_query_count() {
+        Builder::default().build();
+    }
+}
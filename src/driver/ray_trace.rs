@@ -2,16 +2,18 @@
 
 use {
     super::{
+        buffer::{Buffer, BufferInfo},
         device::Device,
         merge_push_constant_ranges,
         physical_device::RayTraceProperties,
         shader::{DescriptorBindingMap, PipelineDescriptorInfo, Shader},
         DriverError,
     },
+    crate::pool::{Lease, Pool},
     ash::vk,
     derive_builder::{Builder, UninitializedFieldError},
     log::warn,
-    std::{ffi::CString, ops::Deref, sync::Arc, thread::panicking},
+    std::{ffi::CString, ops::Deref, slice::from_ref, sync::Arc, thread::panicking},
 };
 
 /// Smart pointer handle to a [pipeline] object.
@@ -312,11 +314,7 @@ impl RayTracePipeline {
     /// Function returning a handle to a shader group of this pipeline.
     /// This can be used to construct a sbt.
     ///
-    /// # Examples
-    ///
-    /// See
-    /// [ray_trace.rs](https://github.com/attackgoat/screen-13/blob/master/examples/ray_trace.rs)
-    /// for a detail example which constructs a shader binding table buffer using this function.
+    /// See [`ShaderBindingTable`] which lays out and writes these handles for you.
     pub fn group_handle(this: &Self, idx: usize) -> Result<&[u8], DriverError> {
         let &RayTraceProperties {
             shader_group_handle_size,
@@ -355,7 +353,9 @@ impl RayTracePipeline {
 
     /// Sets the debugging name assigned to this pipeline.
     pub fn with_name(mut this: Self, name: impl Into<String>) -> Self {
-        this.name = Some(name.into());
+        let name = name.into();
+        Device::set_debug_name(&this.device, this.pipeline, &name);
+        this.name = Some(name);
         this
     }
 }
@@ -644,6 +644,289 @@ impl From<RayTraceShaderGroupType> for vk::RayTracingShaderGroupTypeKHR {
     }
 }
 
+/// A [shader binding table] which holds the shader records used by ray tracing commands.
+///
+/// Shader binding tables are laid out according to the [`RayTraceProperties`] of the device and
+/// written into a host-visible buffer leased from a pool. Each region of the table may be passed
+/// to [`RayTrace::trace_rays`] or the table may be passed to [`RayTrace::trace_rays_with_table`].
+///
+/// The buffer must be bound to the render graph and accessed by ray trace passes which use the
+/// table.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use ash::vk;
+/// # use screen_13::driver::{AccessType, DriverError};
+/// # use screen_13::driver::device::{Device, DeviceInfo};
+/// # use screen_13::driver::ray_trace::{RayTracePipeline, RayTracePipelineInfo, RayTraceShaderGroup, ShaderBindingTable, ShaderBindingTableInfo, ShaderRecord};
+/// # use screen_13::driver::shader::Shader;
+/// # use screen_13::graph::RenderGraph;
+/// # use screen_13::pool::lazy::LazyPool;
+/// # fn main() -> Result<(), DriverError> {
+/// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+/// # let mut pool = LazyPool::new(&device);
+/// # let shaders = [Shader::new_ray_gen([0u8; 1].as_slice())];
+/// # let groups = [RayTraceShaderGroup::new_general(0)];
+/// # let my_pipeline = Arc::new(RayTracePipeline::create(&device, RayTracePipelineInfo::default(), shaders, groups)?);
+/// # let mut my_graph = RenderGraph::new();
+/// // Shader groups: 0 is ray generation, 1 and 2 are miss and 3 is a hit group which reads a
+/// // material index from the shader record
+/// let info = ShaderBindingTableInfo::new(0)
+///     .miss(1)
+///     .miss(2)
+///     .hit(ShaderRecord::new(3).with_data(42u32.to_ne_bytes()));
+/// let sbt = ShaderBindingTable::create(&mut pool, &my_pipeline, info)?;
+/// let sbt_node = my_graph.bind_node(&sbt.buffer);
+///
+/// my_graph.begin_pass("trace some rays")
+///         .bind_pipeline(&my_pipeline)
+///         .access_node(sbt_node, AccessType::RayTracingShaderReadOther)
+///         .record_ray_trace(move |ray_trace, _| {
+///             ray_trace.trace_rays_with_table(&sbt, 320, 200, 1);
+///         });
+/// # Ok(()) }
+/// ```
+///
+/// [shader binding table]: https://registry.khronos.org/vulkan/specs/1.3-extensions/html/vkspec.html#shader-binding-table
+/// [`RayTrace::trace_rays`]: crate::graph::pass_ref::RayTrace::trace_rays
+/// [`RayTrace::trace_rays_with_table`]: crate::graph::pass_ref::RayTrace::trace_rays_with_table
+#[derive(Debug)]
+pub struct ShaderBindingTable {
+    /// The buffer which holds the shader records.
+    pub buffer: Arc<Lease<Buffer>>,
+
+    /// The region of callable shader records, which is empty if there are none.
+    pub callable: vk::StridedDeviceAddressRegionKHR,
+
+    /// The region of hit shader records, which is empty if there are none.
+    pub hit: vk::StridedDeviceAddressRegionKHR,
+
+    /// Information used to create this object.
+    pub info: ShaderBindingTableInfo,
+
+    /// The region of miss shader records, which is empty if there are none.
+    pub miss: vk::StridedDeviceAddressRegionKHR,
+
+    /// The region of the ray generation shader record.
+    pub ray_gen: vk::StridedDeviceAddressRegionKHR,
+}
+
+impl ShaderBindingTable {
+    /// Creates a new shader binding table of shader groups of the given pipeline.
+    ///
+    /// Returns [`DriverError::InvalidData`] if a record refers to a shader group which the pipeline
+    /// does not have, or if a record is larger than the device allows.
+    #[profiling::function]
+    pub fn create(
+        pool: &mut impl Pool<BufferInfo, Buffer>,
+        pipeline: &RayTracePipeline,
+        info: impl Into<ShaderBindingTableInfo>,
+    ) -> Result<Self, DriverError> {
+        let info = info.into();
+        let properties = pipeline
+            .device
+            .physical_device
+            .ray_trace_properties
+            .as_ref()
+            .ok_or(DriverError::Unsupported)?;
+        let group_count =
+            pipeline.shader_group_handles.len() / properties.shader_group_handle_size as usize;
+
+        if let Some(record) = info
+            .records()
+            .flat_map(|records| records.iter())
+            .find(|record| record.group as usize >= group_count)
+        {
+            warn!("invalid shader group {}", record.group);
+
+            return Err(DriverError::InvalidData);
+        }
+
+        let (size, mut regions) = info.layout(properties)?;
+        let mut buffer = pool.lease(
+            BufferInfo::host_mem(
+                size,
+                vk::BufferUsageFlags::SHADER_BINDING_TABLE_KHR
+                    | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+            )
+            .to_builder()
+            .alignment(properties.shader_group_base_alignment as _)
+            .build(),
+        )?;
+        let address = Buffer::device_address(&buffer);
+        let data = Buffer::mapped_slice_mut(&mut buffer);
+
+        // Leased buffers may contain data from previous uses
+        data[0..size as usize].fill(0);
+
+        for (region, records) in regions.iter_mut().zip(info.records()) {
+            for (idx, record) in records.iter().enumerate() {
+                let handle = RayTracePipeline::group_handle(pipeline, record.group as _)?;
+                let start =
+                    (region.device_address + idx as vk::DeviceSize * region.stride) as usize;
+                let end = start + handle.len();
+                data[start..end].copy_from_slice(handle);
+                data[end..end + record.data.len()].copy_from_slice(&record.data);
+            }
+
+            if region.size > 0 {
+                region.device_address += address;
+            }
+        }
+
+        let [ray_gen, miss, hit, callable] = regions;
+
+        Ok(Self {
+            buffer: Arc::new(buffer),
+            callable,
+            hit,
+            info,
+            miss,
+            ray_gen,
+        })
+    }
+}
+
+/// Information used to create a [`ShaderBindingTable`] instance.
+///
+/// Records are placed in the order they are added to each region; the hit record of a geometry
+/// is selected using the instance shader binding table offset and the geometry index of the
+/// acceleration structure, and miss records are selected by the index given to `traceRayEXT`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ShaderBindingTableInfo {
+    /// Callable shader records.
+    pub callable: Vec<ShaderRecord>,
+
+    /// Hit group shader records.
+    pub hit: Vec<ShaderRecord>,
+
+    /// Miss shader records.
+    pub miss: Vec<ShaderRecord>,
+
+    /// The ray generation shader record.
+    pub ray_gen: ShaderRecord,
+}
+
+impl ShaderBindingTableInfo {
+    /// Specifies a shader binding table with the given ray generation record.
+    pub fn new(ray_gen: impl Into<ShaderRecord>) -> Self {
+        Self {
+            callable: vec![],
+            hit: vec![],
+            miss: vec![],
+            ray_gen: ray_gen.into(),
+        }
+    }
+
+    /// Adds a callable shader record.
+    pub fn callable(mut self, record: impl Into<ShaderRecord>) -> Self {
+        self.callable.push(record.into());
+        self
+    }
+
+    /// Adds a hit group shader record.
+    pub fn hit(mut self, record: impl Into<ShaderRecord>) -> Self {
+        self.hit.push(record.into());
+        self
+    }
+
+    /// Returns the total size and the regions of the table, which are in the same order as
+    /// [`Self::records`] and have addresses relative to the start of the table.
+    fn layout(
+        &self,
+        properties: &RayTraceProperties,
+    ) -> Result<(vk::DeviceSize, [vk::StridedDeviceAddressRegionKHR; 4]), DriverError> {
+        let base_alignment = properties.shader_group_base_alignment as vk::DeviceSize;
+        let handle_alignment = properties.shader_group_handle_alignment as vk::DeviceSize;
+        let handle_size = properties.shader_group_handle_size as vk::DeviceSize;
+
+        let mut regions = [vk::StridedDeviceAddressRegionKHR::default(); 4];
+        let mut size: vk::DeviceSize = 0;
+
+        for (region, records) in regions.iter_mut().zip(self.records()) {
+            // Every record of a region uses the stride of the largest record
+            let Some(stride) = records
+                .iter()
+                .map(|record| handle_size + record.data.len() as vk::DeviceSize)
+                .max()
+                .map(|stride| stride.next_multiple_of(handle_alignment))
+            else {
+                continue;
+            };
+
+            if stride > properties.max_shader_group_stride as _ {
+                warn!("shader record stride {stride} exceeds device limit");
+
+                return Err(DriverError::InvalidData);
+            }
+
+            let offset = size.next_multiple_of(base_alignment);
+            *region = vk::StridedDeviceAddressRegionKHR {
+                device_address: offset,
+                stride,
+                size: stride * records.len() as vk::DeviceSize,
+            };
+            size = offset + region.size;
+        }
+
+        Ok((size, regions))
+    }
+
+    /// Adds a miss shader record.
+    pub fn miss(mut self, record: impl Into<ShaderRecord>) -> Self {
+        self.miss.push(record.into());
+        self
+    }
+
+    fn records(&self) -> impl Iterator<Item = &[ShaderRecord]> {
+        [
+            from_ref(&self.ray_gen),
+            self.miss.as_slice(),
+            self.hit.as_slice(),
+            self.callable.as_slice(),
+        ]
+        .into_iter()
+    }
+}
+
+/// A record of a [`ShaderBindingTable`], which is a shader group handle followed by optional data.
+///
+/// The data may be read by shaders of the group using a `shaderRecordEXT` buffer block.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ShaderRecord {
+    /// Data placed after the shader group handle.
+    pub data: Vec<u8>,
+
+    /// The index of the shader group within the pipeline.
+    pub group: u32,
+}
+
+impl ShaderRecord {
+    /// Specifies a record of the given shader group without data.
+    pub fn new(group: u32) -> Self {
+        Self {
+            data: vec![],
+            group,
+        }
+    }
+
+    /// Sets the data placed after the shader group handle.
+    pub fn with_data(mut self, data: impl AsRef<[u8]>) -> Self {
+        self.data = data.as_ref().to_vec();
+        self
+    }
+}
+
+impl From<u32> for ShaderRecord {
+    fn from(group: u32) -> Self {
+        Self::new(group)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -666,4 +949,37 @@ mod tests {
 
         assert_eq!(info, builder);
     }
+
+    #[test]
+    pub fn shader_binding_table_layout() {
+        let properties = RayTraceProperties {
+            shader_group_handle_size: 32,
+            max_ray_recursion_depth: 1,
+            max_shader_group_stride: 4096,
+            shader_group_base_alignment: 64,
+            shader_group_handle_capture_replay_size: 0,
+            max_ray_dispatch_invocation_count: u32::MAX,
+            shader_group_handle_alignment: 32,
+            max_ray_hit_attribute_size: 32,
+        };
+        let info = ShaderBindingTableInfo::new(0)
+            .miss(1)
+            .miss(2)
+            .hit(3)
+            .hit(ShaderRecord::new(4).with_data([0u8; 12]));
+        let (size, [ray_gen, miss, hit, callable]) = info.layout(&properties).unwrap();
+
+        assert_eq!(
+            (ray_gen.device_address, ray_gen.stride, ray_gen.size),
+            (0, 32, 32)
+        );
+        assert_eq!((miss.device_address, miss.stride, miss.size), (64, 32, 64));
+        assert_eq!((hit.device_address, hit.stride, hit.size), (128, 64, 128));
+        assert_eq!(callable.size, 0);
+        assert_eq!(size, 256);
+
+        let info = info.hit(ShaderRecord::new(5).with_data([0u8; 4096]));
+
+        assert!(info.layout(&properties).is_err());
+    }
 }
@@ -316,8 +316,6 @@ impl RenderPass {
         depth_stencil: Option<DepthStencilMode>,
         subpass_idx: u32,
     ) -> Result<vk::Pipeline, DriverError> {
-        use std::slice::from_ref;
-
         let entry = this.graphic_pipelines.entry(GraphicPipelineKey {
             depth_stencil,
             layout: pipeline.layout,
@@ -333,100 +331,16 @@ impl RenderPass {
             _ => unreachable!(),
         };
 
-        let color_blend_attachment_states = this.info.subpasses[subpass_idx as usize]
-            .color_attachments
-            .iter()
-            .map(|_| pipeline.info.blend.into_vk())
-            .collect::<Box<[_]>>();
-        let color_blend_state = vk::PipelineColorBlendStateCreateInfo::builder()
-            .attachments(&color_blend_attachment_states);
-        let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
-        let dynamic_state =
-            vk::PipelineDynamicStateCreateInfo::builder().dynamic_states(&dynamic_states);
-        let multisample_state = vk::PipelineMultisampleStateCreateInfo::builder()
-            .alpha_to_coverage_enable(pipeline.state.multisample.alpha_to_coverage_enable)
-            .alpha_to_one_enable(pipeline.state.multisample.alpha_to_one_enable)
-            .flags(pipeline.state.multisample.flags)
-            .min_sample_shading(pipeline.state.multisample.min_sample_shading)
-            .rasterization_samples(pipeline.state.multisample.rasterization_samples.into_vk())
-            .sample_shading_enable(pipeline.state.multisample.sample_shading_enable)
-            .sample_mask(&pipeline.state.multisample.sample_mask);
-        let mut specializations = Vec::with_capacity(pipeline.state.stages.len());
-        let stages = pipeline
-            .state
-            .stages
-            .iter()
-            .map(|stage| {
-                let mut info = vk::PipelineShaderStageCreateInfo::builder()
-                    .module(stage.module)
-                    .name(&stage.name)
-                    .stage(stage.flags);
-
-                if let Some(specialization_info) = &stage.specialization_info {
-                    specializations.push(
-                        vk::SpecializationInfo::builder()
-                            .map_entries(&specialization_info.map_entries)
-                            .data(&specialization_info.data)
-                            .build(),
-                    );
-
-                    info = info.specialization_info(specializations.last().unwrap());
-                }
-
-                info.build()
-            })
-            .collect::<Box<[_]>>();
-        let vertex_input_state = vk::PipelineVertexInputStateCreateInfo::builder()
-            .vertex_attribute_descriptions(
-                &pipeline.state.vertex_input.vertex_attribute_descriptions,
-            )
-            .vertex_binding_descriptions(&pipeline.state.vertex_input.vertex_binding_descriptions);
-        let viewport_state = vk::PipelineViewportStateCreateInfo::builder()
-            .viewport_count(1)
-            .scissor_count(1);
-        let input_assembly_state = vk::PipelineInputAssemblyStateCreateInfo {
-            topology: pipeline.info.topology,
-            ..Default::default()
-        };
-        let depth_stencil = depth_stencil
-            .map(|depth_stencil| depth_stencil.into_vk())
-            .unwrap_or_default();
-        let rasterization_state = vk::PipelineRasterizationStateCreateInfo {
-            front_face: pipeline.info.front_face,
-            line_width: 1.0,
-            polygon_mode: pipeline.info.polygon_mode,
-            cull_mode: pipeline.info.cull_mode,
-            ..Default::default()
-        };
-        let graphic_pipeline_info = vk::GraphicsPipelineCreateInfo::builder()
-            .color_blend_state(&color_blend_state)
-            .depth_stencil_state(&depth_stencil)
-            .dynamic_state(&dynamic_state)
-            .input_assembly_state(&input_assembly_state)
-            .layout(pipeline.state.layout)
-            .multisample_state(&multisample_state)
-            .rasterization_state(&rasterization_state)
-            .render_pass(this.render_pass)
-            .stages(&stages)
-            .subpass(subpass_idx)
-            .vertex_input_state(&vertex_input_state)
-            .viewport_state(&viewport_state);
-
-        let pipeline = unsafe {
-            this.device.create_graphics_pipelines(
-                vk::PipelineCache::null(),
-                from_ref(&graphic_pipeline_info),
-                None,
-            )
-        }
-        .map_err(|(_, err)| {
-            warn!(
-                "create_graphics_pipelines: {err}\n{:#?}",
-                graphic_pipeline_info.build()
-            );
-
-            DriverError::Unsupported
-        })?[0];
+        let pipeline = GraphicPipeline::create_vk_pipeline(
+            pipeline,
+            depth_stencil,
+            this.info.subpasses[subpass_idx as usize]
+                .color_attachments
+                .len(),
+            this.render_pass,
+            subpass_idx,
+            None,
+        )?;
 
         entry.insert(pipeline);
 
@@ -480,7 +394,7 @@ pub enum ResolveMode {
 }
 
 impl ResolveMode {
-    fn into_vk(mode: Option<ResolveMode>) -> vk::ResolveModeFlags {
+    pub(crate) fn into_vk(mode: Option<ResolveMode>) -> vk::ResolveModeFlags {
         match mode {
             None => vk::ResolveModeFlags::NONE,
             Some(ResolveMode::Average) => vk::ResolveModeFlags::AVERAGE,
@@ -0,0 +1,311 @@
+//! Host-visible memory used to upload data to device resources.
+
+use {
+    super::{device::Device, DriverError},
+    ash::vk,
+    gpu_allocator::{
+        vulkan::{Allocation, AllocationCreateDesc, AllocationScheme},
+        MemoryLocation,
+    },
+    log::{trace, warn},
+    std::{
+        collections::VecDeque,
+        ops::Range,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    },
+};
+
+/// A per-device ring of mappable buffers which hold data until it has been copied to a device
+/// resource.
+///
+/// Data is written into a chunk of staging memory after the most recently staged data, wrapping
+/// around to the start of the chunk once the end is reached. Regions are reused after they have
+/// been dropped, which render graphs do once the command buffer which copies them has executed.
+/// When the current chunk is full a larger chunk replaces it; previous chunks are destroyed once
+/// every region within them has been dropped.
+///
+/// The ring stores raw handles because [`Device`] owns it and a [`Buffer`](super::buffer::Buffer)
+/// would keep the device alive. Chunks are destroyed by the device when it is dropped.
+#[derive(Debug, Default)]
+pub(crate) struct StagingRing {
+    chunks: Vec<StagingChunk>,
+}
+
+impl StagingRing {
+    const MIN_CHUNK_SIZE: vk::DeviceSize = 1 << 20;
+
+    /// Destroys all chunks; the device must be idle.
+    pub(crate) fn destroy(this: &mut Self, device: &Device) {
+        for chunk in this.chunks.drain(..) {
+            StagingChunk::destroy(chunk, device);
+        }
+    }
+
+    /// Copies `data` into staging memory placed at a multiple of `alignment`.
+    #[profiling::function]
+    pub(crate) fn stage(
+        this: &mut Self,
+        device: &Device,
+        data: &[u8],
+        alignment: vk::DeviceSize,
+    ) -> Result<StagingRegion, DriverError> {
+        debug_assert!(!data.is_empty(), "Data must not be empty");
+
+        let size = data.len() as vk::DeviceSize;
+
+        // Destroy previous chunks once nothing is staged within them
+        let mut chunk_idx = 0;
+        while chunk_idx + 1 < this.chunks.len() {
+            if this.chunks[chunk_idx].ring.is_idle() {
+                StagingChunk::destroy(this.chunks.remove(chunk_idx), device);
+            } else {
+                chunk_idx += 1;
+            }
+        }
+
+        let placement = this
+            .chunks
+            .last_mut()
+            .and_then(|chunk| chunk.ring.allocate(size, alignment));
+        let (offset, is_released) = match placement {
+            Some(placement) => placement,
+            None => {
+                let chunk_size = this
+                    .chunks
+                    .last()
+                    .map(|chunk| chunk.ring.size << 1)
+                    .unwrap_or_default()
+                    .max(size.next_power_of_two())
+                    .max(Self::MIN_CHUNK_SIZE);
+                this.chunks.push(StagingChunk::create(device, chunk_size)?);
+                this.chunks
+                    .last_mut()
+                    .unwrap()
+                    .ring
+                    .allocate(size, alignment)
+                    .unwrap()
+            }
+        };
+
+        let chunk = this.chunks.last_mut().unwrap();
+        let start = offset as usize;
+        chunk.allocation.mapped_slice_mut().unwrap()[start..start + data.len()]
+            .copy_from_slice(data);
+
+        Ok(StagingRegion {
+            buffer: chunk.buffer,
+            is_released,
+            offset,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct StagingChunk {
+    allocation: Allocation,
+    buffer: vk::Buffer,
+    ring: RingAllocator,
+}
+
+impl StagingChunk {
+    #[profiling::function]
+    fn create(device: &Device, size: vk::DeviceSize) -> Result<Self, DriverError> {
+        trace!("create: {size}");
+
+        let buffer_info = vk::BufferCreateInfo::builder()
+            .size(size)
+            .usage(vk::BufferUsageFlags::TRANSFER_SRC)
+            .sharing_mode(vk::SharingMode::CONCURRENT)
+            .queue_family_indices(&device.physical_device.queue_family_indices);
+        let buffer = unsafe {
+            device.create_buffer(&buffer_info, None).map_err(|err| {
+                warn!("{err}");
+
+                DriverError::Unsupported
+            })?
+        };
+        let requirements = unsafe { device.get_buffer_memory_requirements(buffer) };
+        let allocation = {
+            #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+            let mut allocator = device.allocator.lock();
+
+            #[cfg(not(feature = "parking_lot"))]
+            let mut allocator = allocator.unwrap();
+
+            allocator
+                .allocate(&AllocationCreateDesc {
+                    name: "staging",
+                    requirements,
+                    location: MemoryLocation::CpuToGpu,
+                    linear: true,
+                    allocation_scheme: AllocationScheme::GpuAllocatorManaged,
+                })
+                .map_err(|err| {
+                    warn!("{err}");
+
+                    unsafe {
+                        device.destroy_buffer(buffer, None);
+                    }
+
+                    DriverError::Unsupported
+                })
+        }?;
+
+        let chunk = Self {
+            allocation,
+            buffer,
+            ring: RingAllocator::new(size),
+        };
+
+        if let Err(err) = unsafe {
+            device.bind_buffer_memory(buffer, chunk.allocation.memory(), chunk.allocation.offset())
+        } {
+            warn!("{err}");
+
+            Self::destroy(chunk, device);
+
+            return Err(DriverError::Unsupported);
+        }
+
+        Ok(chunk)
+    }
+
+    fn destroy(this: Self, device: &Device) {
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let mut allocator = device.allocator.lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let mut allocator = allocator.unwrap();
+
+        allocator
+            .free(this.allocation)
+            .unwrap_or_else(|_| warn!("Unable to free staging allocation"));
+
+        unsafe {
+            device.destroy_buffer(this.buffer, None);
+        }
+    }
+}
+
+/// A range of staging memory which may be reused once dropped.
+#[derive(Debug)]
+pub(crate) struct StagingRegion {
+    pub(crate) buffer: vk::Buffer,
+    is_released: Arc<AtomicBool>,
+    pub(crate) offset: vk::DeviceSize,
+}
+
+impl Drop for StagingRegion {
+    fn drop(&mut self) {
+        self.is_released.store(true, Ordering::Release);
+    }
+}
+
+/// Tracks the ranges of a chunk which are in use, in the order they were allocated.
+#[derive(Debug)]
+struct RingAllocator {
+    regions: VecDeque<(Range<vk::DeviceSize>, Arc<AtomicBool>)>,
+    size: vk::DeviceSize,
+}
+
+impl RingAllocator {
+    fn new(size: vk::DeviceSize) -> Self {
+        Self {
+            regions: Default::default(),
+            size,
+        }
+    }
+
+    /// Returns the offset of `size` bytes of unused space, if available.
+    fn allocate(
+        &mut self,
+        size: vk::DeviceSize,
+        alignment: vk::DeviceSize,
+    ) -> Option<(vk::DeviceSize, Arc<AtomicBool>)> {
+        self.release();
+
+        let align = |offset: vk::DeviceSize| offset.next_multiple_of(alignment);
+        let offset = match (self.regions.front(), self.regions.back()) {
+            // The used space has not wrapped around; free space is after the newest region or
+            // before the oldest region
+            (Some((front, _)), Some((back, _))) if front.start <= back.start => {
+                let offset = align(back.end);
+                if offset + size <= self.size {
+                    offset
+                } else if size <= front.start {
+                    0
+                } else {
+                    return None;
+                }
+            }
+            // The used space has wrapped around; free space is between the newest and oldest
+            // regions
+            (Some((front, _)), Some((back, _))) => {
+                let offset = align(back.end);
+                if offset + size <= front.start {
+                    offset
+                } else {
+                    return None;
+                }
+            }
+            _ if size <= self.size => 0,
+            _ => return None,
+        };
+
+        let is_released = Arc::new(AtomicBool::new(false));
+        self.regions
+            .push_back((offset..offset + size, Arc::clone(&is_released)));
+
+        Some((offset, is_released))
+    }
+
+    fn is_idle(&mut self) -> bool {
+        self.release();
+        self.regions.is_empty()
+    }
+
+    // Regions are reused in the order they were allocated, so a region which is held for a long
+    // time prevents the space after it from being reused
+    fn release(&mut self) {
+        while self
+            .regions
+            .front()
+            .is_some_and(|(_, is_released)| is_released.load(Ordering::Acquire))
+        {
+            self.regions.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn ring_allocator_reuses_released_regions() {
+        let mut ring = RingAllocator::new(256);
+
+        let (a, a_released) = ring.allocate(100, 1).unwrap();
+        let (b, b_released) = ring.allocate(100, 64).unwrap();
+
+        assert_eq!(a, 0);
+        assert_eq!(b, 128);
+        assert!(ring.allocate(100, 1).is_none());
+
+        // Regions are released in allocation order
+        b_released.store(true, Ordering::Release);
+
+        assert!(ring.allocate(100, 1).is_none());
+
+        a_released.store(true, Ordering::Release);
+
+        let (c, _c_released) = ring.allocate(100, 1).unwrap();
+
+        assert_eq!(c, 0);
+        assert!(!ring.is_idle());
+        assert!(ring.allocate(200, 1).is_none());
+    }
+}
@@ -420,7 +420,7 @@ impl Swapchain {
             .into_iter()
             .enumerate()
             .map(|(idx, vk_image)| {
-                let mut image = Image::from_raw(
+                let image = Image::from_raw(
                     &self.device,
                     vk_image,
                     ImageInfo::image_2d(
@@ -430,8 +430,7 @@ impl Swapchain {
                         surface_capabilities.supported_usage_flags,
                     ),
                 );
-                image.name = Some(format!("swapchain{idx}"));
-                Some(image)
+                Some(Image::with_name(image, format!("swapchain{idx}")))
             })
             .collect();
 
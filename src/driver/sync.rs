@@ -0,0 +1,170 @@
+//! Synchronization primitive types.
+
+use {
+    super::{device::Device, DriverError},
+    ash::vk,
+    log::{error, warn},
+    std::{ops::Deref, sync::Arc, thread::panicking, time::Duration},
+};
+
+/// Smart pointer handle to a Vulkan timeline semaphore.
+///
+/// A timeline semaphore holds a monotonically increasing 64-bit counter value which may be signaled
+/// and waited on by both the GPU and the host. Unlike a fence, waiting on a timeline semaphore does
+/// not require blocking the CPU: render graphs may be submitted which wait on values signaled by
+/// other render graphs, threads, or external systems.
+///
+/// See [`Resolver::submit_with_semaphores`](crate::graph::Resolver::submit_with_semaphores).
+///
+/// Requires [`Vulkan12Features::timeline_semaphore`](super::physical_device::Vulkan12Features::timeline_semaphore).
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use screen_13::driver::DriverError;
+/// # use screen_13::driver::device::{Device, DeviceInfo};
+/// # use screen_13::driver::sync::TimelineSemaphore;
+/// # use screen_13::graph::RenderGraph;
+/// # use screen_13::pool::lazy::LazyPool;
+/// # fn main() -> Result<(), DriverError> {
+/// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+/// # let mut pool = LazyPool::new(&device);
+/// let semaphore = TimelineSemaphore::create(&device, 0)?;
+///
+/// let first_graph = RenderGraph::new();
+/// first_graph
+///     .resolve()
+///     .submit_with_semaphores(&mut pool, 0, 0, &[], &[(&semaphore, 1)])?;
+///
+/// // The second graph does not begin executing until the first has finished
+/// let second_graph = RenderGraph::new();
+/// second_graph
+///     .resolve()
+///     .submit_with_semaphores(&mut pool, 0, 0, &[(&semaphore, 1)], &[(&semaphore, 2)])?;
+///
+/// TimelineSemaphore::wait(&semaphore, 2)?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct TimelineSemaphore {
+    device: Arc<Device>,
+    semaphore: vk::Semaphore,
+}
+
+impl TimelineSemaphore {
+    /// Creates a new timeline semaphore on the given device with the given counter value.
+    #[profiling::function]
+    pub fn create(device: &Arc<Device>, initial_value: u64) -> Result<Self, DriverError> {
+        let device = Arc::clone(device);
+
+        if !device.physical_device.features_v1_2.timeline_semaphore {
+            warn!("timeline semaphores are not supported");
+
+            return Err(DriverError::Unsupported);
+        }
+
+        let mut semaphore_type_info = vk::SemaphoreTypeCreateInfo::builder()
+            .semaphore_type(vk::SemaphoreType::TIMELINE)
+            .initial_value(initial_value);
+        let create_info = vk::SemaphoreCreateInfo::builder().push_next(&mut semaphore_type_info);
+        let semaphore = unsafe { device.create_semaphore(&create_info, None) }.map_err(|err| {
+            warn!("{err}");
+
+            DriverError::OutOfMemory
+        })?;
+
+        Ok(Self { device, semaphore })
+    }
+
+    /// Returns `true` once the counter value of this semaphore has reached `value`.
+    ///
+    /// See [`Self::wait`] to block while checking.
+    #[profiling::function]
+    pub fn has_reached(this: &Self, value: u64) -> Result<bool, DriverError> {
+        Ok(Self::value(this)? >= value)
+    }
+
+    /// Sets the counter value of this semaphore from the host.
+    ///
+    /// The value must be greater than the current counter value of the semaphore and less than the
+    /// value of any pending signal operations.
+    #[profiling::function]
+    pub fn signal(this: &Self, value: u64) -> Result<(), DriverError> {
+        let signal_info = vk::SemaphoreSignalInfo::builder()
+            .semaphore(this.semaphore)
+            .value(value);
+
+        unsafe { this.device.signal_semaphore(&signal_info) }.map_err(map_err)
+    }
+
+    /// Returns the current counter value of this semaphore.
+    #[profiling::function]
+    pub fn value(this: &Self) -> Result<u64, DriverError> {
+        unsafe { this.device.get_semaphore_counter_value(this.semaphore) }.map_err(map_err)
+    }
+
+    /// Stalls by blocking the current thread until the counter value of this semaphore has reached
+    /// `value`.
+    ///
+    /// See [`Self::has_reached`] to check without blocking.
+    #[profiling::function]
+    pub fn wait(this: &Self, value: u64) -> Result<(), DriverError> {
+        Self::wait_timeout(this, value, Duration::from_nanos(u64::MAX)).map(|_| ())
+    }
+
+    /// Stalls by blocking the current thread until the counter value of this semaphore has reached
+    /// `value` or `timeout` has elapsed.
+    ///
+    /// Returns `true` if the value was reached.
+    #[profiling::function]
+    pub fn wait_timeout(this: &Self, value: u64, timeout: Duration) -> Result<bool, DriverError> {
+        use std::slice::from_ref;
+
+        let wait_info = vk::SemaphoreWaitInfo::builder()
+            .semaphores(from_ref(&this.semaphore))
+            .values(from_ref(&value));
+        let timeout = timeout.as_nanos().min(u64::MAX as _) as _;
+
+        match unsafe { this.device.wait_semaphores(&wait_info, timeout) } {
+            Ok(_) => Ok(true),
+            Err(err) if err == vk::Result::TIMEOUT => Ok(false),
+            Err(err) => Err(map_err(err)),
+        }
+    }
+}
+
+impl Deref for TimelineSemaphore {
+    type Target = vk::Semaphore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.semaphore
+    }
+}
+
+impl Drop for TimelineSemaphore {
+    #[profiling::function]
+    fn drop(&mut self) {
+        if panicking() {
+            return;
+        }
+
+        unsafe {
+            self.device.destroy_semaphore(self.semaphore, None);
+        }
+    }
+}
+
+fn map_err(err: vk::Result) -> DriverError {
+    if err == vk::Result::ERROR_DEVICE_LOST {
+        error!("Device lost");
+
+        DriverError::InvalidData
+    } else {
+        error!("{err}");
+
+        DriverError::OutOfMemory
+    }
+}
@@ -1,16 +1,20 @@
 use {
     super::{
         AccelerationStructureLeaseNode, AccelerationStructureNode, BufferLeaseNode, BufferNode,
-        ImageLeaseNode, ImageNode, RenderGraph,
+        ImageLeaseNode, ImageNode, QueryPoolLeaseNode, QueryPoolNode, RenderGraph,
     },
     crate::{
         driver::{
-            accel_struct::AccelerationStructure, buffer::Buffer, image::Image,
+            accel_struct::AccelerationStructure,
+            buffer::{Buffer, BufferInfo},
+            image::{Image, ImageInfo},
+            query::QueryPool,
             swapchain::SwapchainImage,
         },
         pool::Lease,
     },
     std::{fmt::Debug, sync::Arc},
+    vk_sync::AccessType,
 };
 
 // #[derive(Debug)]
@@ -75,7 +79,11 @@ pub enum Binding {
     BufferLease(Arc<Lease<Buffer>>, bool),
     Image(Arc<Image>, bool),
     ImageLease(Arc<Lease<Image>>, bool),
+    QueryPool(Arc<QueryPool>, bool),
+    QueryPoolLease(Arc<Lease<QueryPool>>, bool),
     SwapchainImage(Box<SwapchainImage>, bool),
+    TransientBuffer(BufferInfo, Option<Arc<Buffer>>),
+    TransientImage(ImageInfo, Option<Arc<Image>>),
 }
 
 impl Binding {
@@ -91,6 +99,7 @@ impl Binding {
         Some(match self {
             Self::Buffer(binding, _) => binding,
             Self::BufferLease(binding, _) => binding,
+            Self::TransientBuffer(_, Some(binding)) => binding,
             _ => return None,
         })
     }
@@ -100,10 +109,46 @@ impl Binding {
             Self::Image(binding, _) => binding,
             Self::ImageLease(binding, _) => binding,
             Self::SwapchainImage(binding, _) => binding,
+            Self::TransientImage(_, Some(binding)) => binding,
             _ => return None,
         })
     }
 
+    pub(super) fn as_driver_query_pool(&self) -> Option<&QueryPool> {
+        Some(match self {
+            Self::QueryPool(binding, _) => binding,
+            Self::QueryPoolLease(binding, _) => binding,
+            _ => return None,
+        })
+    }
+
+    /// Returns the information of a buffer binding, which is available before transient buffers
+    /// have been created.
+    pub(super) fn buffer_info(&self) -> Option<&BufferInfo> {
+        Some(match self {
+            Self::TransientBuffer(info, _) => info,
+            _ => &self.as_driver_buffer()?.info,
+        })
+    }
+
+    /// Returns the information of an image binding, which is available before transient images
+    /// have been created.
+    pub(super) fn image_info(&self) -> Option<&ImageInfo> {
+        Some(match self {
+            Self::TransientImage(info, _) => info,
+            _ => &self.as_driver_image()?.info,
+        })
+    }
+
+    /// Returns `true` if both bindings are the same kind of resource, such as two images.
+    pub(super) fn is_same_kind(&self, other: &Self) -> bool {
+        (self.buffer_info().is_some() && other.buffer_info().is_some())
+            || (self.image_info().is_some() && other.image_info().is_some())
+            || (self.as_driver_acceleration_structure().is_some()
+                && other.as_driver_acceleration_structure().is_some())
+            || (self.as_driver_query_pool().is_some() && other.as_driver_query_pool().is_some())
+    }
+
     pub(super) fn is_bound(&self) -> bool {
         match self {
             Self::AccelerationStructure(_, is_bound) => *is_bound,
@@ -112,7 +157,35 @@ impl Binding {
             Self::BufferLease(_, is_bound) => *is_bound,
             Self::Image(_, is_bound) => *is_bound,
             Self::ImageLease(_, is_bound) => *is_bound,
+            Self::QueryPool(_, is_bound) => *is_bound,
+            Self::QueryPoolLease(_, is_bound) => *is_bound,
             Self::SwapchainImage(_, is_bound) => *is_bound,
+            Self::TransientBuffer(..) | Self::TransientImage(..) => true,
+        }
+    }
+
+    /// Returns `true` if this is a transient binding which has not yet been created.
+    pub(super) fn is_pending(&self) -> bool {
+        matches!(
+            self,
+            Self::TransientBuffer(_, None) | Self::TransientImage(_, None)
+        )
+    }
+
+    /// Returns the most recent access of the bound resource without modifying it.
+    pub(super) fn peek_access(&self) -> AccessType {
+        if let Some(buffer) = self.as_driver_buffer() {
+            Buffer::peek_access(buffer)
+        } else if let Some(image) = self.as_driver_image() {
+            Image::peek_access(image)
+        } else if let Some(accel_struct) = self.as_driver_acceleration_structure() {
+            AccelerationStructure::peek_access(accel_struct)
+        } else if let Some(query_pool) = self.as_driver_query_pool() {
+            QueryPool::peek_access(query_pool)
+        } else if self.is_pending() {
+            AccessType::Nothing
+        } else {
+            unreachable!();
         }
     }
 
@@ -124,7 +197,12 @@ impl Binding {
             Self::BufferLease(_, is_bound) => is_bound,
             Self::Image(_, is_bound) => is_bound,
             Self::ImageLease(_, is_bound) => is_bound,
+            Self::QueryPool(_, is_bound) => is_bound,
+            Self::QueryPoolLease(_, is_bound) => is_bound,
             Self::SwapchainImage(_, is_bound) => is_bound,
+            Self::TransientBuffer(..) | Self::TransientImage(..) => {
+                unreachable!("transient nodes cannot be unbound")
+            }
         } = false;
     }
 }
@@ -206,6 +284,7 @@ macro_rules! bind {
 bind!(AccelerationStructure);
 bind!(Image);
 bind!(Buffer);
+bind!(QueryPool);
 
 macro_rules! bind_lease {
     ($name:ident) => {
@@ -285,6 +364,7 @@ macro_rules! bind_lease {
 bind_lease!(AccelerationStructure);
 bind_lease!(Image);
 bind_lease!(Buffer);
+bind_lease!(QueryPool);
 
 /// A trait for resources which may be unbound from a `RenderGraph`.
 ///
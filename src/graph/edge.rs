@@ -2,12 +2,13 @@ use {
     super::{
         pass_ref::{PassRef, PipelinePassRef},
         AccelerationStructureLeaseNode, AccelerationStructureNode, BufferLeaseNode, BufferNode,
-        ImageLeaseNode, ImageNode, RenderGraph, Resolver, SwapchainImageNode,
+        ImageLeaseNode, ImageNode, QueryPoolLeaseNode, QueryPoolNode, RenderGraph, Resolver,
+        SwapchainImageNode,
     },
     crate::{
         driver::{
             accel_struct::AccelerationStructure, buffer::Buffer, compute::ComputePipeline,
-            graphic::GraphicPipeline, image::Image, ray_trace::RayTracePipeline,
+            graphic::GraphicPipeline, image::Image, query::QueryPool, ray_trace::RayTracePipeline,
             swapchain::SwapchainImage,
         },
         pool::Lease,
@@ -44,6 +45,10 @@ graph_edge!(Image => ImageNode);
 graph_edge!(Arc<Image> => ImageNode);
 graph_edge!(Lease<Image> => ImageLeaseNode);
 graph_edge!(Arc<Lease<Image>> => ImageLeaseNode);
+graph_edge!(QueryPool => QueryPoolNode);
+graph_edge!(Arc<QueryPool> => QueryPoolNode);
+graph_edge!(Lease<QueryPool> => QueryPoolLeaseNode);
+graph_edge!(Arc<Lease<QueryPool>> => QueryPoolLeaseNode);
 graph_edge!(SwapchainImage => SwapchainImageNode);
 
 // Edges that can be unbound from the render graph:
@@ -54,6 +59,8 @@ graph_edge!(BufferNode => Arc<Buffer>);
 graph_edge!(BufferLeaseNode => Arc<Lease<Buffer>>);
 graph_edge!(ImageNode => Arc<Image>);
 graph_edge!(ImageLeaseNode => Arc<Lease<Image>>);
+graph_edge!(QueryPoolNode => Arc<QueryPool>);
+graph_edge!(QueryPoolLeaseNode => Arc<Lease<QueryPool>>);
 graph_edge!(SwapchainImageNode => SwapchainImage);
 
 macro_rules! graph_edge_borrow {
@@ -70,6 +77,8 @@ graph_edge_borrow!(Arc<Buffer> => BufferNode);
 graph_edge_borrow!(Arc<Lease<Buffer>> => BufferLeaseNode);
 graph_edge_borrow!(Arc<Image> => ImageNode);
 graph_edge_borrow!(Arc<Lease<Image>> => ImageLeaseNode);
+graph_edge_borrow!(Arc<QueryPool> => QueryPoolNode);
+graph_edge_borrow!(Arc<Lease<QueryPool>> => QueryPoolLeaseNode);
 
 // Specialized edges for pipelines added to a pass:
 // Ex: PassRef::bind_pipeline(&mut self, pipeline: X) -> PipelinePassRef
@@ -1,10 +1,12 @@
 use {
     super::{
         AccelerationStructureLeaseNode, AccelerationStructureNode, BufferLeaseNode, BufferNode,
-        ImageLeaseNode, ImageNode, RenderGraph, SwapchainImageNode,
+        ImageLeaseNode, ImageNode, QueryPoolLeaseNode, QueryPoolNode, RenderGraph,
+        SwapchainImageNode, TransientBufferNode, TransientImageNode,
     },
     crate::driver::{
         accel_struct::AccelerationStructureInfo, buffer::BufferInfo, image::ImageInfo,
+        query::QueryPoolInfo,
     },
 };
 
@@ -34,4 +36,24 @@ information!(buffer: BufferNode -> BufferInfo);
 information!(buffer_lease: BufferLeaseNode -> BufferInfo);
 information!(image: ImageNode -> ImageInfo);
 information!(image_lease: ImageLeaseNode -> ImageInfo);
+information!(query_pool: QueryPoolNode -> QueryPoolInfo);
+information!(query_pool_lease: QueryPoolLeaseNode -> QueryPoolInfo);
 information!(swapchain_image: SwapchainImageNode -> ImageInfo);
+
+// Transient nodes have information before the resource has been created
+macro_rules! transient_information {
+    ($name:ident: $src:ident -> $dst:ident) => {
+        paste::paste! {
+            impl Information for $src {
+                type Info = $dst;
+
+                fn get(self, graph: &RenderGraph) -> $dst {
+                    *graph.bindings[self.idx].[<$name _info>]().unwrap()
+                }
+            }
+        }
+    };
+}
+
+transient_information!(buffer: TransientBufferNode -> BufferInfo);
+transient_information!(image: TransientImageNode -> ImageInfo);
@@ -8,12 +8,24 @@ pub mod pass_ref;
 mod binding;
 mod edge;
 mod info;
+mod readback;
+mod report;
 mod resolver;
+mod sub_graph;
 mod swapchain;
+mod template;
+mod upload;
 
 pub use self::{
     binding::{Bind, Unbind},
-    resolver::Resolver,
+    readback::Readback,
+    report::{
+        BarrierKind, BarrierReport, NodeAccessReport, NodeReport, PassReport, PassTimestampReport,
+        ScheduleReport,
+    },
+    resolver::{BarrierCounter, BarrierCounts, PassTimestamps, Resolver, SubmissionToken},
+    sub_graph::{SubGraph, SubGraphOutputs},
+    template::GraphTemplate,
 };
 
 use {
@@ -24,32 +36,37 @@ use {
         node::Node,
         node::{
             AccelerationStructureLeaseNode, AccelerationStructureNode,
-            AnyAccelerationStructureNode, AnyBufferNode, AnyImageNode, BufferLeaseNode, BufferNode,
-            ImageLeaseNode, ImageNode, SwapchainImageNode,
+            AnyAccelerationStructureNode, AnyBufferNode, AnyImageNode, AnyNode, AnyQueryPoolNode,
+            BufferLeaseNode, BufferNode, ImageLeaseNode, ImageNode, QueryPoolLeaseNode,
+            QueryPoolNode, SwapchainImageNode, TransientBufferNode, TransientImageNode,
         },
         pass_ref::{AttachmentIndex, Bindings, Descriptor, PassRef, SubresourceAccess, ViewType},
+        readback::PendingReadback,
+        upload::Upload,
     },
     crate::driver::{
-        buffer::Buffer,
+        accel_struct::AccelerationStructure,
+        buffer::{Buffer, BufferInfo},
         buffer_copy_subresources, buffer_image_copy_subresource,
-        compute::ComputePipeline,
+        compute::{ComputePipeline, DownsamplePipeline},
         device::Device,
-        format_aspect_mask,
+        format_aspect_mask, format_texel_size,
         graphic::{DepthStencilMode, GraphicPipeline},
-        image::{ImageType, ImageViewInfo, SampleCount},
+        image::{ImageInfo, ImageType, ImageViewInfo, SampleCount},
         is_write_access,
         ray_trace::RayTracePipeline,
         render_pass::ResolveMode,
         shader::PipelineDescriptorInfo,
-        DescriptorBindingMap,
+        DescriptorBindingMap, DriverError, StagingRegion,
     },
     ash::vk,
+    log::warn,
     std::{
         cmp::Ord,
         collections::{BTreeMap, HashMap},
         fmt::{Debug, Formatter},
         ops::Range,
-        sync::Arc,
+        sync::{Arc, OnceLock},
     },
     vk_sync::AccessType,
 };
@@ -65,7 +82,7 @@ struct Area {
     y: i32,
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 struct Attachment {
     array_layer_count: u32,
     aspect_mask: vk::ImageAspectFlags,
@@ -165,6 +182,66 @@ struct Execution {
 
     func: Option<ExecutionFunction>,
     pipeline: Option<ExecutionPipeline>,
+
+    /// The node indexes of this graph which correspond to the nodes used by `func`, which differ
+    /// when the execution was appended from a sub-graph.
+    node_indices: Option<Arc<[NodeIndex]>>,
+}
+
+impl Execution {
+    /// Moves the nodes accessed by this execution to the given indexes of another graph.
+    fn remap_nodes(&mut self, node_indices: &Arc<[NodeIndex]>) {
+        self.accesses = self
+            .accesses
+            .drain()
+            .map(|(node_idx, accesses)| (node_indices[node_idx], accesses))
+            .collect();
+
+        for (node_idx, _) in self.bindings.values_mut() {
+            *node_idx = node_indices[*node_idx];
+        }
+
+        for attachment in self
+            .color_attachments
+            .values_mut()
+            .chain(
+                self.color_clears
+                    .values_mut()
+                    .map(|(attachment, _)| attachment),
+            )
+            .chain(self.color_loads.values_mut())
+            .chain(
+                self.color_resolves
+                    .values_mut()
+                    .map(|(attachment, _)| attachment),
+            )
+            .chain(self.color_stores.values_mut())
+            .chain(self.depth_stencil_attachment.as_mut())
+            .chain(
+                self.depth_stencil_clear
+                    .as_mut()
+                    .map(|(attachment, _)| attachment),
+            )
+            .chain(self.depth_stencil_load.as_mut())
+            .chain(
+                self.depth_stencil_resolve
+                    .as_mut()
+                    .map(|(attachment, ..)| attachment),
+            )
+            .chain(self.depth_stencil_store.as_mut())
+        {
+            attachment.target = node_indices[attachment.target];
+        }
+
+        // Nested sub-graphs map the nodes of the function through each graph
+        self.node_indices = Some(match self.node_indices.take() {
+            Some(prev_node_indices) => prev_node_indices
+                .iter()
+                .map(|&node_idx| node_indices[node_idx])
+                .collect(),
+            None => Arc::clone(node_indices),
+        });
+    }
 }
 
 impl Debug for Execution {
@@ -192,6 +269,10 @@ impl Debug for Execution {
 
 struct ExecutionFunction(ExecFn);
 
+// SAFETY: The function may only be called by value, so a shared reference cannot access it; this
+// allows executions to be shared with the threads which record secondary command buffers.
+unsafe impl Sync for ExecutionFunction {}
+
 #[derive(Debug)]
 enum ExecutionPipeline {
     Compute(Arc<ComputePipeline>),
@@ -251,10 +332,21 @@ impl Clone for ExecutionPipeline {
     }
 }
 
+/// A 32-bit value stored in a buffer node which predicates the commands of a pass.
+///
+/// See [`PassRef::set_condition`].
+#[derive(Clone, Copy, Debug)]
+struct Condition {
+    node_idx: NodeIndex,
+    offset: vk::DeviceSize,
+}
+
 #[derive(Debug)]
 struct Pass {
+    condition: Option<Condition>,
     execs: Vec<Execution>,
     name: String,
+    queue: QueueType,
     render_area: Option<Area>,
 }
 
@@ -269,6 +361,31 @@ impl Pass {
     }
 }
 
+/// Specifies the kind of device queue which executes a pass.
+///
+/// See [`PassRef::set_queue`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum QueueType {
+    /// The queue the graph is submitted to.
+    ///
+    /// Graphic passes always execute on this queue.
+    #[default]
+    Graphics,
+
+    /// A queue family which supports compute operations but not graphic operations, allowing the
+    /// pass to overlap with graphic work.
+    ///
+    /// If the device has no such queue family the pass executes on the submitted queue.
+    AsyncCompute,
+
+    /// A queue family which supports only transfer operations, allowing the pass to overlap with
+    /// graphic and compute work.
+    ///
+    /// Passes which bind a pipeline, or any pass when the device has no such queue family, execute
+    /// on the async compute queue, if available, or the submitted queue.
+    Transfer,
+}
+
 /// A composable graph of render pass operations.
 ///
 /// `RenderGraph` instances are are intended for one-time use.
@@ -280,7 +397,10 @@ impl Pass {
 #[derive(Debug)]
 pub struct RenderGraph {
     bindings: Vec<Binding>,
+    dependencies: Vec<(NodeIndex, SubmissionToken)>,
     passes: Vec<Pass>,
+    readbacks: Vec<(NodeIndex, PendingReadback)>,
+    uploads: Vec<Upload>,
 
     /// Set to true (when in debug mode) in order to get a breakpoint hit where you want.
     #[cfg(debug_assertions)]
@@ -292,19 +412,151 @@ impl RenderGraph {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         let bindings = vec![];
+        let dependencies = vec![];
         let passes = vec![];
+        let readbacks = vec![];
+        let uploads = vec![];
 
         #[cfg(debug_assertions)]
         let debug = false;
 
         Self {
             bindings,
+            dependencies,
             passes,
+            readbacks,
+            uploads,
             #[cfg(debug_assertions)]
             debug,
         }
     }
 
+    /// Appends the nodes and passes of a sub-graph to this graph.
+    ///
+    /// Each of the given `inputs` names an input of the sub-graph and the node of this graph which
+    /// replaces it; inputs which are not given keep the node declared by the sub-graph. Resources
+    /// bound to both graphs share the same node. Returns the nodes of this graph which correspond
+    /// to the outputs of the sub-graph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an input is not declared by the sub-graph or if the given node is a different kind
+    /// of resource than the input.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use ash::vk;
+    /// # use screen_13::driver::buffer::BufferInfo;
+    /// # use screen_13::graph::{RenderGraph, SubGraph};
+    /// # use screen_13::graph::node::Node;
+    /// # let info = BufferInfo::device_mem(1024, vk::BufferUsageFlags::TRANSFER_DST);
+    /// fn clear_feature(info: BufferInfo) -> SubGraph {
+    ///     let mut sub_graph = SubGraph::new();
+    ///     let buf = sub_graph.input_buffer("target", info);
+    ///     sub_graph.fill_buffer(buf, 0);
+    ///     sub_graph.set_output("cleared", buf);
+    ///     sub_graph
+    /// }
+    ///
+    /// let mut my_graph = RenderGraph::new();
+    /// let my_buf = my_graph.create_buffer(info);
+    /// let outputs = my_graph.append(clear_feature(info), [("target", my_buf.into())]);
+    ///
+    /// assert_eq!(outputs.buffer("cleared").unwrap().index(), my_buf.index());
+    /// ```
+    #[profiling::function]
+    pub fn append<'a>(
+        &mut self,
+        sub_graph: SubGraph,
+        inputs: impl IntoIterator<Item = (&'a str, AnyNode)>,
+    ) -> SubGraphOutputs {
+        let (graph, declared_inputs, outputs) = SubGraph::into_parts(sub_graph);
+
+        let mut input_nodes = HashMap::new();
+        for (name, node) in inputs {
+            let sub_node_idx = *declared_inputs
+                .get(name)
+                .unwrap_or_else(|| panic!("sub-graph input `{name}` is not declared"));
+
+            assert!(
+                graph.bindings[sub_node_idx].is_same_kind(&self.bindings[node.index()]),
+                "sub-graph input `{name}` is a different kind of node"
+            );
+
+            input_nodes.insert(sub_node_idx, node);
+        }
+
+        let node_indices = graph
+            .bindings
+            .into_iter()
+            .enumerate()
+            .map(|(sub_node_idx, binding)| {
+                input_nodes
+                    .get(&sub_node_idx)
+                    .map(|node| node.index())
+                    .unwrap_or_else(|| self.append_binding(binding))
+            })
+            .collect::<Arc<[_]>>();
+
+        self.dependencies.extend(
+            graph
+                .dependencies
+                .into_iter()
+                .map(|(node_idx, token)| (node_indices[node_idx], token)),
+        );
+
+        for mut pass in graph.passes {
+            if let Some(condition) = pass.condition.as_mut() {
+                condition.node_idx = node_indices[condition.node_idx];
+            }
+
+            for exec in &mut pass.execs {
+                exec.remap_nodes(&node_indices);
+            }
+
+            self.passes.push(pass);
+        }
+
+        self.readbacks.extend(
+            graph
+                .readbacks
+                .into_iter()
+                .map(|(node_idx, readback)| (node_indices[node_idx], readback)),
+        );
+        self.uploads.extend(graph.uploads);
+
+        SubGraphOutputs::remap(outputs, |node| {
+            input_nodes
+                .get(&node.index())
+                .copied()
+                .unwrap_or_else(|| node.with_index(node_indices[node.index()]))
+        })
+    }
+
+    // Moves a binding of a sub-graph into this graph, returning the existing node of resources
+    // which are already bound
+    fn append_binding(&mut self, binding: Binding) -> NodeIndex {
+        match binding {
+            Binding::AccelerationStructure(binding, _) => binding.bind(self).index(),
+            Binding::AccelerationStructureLease(binding, _) => binding.bind(self).index(),
+            Binding::Buffer(binding, _) => binding.bind(self).index(),
+            Binding::BufferLease(binding, _) => binding.bind(self).index(),
+            Binding::Image(binding, _) => binding.bind(self).index(),
+            Binding::ImageLease(binding, _) => binding.bind(self).index(),
+            Binding::QueryPool(binding, _) => binding.bind(self).index(),
+            Binding::QueryPoolLease(binding, _) => binding.bind(self).index(),
+            binding @ (Binding::SwapchainImage(..)
+            | Binding::TransientBuffer(..)
+            | Binding::TransientImage(..)) => {
+                self.bindings.push(binding);
+                self.bindings.len() - 1
+            }
+        }
+    }
+
     /// Begins a new pass.
     pub fn begin_pass(&mut self, name: impl AsRef<str>) -> PassRef<'_> {
         PassRef::new(self, name.as_ref().to_string())
@@ -321,6 +573,32 @@ impl RenderGraph {
         binding.bind(self)
     }
 
+    /// Binds a Vulkan acceleration structure, buffer, or image to this graph and declares that the
+    /// graph must wait for the work represented by `token` before accessing it.
+    ///
+    /// The wait happens on the GPU when this graph is submitted using [`Resolver::submit`] or one
+    /// of its variants, so the work may have been submitted to a different queue or from a
+    /// different thread. See [`Resolver::submit_with_token`].
+    ///
+    /// When the passes which access the node are instead recorded into a caller-owned command
+    /// buffer, using [`Resolver::record_node`] or [`Resolver::record_unscheduled_passes`], the
+    /// current thread blocks until the work has executed before recording them.
+    pub fn bind_node_after<B>(
+        &mut self,
+        binding: B,
+        token: &SubmissionToken,
+    ) -> <B as Edge<Self>>::Result
+    where
+        B: Edge<Self>,
+        B: for<'a> Bind<&'a mut Self, <B as Edge<Self>>::Result>,
+        <B as Edge<Self>>::Result: Node,
+    {
+        let node = binding.bind(self);
+        self.dependencies.push((node.index(), token.clone()));
+
+        node
+    }
+
     /// Copy an image, potentially performing format conversion.
     pub fn blit_image(
         &mut self,
@@ -487,6 +765,98 @@ impl RenderGraph {
             .submit_pass()
     }
 
+    /// Creates an acceleration structure of `compacted_size` bytes and copies a compacted version
+    /// of the given acceleration structure into it, returning the node of the new acceleration
+    /// structure.
+    ///
+    /// The given acceleration structure must have been built using
+    /// [`vk::BuildAccelerationStructureFlagsKHR::ALLOW_COMPACTION`]. Its compacted size may be
+    /// queried using [`Self::write_accel_struct_compacted_size`]. Once this graph has been
+    /// submitted the given acceleration structure may be dropped; the new acceleration structure
+    /// may be retrieved using [`Self::unbind_node`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given acceleration structure is not currently bound.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::accel_struct::{AccelerationStructure, AccelerationStructureInfo};
+    /// # use screen_13::driver::query::{QueryPool, QueryPoolInfo};
+    /// # use screen_13::graph::RenderGraph;
+    /// # use screen_13::pool::lazy::LazyPool;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let mut pool = LazyPool::new(&device);
+    /// # let info = AccelerationStructureInfo::blas(1024);
+    /// # let my_blas = Arc::new(AccelerationStructure::create(&device, info)?);
+    /// let query_pool = Arc::new(QueryPool::create(&device, QueryPoolInfo::accel_struct_compacted_size(1))?);
+    ///
+    /// // After building my_blas using the ALLOW_COMPACTION flag
+    /// let mut my_graph = RenderGraph::new();
+    /// let blas_node = my_graph.bind_node(&my_blas);
+    /// let query_pool_node = my_graph.bind_node(&query_pool);
+    /// my_graph.reset_queries(query_pool_node, 0..1)
+    ///         .write_accel_struct_compacted_size(blas_node, query_pool_node, 0);
+    /// my_graph.resolve().submit(&mut pool, 0, 0)?.wait_until_executed()?;
+    ///
+    /// let mut compacted_size = [0];
+    /// QueryPool::results(&query_pool, 0, 1, &mut compacted_size)?;
+    ///
+    /// let mut my_graph = RenderGraph::new();
+    /// let blas_node = my_graph.bind_node(my_blas);
+    /// let compacted_blas_node = my_graph.compact_accel_struct(blas_node, compacted_size[0])?;
+    /// let my_compacted_blas = my_graph.unbind_node(compacted_blas_node);
+    /// my_graph.resolve().submit(&mut pool, 0, 0)?;
+    ///
+    /// assert_eq!(my_compacted_blas.info.size, compacted_size[0]);
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    pub fn compact_accel_struct(
+        &mut self,
+        accel_struct_node: impl Into<AnyAccelerationStructureNode>,
+        compacted_size: vk::DeviceSize,
+    ) -> Result<AccelerationStructureNode, DriverError> {
+        let src_node = accel_struct_node.into();
+        let dst = {
+            let src = self.bindings[src_node.index()]
+                .as_driver_acceleration_structure()
+                .unwrap();
+            let info = src.info.to_builder().size(compacted_size).build();
+
+            AccelerationStructure::create(&src.device, info)?
+        };
+        let dst_node = self.bind_node(dst);
+
+        self.begin_pass("compact acceleration structure")
+            .access_node(src_node, AccessType::AccelerationStructureBuildRead)
+            .access_node(dst_node, AccessType::AccelerationStructureBuildWrite)
+            .record_cmd_buf(move |device, cmd_buf, bindings| unsafe {
+                device
+                    .accel_struct_ext
+                    .as_ref()
+                    .expect("ray tracing feature must be enabled")
+                    .cmd_copy_acceleration_structure(
+                        cmd_buf,
+                        &vk::CopyAccelerationStructureInfoKHR::builder()
+                            .src(*bindings[src_node])
+                            .dst(*bindings[dst_node])
+                            .mode(vk::CopyAccelerationStructureModeKHR::COMPACT),
+                    );
+            })
+            .submit_pass();
+
+        Ok(dst_node)
+    }
+
     /// Copy data between buffers
     pub fn copy_buffer(
         &mut self,
@@ -773,6 +1143,132 @@ impl RenderGraph {
             .submit_pass()
     }
 
+    /// Copy the results of a range of queries into a buffer, starting at `dst_offset` bytes.
+    ///
+    /// The results of each query are tightly packed: every query writes
+    /// [`QueryPoolInfo::query_result_count`](crate::driver::query::QueryPoolInfo::query_result_count)
+    /// values, plus one availability value if `flags` contains `WITH_AVAILABILITY`, each of which
+    /// is 64 bits if `flags` contains `TYPE_64` and 32 bits otherwise.
+    #[profiling::function]
+    pub fn copy_query_results(
+        &mut self,
+        src_node: impl Into<AnyQueryPoolNode>,
+        queries: Range<u32>,
+        dst_node: impl Into<AnyBufferNode>,
+        dst_offset: vk::DeviceSize,
+        flags: vk::QueryResultFlags,
+    ) -> &mut Self {
+        let src_node = src_node.into();
+        let dst_node = dst_node.into();
+
+        let src_info = self.node_info(src_node);
+        let value_count = src_info.query_result_count()
+            + flags.contains(vk::QueryResultFlags::WITH_AVAILABILITY) as u32;
+        let value_size = if flags.contains(vk::QueryResultFlags::TYPE_64) {
+            8
+        } else {
+            4
+        };
+        let stride = (value_count * value_size) as vk::DeviceSize;
+        let dst_subresource =
+            dst_offset..dst_offset + stride * (queries.end - queries.start) as vk::DeviceSize;
+
+        debug_assert!(
+            queries.end <= src_info.query_count,
+            "Queries must be within the range of the query pool"
+        );
+
+        self.begin_pass("copy query results")
+            .access_node(src_node, AccessType::TransferRead)
+            .access_node_subrange(dst_node, AccessType::TransferWrite, dst_subresource)
+            .record_cmd_buf(move |device, cmd_buf, bindings| unsafe {
+                device.cmd_copy_query_pool_results(
+                    cmd_buf,
+                    *bindings[src_node],
+                    queries.start,
+                    queries.end - queries.start,
+                    *bindings[dst_node],
+                    dst_offset,
+                    stride,
+                    flags,
+                );
+            })
+            .submit_pass()
+    }
+
+    /// Declares a buffer which only exists while the passes of this graph execute.
+    ///
+    /// The buffer is created when the first pass which accesses it is recorded. The usage flags of
+    /// `info` are extended with the usage required by the passes of this graph which access the
+    /// buffer, so they may be left empty. The contents of the buffer are undefined until it is
+    /// written.
+    ///
+    /// Buffers which are not mappable share device memory with the other buffers and images created
+    /// by this graph which are not accessed by the same passes, or by passes which execute in
+    /// between. The memory and buffer objects are cached by the command buffer leased from the pool
+    /// given to [`Resolver::submit`], or a similar function, and are reused by later graphs once the
+    /// submitted commands have executed.
+    ///
+    /// Transient nodes cannot be unbound.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+    /// # use screen_13::graph::RenderGraph;
+    /// # use screen_13::pool::lazy::LazyPool;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let mut pool = LazyPool::new(&device);
+    /// # let info = BufferInfo::device_mem(1024, vk::BufferUsageFlags::TRANSFER_DST);
+    /// # let buf = Buffer::create(&device, info)?;
+    /// let mut my_graph = RenderGraph::new();
+    /// let my_buf = my_graph.bind_node(buf);
+    ///
+    /// // These buffers are created with TRANSFER_SRC and TRANSFER_DST usage and, because they are
+    /// // not used at the same time, they use the same memory
+    /// let info = BufferInfo::device_mem(1024, vk::BufferUsageFlags::empty());
+    /// let first_buf = my_graph.create_buffer(info);
+    /// let second_buf = my_graph.create_buffer(info);
+    ///
+    /// my_graph
+    ///     .fill_buffer(first_buf, 0x01)
+    ///     .copy_buffer(first_buf, my_buf)
+    ///     .fill_buffer(second_buf, 0x02)
+    ///     .copy_buffer(second_buf, my_buf);
+    ///
+    /// my_graph.resolve().submit(&mut pool, 0, 0)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn create_buffer(&mut self, info: impl Into<BufferInfo>) -> TransientBufferNode {
+        let node = TransientBufferNode::new(self.bindings.len());
+        self.bindings
+            .push(Binding::TransientBuffer(info.into(), None));
+
+        node
+    }
+
+    /// Declares an image which only exists while the passes of this graph execute.
+    ///
+    /// The usage flags of `info` are extended with the usage required by the passes of this graph
+    /// which access the image, so they may be left empty. Images share device memory with the other
+    /// resources created by this graph which are not used at the same time.
+    ///
+    /// See [`Self::create_buffer`].
+    pub fn create_image(&mut self, info: impl Into<ImageInfo>) -> TransientImageNode {
+        let node = TransientImageNode::new(self.bindings.len());
+        self.bindings
+            .push(Binding::TransientImage(info.into(), None));
+
+        node
+    }
+
     /// Fill a region of a buffer with a fixed value.
     pub fn fill_buffer(&mut self, buffer_node: impl Into<AnyBufferNode>, data: u32) -> &mut Self {
         let buffer_node = buffer_node.into();
@@ -808,6 +1304,169 @@ impl RenderGraph {
             .submit_pass()
     }
 
+    /// Fills every mip level of an image after the first by downsampling the previous mip level.
+    ///
+    /// Every array layer is blitted using linear filtering, or nearest filtering for depth and
+    /// stencil formats; the image must have been created with `TRANSFER_SRC` and `TRANSFER_DST`
+    /// usage. Color images whose format does not support linear-filtered blits, according to
+    /// [`Device::format_properties`], are instead downsampled using a compute shader; those images
+    /// must be two-dimensional, must have been created with `SAMPLED` and `STORAGE` usage, the
+    /// format must support sampled and storage images and the device must support
+    /// `VK_KHR_push_descriptor` and `shaderStorageImageWriteWithoutFormat`.
+    ///
+    /// Once the pass has executed every mip level of the image has been accessed using
+    /// [`AccessType::TransferRead`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DriverError::Unsupported`] if the mip levels of the image cannot be generated
+    /// using either method.
+    #[profiling::function]
+    pub fn generate_mipmaps(
+        &mut self,
+        device: &Device,
+        image_node: impl Into<AnyImageNode>,
+    ) -> Result<&mut Self, DriverError> {
+        let image_node = image_node.into();
+        let info = self.node_info(image_node);
+
+        if info.mip_level_count < 2 {
+            return Ok(self);
+        }
+
+        if !info
+            .usage
+            .contains(vk::ImageUsageFlags::TRANSFER_SRC | vk::ImageUsageFlags::TRANSFER_DST)
+        {
+            warn!("generating mipmaps requires transfer source and destination usage");
+
+            return Err(DriverError::Unsupported);
+        }
+
+        let features = Device::format_properties(device, info.fmt);
+        let features = if info.tiling == vk::ImageTiling::LINEAR {
+            features.linear_tiling_features
+        } else {
+            features.optimal_tiling_features
+        };
+
+        // Depth and stencil images may only be blitted using nearest filtering
+        let filter = if !features
+            .contains(vk::FormatFeatureFlags::BLIT_SRC | vk::FormatFeatureFlags::BLIT_DST)
+        {
+            None
+        } else if format_aspect_mask(info.fmt) != vk::ImageAspectFlags::COLOR {
+            Some(vk::Filter::NEAREST)
+        } else if features.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR) {
+            Some(vk::Filter::LINEAR)
+        } else {
+            None
+        };
+
+        if filter.is_none() {
+            DownsamplePipeline::pipeline(device, info)?;
+        }
+
+        Ok(self.record_mipmaps(image_node, filter))
+    }
+
+    // Records the pass which generates mip levels using blits with the given filter, or using the
+    // downsample compute pipeline if there is no filter
+    fn record_mipmaps(
+        &mut self,
+        image_node: AnyImageNode,
+        filter: Option<vk::Filter>,
+    ) -> &mut Self {
+        let info = self.node_info(image_node);
+
+        let aspect_mask = format_aspect_mask(info.fmt);
+        let layer_count = info.array_layer_count();
+        let mip_access_range = |mip_level: u32| ImageViewInfo {
+            array_layer_count: Some(layer_count),
+            base_mip_level: mip_level,
+            mip_level_count: Some(1),
+            ..info.default_view_info()
+        };
+        let mip_offset = |mip_level: u32| vk::Offset3D {
+            x: (info.width >> mip_level).max(1) as _,
+            y: (info.height >> mip_level).max(1) as _,
+            z: (info.depth >> mip_level).max(1) as _,
+        };
+
+        // Every mip level starts as a transfer destination; each is then read to write the next
+        let mut pass = self
+            .begin_pass("generate mipmaps")
+            .access_node_subrange(
+                image_node,
+                AccessType::TransferWrite,
+                ImageViewInfo {
+                    array_layer_count: Some(layer_count),
+                    ..info.default_view_info()
+                },
+            )
+            .record_cmd_buf(|_, _, _| ());
+
+        for mip_level in 1..info.mip_level_count {
+            let region = vk::ImageBlit {
+                src_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask,
+                    mip_level: mip_level - 1,
+                    base_array_layer: 0,
+                    layer_count,
+                },
+                src_offsets: [vk::Offset3D::default(), mip_offset(mip_level - 1)],
+                dst_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask,
+                    mip_level,
+                    base_array_layer: 0,
+                    layer_count,
+                },
+                dst_offsets: [vk::Offset3D::default(), mip_offset(mip_level)],
+            };
+
+            pass = pass
+                .access_node_subrange(
+                    image_node,
+                    AccessType::TransferRead,
+                    mip_access_range(mip_level - 1),
+                )
+                .access_node_subrange(
+                    image_node,
+                    AccessType::TransferWrite,
+                    mip_access_range(mip_level),
+                )
+                .record_cmd_buf(move |device, cmd_buf, bindings| {
+                    let image = &bindings[image_node];
+                    if let Some(filter) = filter {
+                        unsafe {
+                            device.cmd_blit_image(
+                                cmd_buf,
+                                **image,
+                                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                                **image,
+                                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                                &[region],
+                                filter,
+                            );
+                        }
+                    } else {
+                        DownsamplePipeline::record(device, cmd_buf, image, mip_level)
+                            .unwrap_or_else(|err| {
+                                panic!("Unable to generate mip level {mip_level}: {err}")
+                            });
+                    }
+                });
+        }
+
+        pass.access_node_subrange(
+            image_node,
+            AccessType::TransferRead,
+            mip_access_range(info.mip_level_count - 1),
+        )
+        .record_cmd_buf(|_, _, _| ())
+        .submit_pass()
+    }
+
     #[profiling::function]
     pub(super) fn last_write(&self, node: impl Node) -> Option<AccessType> {
         let node_idx = node.index();
@@ -864,6 +1523,121 @@ impl RenderGraph {
         node.get(self)
     }
 
+    // Adds a pass which makes the staging buffer of a readback visible to the host
+    fn push_readback(&mut self, staging_node: TransientBufferNode, len: usize) -> Readback {
+        self.begin_pass("readback")
+            .access_node(staging_node, AccessType::HostRead)
+            .record_cmd_buf(|_, _, _| ())
+            .submit_pass();
+
+        let (readback, pending) = Readback::new(len);
+        self.readbacks.push((staging_node.index(), pending));
+
+        readback
+    }
+
+    // Adds data which is copied into device staging memory when the graph is resolved
+    fn push_upload(
+        &mut self,
+        data: impl AsRef<[u8]> + 'static + Send,
+        alignment: vk::DeviceSize,
+    ) -> Arc<OnceLock<StagingRegion>> {
+        let (upload, staging) = Upload::new(data, alignment);
+        self.uploads.push(upload);
+
+        staging
+    }
+
+    /// Copies the contents of a buffer into host-visible memory which may be read once the graph
+    /// has executed.
+    ///
+    /// See [`Readback`].
+    pub fn read_buffer(&mut self, buffer_node: impl Into<AnyBufferNode>) -> Readback {
+        let buffer_node = buffer_node.into();
+        let size = self.node_info(buffer_node).size;
+
+        self.read_buffer_region(buffer_node, 0..size)
+    }
+
+    /// Copies a range of bytes of a buffer into host-visible memory which may be read once the
+    /// graph has executed.
+    ///
+    /// See [`Readback`].
+    pub fn read_buffer_region(
+        &mut self,
+        buffer_node: impl Into<AnyBufferNode>,
+        region: Range<vk::DeviceSize>,
+    ) -> Readback {
+        let size = region.end - region.start;
+        let staging_node =
+            self.create_buffer(BufferInfo::host_mem(size, vk::BufferUsageFlags::empty()));
+
+        self.copy_buffer_region(
+            buffer_node,
+            staging_node,
+            vk::BufferCopy {
+                src_offset: region.start,
+                dst_offset: 0,
+                size,
+            },
+        );
+
+        self.push_readback(staging_node, size as _)
+    }
+
+    /// Copies the texels of the first mip level and array layer of an image into host-visible
+    /// memory which may be read once the graph has executed.
+    ///
+    /// Texels are tightly packed in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the image has a compressed format or both depth and stencil aspects.
+    ///
+    /// See [`Readback`].
+    pub fn read_image(&mut self, image_node: impl Into<AnyImageNode>) -> Readback {
+        let image_node = image_node.into();
+        let info = self.node_info(image_node);
+        let texel_size = format_texel_size(info.fmt)
+            .unwrap_or_else(|| panic!("Unsupported readback format {:?}", info.fmt));
+        let size = info.width as vk::DeviceSize
+            * info.height as vk::DeviceSize
+            * info.depth as vk::DeviceSize
+            * texel_size;
+        let staging_node =
+            self.create_buffer(BufferInfo::host_mem(size, vk::BufferUsageFlags::empty()));
+
+        self.copy_image_to_buffer(image_node, staging_node);
+        self.push_readback(staging_node, size as _)
+    }
+
+    /// Resets a range of queries so that they may be used by later passes.
+    #[profiling::function]
+    pub fn reset_queries(
+        &mut self,
+        query_pool_node: impl Into<AnyQueryPoolNode>,
+        queries: Range<u32>,
+    ) -> &mut Self {
+        let query_pool_node = query_pool_node.into();
+
+        debug_assert!(
+            queries.end <= self.node_info(query_pool_node).query_count,
+            "Queries must be within the range of the query pool"
+        );
+
+        self.begin_pass("reset queries")
+            .access_node(query_pool_node, AccessType::TransferWrite)
+            .record_cmd_buf(move |device, cmd_buf, bindings| unsafe {
+                device.cmd_reset_query_pool(
+                    cmd_buf,
+                    *bindings[query_pool_node],
+                    queries.start,
+                    queries.end - queries.start,
+                );
+            })
+            .submit_pass()
+    }
+
     /// Finalizes the graph and provides an object with functions for submitting the resulting
     /// commands.
     #[profiling::function]
@@ -887,7 +1661,9 @@ impl RenderGraph {
         node.unbind(self)
     }
 
-    /// Note: `data` must not exceed 65536 bytes.
+    /// Copies data into a buffer.
+    ///
+    /// See [`Self::update_buffer_offset`].
     pub fn update_buffer(
         &mut self,
         buffer_node: impl Into<AnyBufferNode>,
@@ -896,7 +1672,12 @@ impl RenderGraph {
         self.update_buffer_offset(buffer_node, 0, data)
     }
 
-    /// Note: `data` must not exceed 65536 bytes.
+    /// Copies data into a buffer, starting at `offset`.
+    ///
+    /// Data of up to 65536 bytes, where both the size of the data and `offset` are multiples of
+    /// four, is recorded inline using `vkCmdUpdateBuffer`. Other data is copied into the staging
+    /// memory of the device when the graph is resolved and then copied into the buffer; the staging
+    /// memory is reused once the graph has executed.
     #[profiling::function]
     pub fn update_buffer_offset(
         &mut self,
@@ -904,15 +1685,360 @@ impl RenderGraph {
         offset: vk::DeviceSize,
         data: impl AsRef<[u8]> + 'static + Send,
     ) -> &mut Self {
+        const MAX_INLINE_SIZE: usize = 65536;
+
         let buffer_node = buffer_node.into();
         let buffer_info = self.node_info(buffer_node);
         let buffer_access_range = 0..buffer_info.size;
+        let size = data.as_ref().len();
+
+        debug_assert!(
+            offset + size as vk::DeviceSize <= buffer_info.size,
+            "Data must be within the range of the buffer"
+        );
+
+        if size <= MAX_INLINE_SIZE && size & 3 == 0 && offset & 3 == 0 {
+            return self
+                .begin_pass("update buffer")
+                .access_node_subrange(buffer_node, AccessType::TransferWrite, buffer_access_range)
+                .record_cmd_buf(move |device, cmd_buf, bindings| unsafe {
+                    device.cmd_update_buffer(
+                        cmd_buf,
+                        *bindings[buffer_node],
+                        offset,
+                        data.as_ref(),
+                    );
+                })
+                .submit_pass();
+        }
+
+        let staging = self.push_upload(data, 4);
 
         self.begin_pass("update buffer")
             .access_node_subrange(buffer_node, AccessType::TransferWrite, buffer_access_range)
             .record_cmd_buf(move |device, cmd_buf, bindings| unsafe {
-                device.cmd_update_buffer(cmd_buf, *bindings[buffer_node], offset, data.as_ref());
+                let staging = staging.get().unwrap();
+
+                device.cmd_copy_buffer(
+                    cmd_buf,
+                    staging.buffer,
+                    *bindings[buffer_node],
+                    &[vk::BufferCopy {
+                        src_offset: staging.offset,
+                        dst_offset: offset,
+                        size: size as _,
+                    }],
+                );
+            })
+            .submit_pass()
+    }
+
+    /// Copies tightly packed texels, in row-major order, into the first mip level and array layer
+    /// of an image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the image has a compressed format or both depth and stencil aspects.
+    ///
+    /// See [`Self::update_image_region`].
+    pub fn update_image(
+        &mut self,
+        image_node: impl Into<AnyImageNode>,
+        data: impl AsRef<[u8]> + 'static + Send,
+    ) -> &mut Self {
+        let image_node = image_node.into();
+        let info = self.node_info(image_node);
+
+        debug_assert_eq!(
+            data.as_ref().len() as vk::DeviceSize,
+            info.width as vk::DeviceSize
+                * info.height as vk::DeviceSize
+                * info.depth as vk::DeviceSize
+                * format_texel_size(info.fmt).unwrap_or_default(),
+            "Data must contain every texel of the image"
+        );
+
+        self.update_image_region(
+            image_node,
+            data,
+            vk::BufferImageCopy {
+                buffer_offset: 0,
+                buffer_row_length: info.width,
+                buffer_image_height: info.height,
+                image_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask: format_aspect_mask(info.fmt),
+                    mip_level: 0,
+                    base_array_layer: 0,
+                    layer_count: 1,
+                },
+                image_offset: Default::default(),
+                image_extent: vk::Extent3D {
+                    depth: info.depth,
+                    height: info.height,
+                    width: info.width,
+                },
+            },
+        )
+    }
+
+    /// Copies data into a region of an image.
+    ///
+    /// The data is copied into the staging memory of the device when the graph is resolved and
+    /// then copied into the image as if `data` were a buffer described by `region`, so
+    /// `region.buffer_offset` must be a multiple of four and of the texel size of the image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the image has a compressed format or both depth and stencil aspects.
+    #[profiling::function]
+    pub fn update_image_region(
+        &mut self,
+        image_node: impl Into<AnyImageNode>,
+        data: impl AsRef<[u8]> + 'static + Send,
+        region: vk::BufferImageCopy,
+    ) -> &mut Self {
+        let image_node = image_node.into();
+        let info = self.node_info(image_node);
+        let texel_size = format_texel_size(info.fmt)
+            .unwrap_or_else(|| panic!("Unsupported update format {:?}", info.fmt));
+        let image_access_range = info.default_view_info();
+
+        // Buffer to image copies must start at a multiple of both four and the texel size; the
+        // greatest common divisor of four and the texel size is its largest power-of-two factor
+        // up to four
+        let alignment = (texel_size * 4) >> texel_size.trailing_zeros().min(2);
+
+        debug_assert_eq!(
+            region.buffer_offset % alignment,
+            0,
+            "Buffer offset must be a multiple of four and of the texel size"
+        );
+
+        let staging = self.push_upload(data, alignment);
+
+        self.begin_pass("update image")
+            .access_node_subrange(image_node, AccessType::TransferWrite, image_access_range)
+            .record_cmd_buf(move |device, cmd_buf, bindings| unsafe {
+                let staging = staging.get().unwrap();
+
+                device.cmd_copy_buffer_to_image(
+                    cmd_buf,
+                    staging.buffer,
+                    *bindings[image_node],
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &[vk::BufferImageCopy {
+                        buffer_offset: staging.offset + region.buffer_offset,
+                        ..region
+                    }],
+                );
+            })
+            .submit_pass()
+    }
+
+    /// Writes the compacted size, in bytes, of an acceleration structure into a query of a pool
+    /// created using [`QueryPoolInfo::accel_struct_compacted_size`].
+    ///
+    /// The acceleration structure must have been built using
+    /// [`vk::BuildAccelerationStructureFlagsKHR::ALLOW_COMPACTION`] and the query must have been
+    /// reset. See [`Self::compact_accel_struct`].
+    ///
+    /// [`QueryPoolInfo::accel_struct_compacted_size`]: crate::driver::query::QueryPoolInfo::accel_struct_compacted_size
+    pub fn write_accel_struct_compacted_size(
+        &mut self,
+        accel_struct_node: impl Into<AnyAccelerationStructureNode>,
+        query_pool_node: impl Into<AnyQueryPoolNode>,
+        query: u32,
+    ) -> &mut Self {
+        self.write_accel_struct_property(
+            accel_struct_node,
+            query_pool_node,
+            query,
+            vk::QueryType::ACCELERATION_STRUCTURE_COMPACTED_SIZE_KHR,
+        )
+    }
+
+    /// Writes the serialized size, in bytes, of an acceleration structure into a query of a pool
+    /// created using [`QueryPoolInfo::accel_struct_serialization_size`].
+    ///
+    /// The query must have been reset. See
+    /// [`Acceleration::serialize_structure`](pass_ref::Acceleration::serialize_structure).
+    ///
+    /// [`QueryPoolInfo::accel_struct_serialization_size`]: crate::driver::query::QueryPoolInfo::accel_struct_serialization_size
+    #[profiling::function]
+    pub fn write_accel_struct_serialization_size(
+        &mut self,
+        accel_struct_node: impl Into<AnyAccelerationStructureNode>,
+        query_pool_node: impl Into<AnyQueryPoolNode>,
+        query: u32,
+    ) -> &mut Self {
+        self.write_accel_struct_property(
+            accel_struct_node,
+            query_pool_node,
+            query,
+            vk::QueryType::ACCELERATION_STRUCTURE_SERIALIZATION_SIZE_KHR,
+        )
+    }
+
+    #[profiling::function]
+    fn write_accel_struct_property(
+        &mut self,
+        accel_struct_node: impl Into<AnyAccelerationStructureNode>,
+        query_pool_node: impl Into<AnyQueryPoolNode>,
+        query: u32,
+        ty: vk::QueryType,
+    ) -> &mut Self {
+        let accel_struct_node = accel_struct_node.into();
+        let query_pool_node = query_pool_node.into();
+
+        debug_assert_eq!(
+            self.node_info(query_pool_node).ty,
+            ty,
+            "Query pool must hold the requested acceleration structure property"
+        );
+        debug_assert!(
+            query < self.node_info(query_pool_node).query_count,
+            "Query must be within the range of the query pool"
+        );
+
+        self.begin_pass("write acceleration structure property")
+            .access_node(
+                accel_struct_node,
+                AccessType::AccelerationStructureBuildRead,
+            )
+            .access_node(query_pool_node, AccessType::AccelerationStructureBuildWrite)
+            .record_cmd_buf(move |device, cmd_buf, bindings| unsafe {
+                device
+                    .accel_struct_ext
+                    .as_ref()
+                    .expect("ray tracing feature must be enabled")
+                    .cmd_write_acceleration_structures_properties(
+                        cmd_buf,
+                        &[*bindings[accel_struct_node]],
+                        ty,
+                        *bindings[query_pool_node],
+                        query,
+                    );
             })
             .submit_pass()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn generate_mipmaps_accesses_each_mip_level() {
+        let mut graph = RenderGraph::new();
+        let image = graph.create_image(ImageInfo {
+            mip_level_count: 4,
+            ..ImageInfo::image_2d(
+                64,
+                64,
+                vk::Format::R8G8B8A8_UNORM,
+                vk::ImageUsageFlags::SAMPLED,
+            )
+        });
+        graph.record_mipmaps(image.into(), Some(vk::Filter::LINEAR));
+
+        let mip_accesses = graph.passes[0]
+            .execs
+            .iter()
+            .filter_map(|exec| exec.accesses.get(&image.index()))
+            .map(|accesses| {
+                accesses.map(|access| {
+                    let subresource = access.subresource.unwrap().unwrap_image();
+
+                    (
+                        access.access,
+                        subresource.base_mip_level,
+                        subresource.mip_level_count,
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(mip_accesses.len(), 5);
+        assert!(matches!(
+            mip_accesses[0][0],
+            (AccessType::TransferWrite, 0, Some(4))
+        ));
+
+        for mip_level in 1..4 {
+            assert!(matches!(
+                mip_accesses[mip_level as usize],
+                [
+                    (AccessType::TransferRead, src, Some(1)),
+                    (AccessType::TransferWrite, dst, Some(1)),
+                ] if src == mip_level - 1 && dst == mip_level
+            ));
+        }
+
+        assert!(matches!(
+            mip_accesses[4][1],
+            (AccessType::TransferRead, 3, Some(1))
+        ));
+    }
+
+    #[test]
+    pub fn update_image_region_offsets() {
+        let mut graph = RenderGraph::new();
+
+        for (fmt, buffer_offset) in [
+            (vk::Format::R8G8B8A8_UNORM, 4),
+            (vk::Format::R8G8B8A8_UNORM, 8),
+            (vk::Format::R8G8B8_UNORM, 12),
+            (vk::Format::R16G16B16A16_SFLOAT, 8),
+        ] {
+            let image = graph.create_image(ImageInfo::image_2d(
+                1,
+                1,
+                fmt,
+                vk::ImageUsageFlags::TRANSFER_DST,
+            ));
+
+            graph.update_image_region(
+                image,
+                [0u8; 32],
+                vk::BufferImageCopy {
+                    buffer_offset,
+                    image_subresource: vk::ImageSubresourceLayers {
+                        aspect_mask: vk::ImageAspectFlags::COLOR,
+                        mip_level: 0,
+                        base_array_layer: 0,
+                        layer_count: 1,
+                    },
+                    image_extent: vk::Extent3D {
+                        width: 1,
+                        height: 1,
+                        depth: 1,
+                    },
+                    ..Default::default()
+                },
+            );
+        }
+
+        assert_eq!(graph.uploads.len(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Buffer offset must be a multiple of four and of the texel size")]
+    pub fn update_image_region_unaligned_offset() {
+        let mut graph = RenderGraph::new();
+        let image = graph.create_image(ImageInfo::image_2d(
+            1,
+            1,
+            vk::Format::R8G8B8_UNORM,
+            vk::ImageUsageFlags::TRANSFER_DST,
+        ));
+
+        graph.update_image_region(
+            image,
+            [0u8; 32],
+            vk::BufferImageCopy {
+                buffer_offset: 4,
+                ..Default::default()
+            },
+        );
+    }
+}
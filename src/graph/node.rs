@@ -7,6 +7,7 @@ use {
             accel_struct::{AccelerationStructure, AccelerationStructureInfo},
             buffer::{Buffer, BufferInfo},
             image::{Image, ImageInfo},
+            query::{QueryPool, QueryPoolInfo},
         },
         pool::Lease,
     },
@@ -64,7 +65,7 @@ impl Node for AnyAccelerationStructureNode {
     }
 }
 
-/// Specifies either an owned buffer or a buffer leased from a pool.
+/// Specifies either an owned buffer, a buffer leased from a pool, or a transient buffer.
 #[derive(Debug)]
 pub enum AnyBufferNode {
     /// An owned buffer.
@@ -72,6 +73,9 @@ pub enum AnyBufferNode {
 
     /// A buffer leased from a pool.
     BufferLease(BufferLeaseNode),
+
+    /// A transient buffer created or leased by the render graph.
+    TransientBuffer(TransientBufferNode),
 }
 
 impl Clone for AnyBufferNode {
@@ -89,6 +93,7 @@ impl Information for AnyBufferNode {
         match self {
             Self::Buffer(node) => node.get(graph),
             Self::BufferLease(node) => node.get(graph),
+            Self::TransientBuffer(node) => node.get(graph),
         }
     }
 }
@@ -105,16 +110,23 @@ impl From<BufferLeaseNode> for AnyBufferNode {
     }
 }
 
+impl From<TransientBufferNode> for AnyBufferNode {
+    fn from(node: TransientBufferNode) -> Self {
+        Self::TransientBuffer(node)
+    }
+}
+
 impl Node for AnyBufferNode {
     fn index(self) -> NodeIndex {
         match self {
             Self::Buffer(node) => node.index(),
             Self::BufferLease(node) => node.index(),
+            Self::TransientBuffer(node) => node.index(),
         }
     }
 }
 
-/// Specifies either an owned image or an image leased from a pool.
+/// Specifies either an owned image, an image leased from a pool, or a transient image.
 ///
 /// The image may also be a special swapchain type of image.
 #[derive(Debug)]
@@ -127,6 +139,9 @@ pub enum AnyImageNode {
 
     /// A special swapchain image.
     SwapchainImage(SwapchainImageNode),
+
+    /// A transient image created or leased by the render graph.
+    TransientImage(TransientImageNode),
 }
 
 impl Clone for AnyImageNode {
@@ -145,6 +160,7 @@ impl Information for AnyImageNode {
             Self::Image(node) => node.get(graph),
             Self::ImageLease(node) => node.get(graph),
             Self::SwapchainImage(node) => node.get(graph),
+            Self::TransientImage(node) => node.get(graph),
         }
     }
 }
@@ -167,12 +183,160 @@ impl From<SwapchainImageNode> for AnyImageNode {
     }
 }
 
+impl From<TransientImageNode> for AnyImageNode {
+    fn from(node: TransientImageNode) -> Self {
+        Self::TransientImage(node)
+    }
+}
+
 impl Node for AnyImageNode {
     fn index(self) -> NodeIndex {
         match self {
             Self::Image(node) => node.index(),
             Self::ImageLease(node) => node.index(),
             Self::SwapchainImage(node) => node.index(),
+            Self::TransientImage(node) => node.index(),
+        }
+    }
+}
+
+/// Specifies any kind of node.
+#[derive(Debug)]
+pub enum AnyNode {
+    /// An acceleration structure node.
+    AccelerationStructure(AnyAccelerationStructureNode),
+
+    /// A buffer node.
+    Buffer(AnyBufferNode),
+
+    /// An image node.
+    Image(AnyImageNode),
+
+    /// A query pool node.
+    QueryPool(AnyQueryPoolNode),
+}
+
+impl AnyNode {
+    /// Returns the same kind of node at another index.
+    pub(super) fn with_index(self, idx: NodeIndex) -> Self {
+        match self {
+            Self::AccelerationStructure(node) => Self::AccelerationStructure(match node {
+                AnyAccelerationStructureNode::AccelerationStructure(_) => {
+                    AccelerationStructureNode::new(idx).into()
+                }
+                AnyAccelerationStructureNode::AccelerationStructureLease(_) => {
+                    AccelerationStructureLeaseNode::new(idx).into()
+                }
+            }),
+            Self::Buffer(node) => Self::Buffer(match node {
+                AnyBufferNode::Buffer(_) => BufferNode::new(idx).into(),
+                AnyBufferNode::BufferLease(_) => BufferLeaseNode::new(idx).into(),
+                AnyBufferNode::TransientBuffer(_) => TransientBufferNode::new(idx).into(),
+            }),
+            Self::Image(node) => Self::Image(match node {
+                AnyImageNode::Image(_) => ImageNode::new(idx).into(),
+                AnyImageNode::ImageLease(_) => ImageLeaseNode::new(idx).into(),
+                AnyImageNode::SwapchainImage(_) => SwapchainImageNode::new(idx).into(),
+                AnyImageNode::TransientImage(_) => TransientImageNode::new(idx).into(),
+            }),
+            Self::QueryPool(node) => Self::QueryPool(match node {
+                AnyQueryPoolNode::QueryPool(_) => QueryPoolNode::new(idx).into(),
+                AnyQueryPoolNode::QueryPoolLease(_) => QueryPoolLeaseNode::new(idx).into(),
+            }),
+        }
+    }
+}
+
+impl Clone for AnyNode {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for AnyNode {}
+
+impl Node for AnyNode {
+    fn index(self) -> NodeIndex {
+        match self {
+            Self::AccelerationStructure(node) => node.index(),
+            Self::Buffer(node) => node.index(),
+            Self::Image(node) => node.index(),
+            Self::QueryPool(node) => node.index(),
+        }
+    }
+}
+
+macro_rules! any_node {
+    ($kind:ident: $($name:ident),+) => {
+        paste::paste! {
+            impl From<[<Any $kind Node>]> for AnyNode {
+                fn from(node: [<Any $kind Node>]) -> Self {
+                    Self::$kind(node)
+                }
+            }
+
+            $(
+                impl From<[<$name Node>]> for AnyNode {
+                    fn from(node: [<$name Node>]) -> Self {
+                        Self::$kind(node.into())
+                    }
+                }
+            )+
+        }
+    };
+}
+
+any_node!(AccelerationStructure: AccelerationStructure, AccelerationStructureLease);
+any_node!(Buffer: Buffer, BufferLease, TransientBuffer);
+any_node!(Image: Image, ImageLease, SwapchainImage, TransientImage);
+any_node!(QueryPool: QueryPool, QueryPoolLease);
+
+/// Specifies either an owned query pool or a query pool leased from a pool.
+#[derive(Debug)]
+pub enum AnyQueryPoolNode {
+    /// An owned query pool.
+    QueryPool(QueryPoolNode),
+
+    /// A query pool leased from a pool.
+    QueryPoolLease(QueryPoolLeaseNode),
+}
+
+impl Clone for AnyQueryPoolNode {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for AnyQueryPoolNode {}
+
+impl Information for AnyQueryPoolNode {
+    type Info = QueryPoolInfo;
+
+    fn get(self, graph: &RenderGraph) -> Self::Info {
+        match self {
+            Self::QueryPool(node) => node.get(graph),
+            Self::QueryPoolLease(node) => node.get(graph),
+        }
+    }
+}
+
+impl From<QueryPoolNode> for AnyQueryPoolNode {
+    fn from(node: QueryPoolNode) -> Self {
+        Self::QueryPool(node)
+    }
+}
+
+impl From<QueryPoolLeaseNode> for AnyQueryPoolNode {
+    fn from(node: QueryPoolLeaseNode) -> Self {
+        Self::QueryPoolLease(node)
+    }
+}
+
+impl Node for AnyQueryPoolNode {
+    fn index(self) -> NodeIndex {
+        match self {
+            Self::QueryPool(node) => node.index(),
+            Self::QueryPoolLease(node) => node.index(),
         }
     }
 }
@@ -223,7 +387,11 @@ node!(Buffer);
 node!(BufferLease);
 node!(Image);
 node!(ImageLease);
+node!(QueryPool);
+node!(QueryPoolLease);
 node!(SwapchainImage);
+node!(TransientBuffer);
+node!(TransientImage);
 
 macro_rules! node_unbind {
     ($name:ident) => {
@@ -248,6 +416,7 @@ macro_rules! node_unbind {
 node_unbind!(AccelerationStructure);
 node_unbind!(Buffer);
 node_unbind!(Image);
+node_unbind!(QueryPool);
 
 macro_rules! node_unbind_lease {
     ($name:ident) => {
@@ -272,3 +441,4 @@ macro_rules! node_unbind_lease {
 node_unbind_lease!(AccelerationStructure);
 node_unbind_lease!(Buffer);
 node_unbind_lease!(Image);
+node_unbind_lease!(QueryPool);
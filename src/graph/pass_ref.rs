@@ -3,10 +3,11 @@
 use {
     super::{
         AccelerationStructureLeaseNode, AccelerationStructureNode, AnyAccelerationStructureNode,
-        AnyBufferNode, AnyImageNode, Area, Attachment, Bind, Binding, BufferLeaseNode, BufferNode,
-        ClearColorValue, Edge, Execution, ExecutionFunction, ExecutionPipeline, ImageLeaseNode,
-        ImageNode, Information, Node, NodeIndex, Pass, RenderGraph, SampleCount,
-        SwapchainImageNode,
+        AnyBufferNode, AnyImageNode, AnyQueryPoolNode, Area, Attachment, Bind, Binding,
+        BufferLeaseNode, BufferNode, ClearColorValue, Condition, Edge, Execution,
+        ExecutionFunction, ExecutionPipeline, ImageLeaseNode, ImageNode, Information, Node,
+        NodeIndex, Pass, QueryPoolLeaseNode, QueryPoolNode, QueueType, RenderGraph, SampleCount,
+        SwapchainImageNode, TransientBufferNode, TransientImageNode,
     },
     crate::driver::{
         accel_struct::{AccelerationStructure, AccelerationStructureGeometryInfo},
@@ -15,10 +16,11 @@ use {
         device::Device,
         graphic::{DepthStencilMode, GraphicPipeline},
         image::{Image, ImageSubresource, ImageViewInfo},
-        ray_trace::RayTracePipeline,
+        query::QueryPool,
+        ray_trace::{RayTracePipeline, ShaderBindingTable},
         render_pass::ResolveMode,
     },
-    ash::vk,
+    ash::{extensions::ext, vk},
     log::trace,
     std::{
         cell::RefCell,
@@ -85,6 +87,13 @@ impl<'a> Acceleration<'a> {
     ///   [`AccelerationStructure::size_of`] plus `min_accel_struct_scratch_offset_alignment` of
     ///   [`PhysicalDevice::accel_struct_properties`](crate::driver::physical_device::PhysicalDevice::accel_struct_properties).
     ///
+    /// Geometry data must use device addresses; host addresses may only be used by
+    /// [`AccelerationStructure::build_host`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any geometry uses a host address.
+    ///
     /// # Examples
     ///
     /// Basic usage:
@@ -179,6 +188,11 @@ impl<'a> Acceleration<'a> {
                 tls.max_primitive_counts.clear();
 
                 for info in build_info.geometries.iter() {
+                    assert!(
+                        !info.has_host_address(),
+                        "Host addresses may only be used by AccelerationStructure::build_host"
+                    );
+
                     tls.geometries.push(info.into_vk());
                     tls.max_primitive_counts.push(info.max_primitive_count);
                 }
@@ -214,6 +228,13 @@ impl<'a> Acceleration<'a> {
     /// - Size must be equal to or greater than the `update_size` value returned by
     ///   [`AccelerationStructure::size_of`] plus `min_accel_struct_scratch_offset_alignment` of
     ///   [`PhysicalDevice::accel_struct_properties`](crate::driver::physical_device::PhysicalDevice::accel_struct_properties).
+    ///
+    /// Geometry data must use device addresses; host addresses may only be used by
+    /// [`AccelerationStructure::build_host`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any geometry uses a host address.
     pub fn update_structure(
         &self,
         src_accel_node: impl Into<AnyAccelerationStructureNode>,
@@ -244,6 +265,11 @@ impl<'a> Acceleration<'a> {
                 tls.max_primitive_counts.clear();
 
                 for info in build_info.geometries.iter() {
+                    assert!(
+                        !info.has_host_address(),
+                        "Host addresses may only be used by AccelerationStructure::build_host"
+                    );
+
                     tls.geometries.push(info.into_vk());
                     tls.max_primitive_counts.push(info.max_primitive_count);
                 }
@@ -271,6 +297,237 @@ impl<'a> Acceleration<'a> {
             });
         }
     }
+
+    /// Clone an acceleration structure into another acceleration structure.
+    ///
+    /// The destination must have been created using the same type as the source and a size equal
+    /// to or greater than the source.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::accel_struct::{AccelerationStructure, AccelerationStructureInfo};
+    /// # use screen_13::graph::RenderGraph;
+    /// # use vk_sync::AccessType;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let mut my_graph = RenderGraph::new();
+    /// # let info = AccelerationStructureInfo::blas(1024);
+    /// # let my_blas = AccelerationStructure::create(&device, info)?;
+    /// let my_clone = AccelerationStructure::create(&device, my_blas.info)?;
+    /// let blas_node = my_graph.bind_node(my_blas);
+    /// let clone_node = my_graph.bind_node(my_clone);
+    ///
+    /// my_graph.begin_pass("clone my blas")
+    ///         .access_node(blas_node, AccessType::AccelerationStructureBuildRead)
+    ///         .access_node(clone_node, AccessType::AccelerationStructureBuildWrite)
+    ///         .record_acceleration(move |acceleration, _| {
+    ///             acceleration.copy_structure(blas_node, clone_node);
+    ///         });
+    /// # Ok(()) }
+    /// ```
+    pub fn copy_structure(
+        &self,
+        src_accel_node: impl Into<AnyAccelerationStructureNode>,
+        dst_accel_node: impl Into<AnyAccelerationStructureNode>,
+    ) {
+        let src_accel_node = src_accel_node.into();
+        let dst_accel_node = dst_accel_node.into();
+
+        unsafe {
+            self.device
+                .accel_struct_ext
+                .as_ref()
+                .expect("ray tracing feature must be enabled")
+                .cmd_copy_acceleration_structure(
+                    self.cmd_buf,
+                    &vk::CopyAccelerationStructureInfoKHR::builder()
+                        .src(*self.bindings[src_accel_node])
+                        .dst(*self.bindings[dst_accel_node])
+                        .mode(vk::CopyAccelerationStructureModeKHR::CLONE),
+                );
+        }
+    }
+
+    /// Deserialize an acceleration structure from a buffer which holds data previously written
+    /// using [`Self::serialize_structure`].
+    ///
+    /// Requires a source buffer which was created with the following requirements:
+    ///
+    /// - Flags must include [`vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS`]
+    /// - Alignment must be a multiple of 256
+    ///
+    /// The destination must have been created using a size equal to or greater than the value
+    /// returned by [`AccelerationStructure::deserialized_size`], which also checks that the data
+    /// is compatible with the device.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::accel_struct::{AccelerationStructure, AccelerationStructureInfo};
+    /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+    /// # use screen_13::graph::RenderGraph;
+    /// # use vk_sync::AccessType;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let mut my_graph = RenderGraph::new();
+    /// let data = std::fs::read("my_blas.bin").unwrap();
+    /// let size = AccelerationStructure::deserialized_size(&device, &data)?;
+    /// let my_blas = AccelerationStructure::create(&device, AccelerationStructureInfo::blas(size))?;
+    /// let buf_info = BufferInfo::device_mem(
+    ///     data.len() as _,
+    ///     vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS | vk::BufferUsageFlags::TRANSFER_DST,
+    /// )
+    /// .to_builder()
+    /// .alignment(256);
+    /// let buf_node = my_graph.bind_node(Buffer::create(&device, buf_info)?);
+    /// let blas_node = my_graph.bind_node(my_blas);
+    ///
+    /// my_graph.update_buffer(buf_node, data)
+    ///         .begin_pass("deserialize my blas")
+    ///         .access_node(buf_node, AccessType::AccelerationStructureBuildRead)
+    ///         .access_node(blas_node, AccessType::AccelerationStructureBuildWrite)
+    ///         .record_acceleration(move |acceleration, _| {
+    ///             acceleration.deserialize_structure(buf_node, blas_node);
+    ///         });
+    /// # Ok(()) }
+    /// ```
+    pub fn deserialize_structure(
+        &self,
+        src_buf_node: impl Into<AnyBufferNode>,
+        dst_accel_node: impl Into<AnyAccelerationStructureNode>,
+    ) {
+        let src_buf_node = src_buf_node.into();
+        let dst_accel_node = dst_accel_node.into();
+        let src_addr = Buffer::device_address(&self.bindings[src_buf_node]);
+
+        debug_assert_eq!(src_addr % 256, 0, "Source address must be aligned to 256");
+
+        unsafe {
+            self.device
+                .accel_struct_ext
+                .as_ref()
+                .expect("ray tracing feature must be enabled")
+                .cmd_copy_memory_to_acceleration_structure(
+                    self.cmd_buf,
+                    &vk::CopyMemoryToAccelerationStructureInfoKHR::builder()
+                        .src(vk::DeviceOrHostAddressConstKHR {
+                            device_address: src_addr,
+                        })
+                        .dst(*self.bindings[dst_accel_node])
+                        .mode(vk::CopyAccelerationStructureModeKHR::DESERIALIZE),
+                );
+        }
+    }
+
+    /// Serialize an acceleration structure into a buffer so that it may be stored and later
+    /// deserialized using [`Self::deserialize_structure`].
+    ///
+    /// Requires a destination buffer which was created with the following requirements:
+    ///
+    /// - Flags must include [`vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS`]
+    /// - Alignment must be a multiple of 256
+    /// - Size must be equal to or greater than the serialized size written by
+    ///   [`RenderGraph::write_accel_struct_serialization_size`]
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::accel_struct::{AccelerationStructure, AccelerationStructureInfo};
+    /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+    /// # use screen_13::driver::query::{QueryPool, QueryPoolInfo};
+    /// # use screen_13::graph::RenderGraph;
+    /// # use screen_13::pool::lazy::LazyPool;
+    /// # use vk_sync::AccessType;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let mut pool = LazyPool::new(&device);
+    /// # let info = AccelerationStructureInfo::blas(1024);
+    /// # let my_blas = Arc::new(AccelerationStructure::create(&device, info)?);
+    /// let query_pool = Arc::new(QueryPool::create(&device, QueryPoolInfo::accel_struct_serialization_size(1))?);
+    ///
+    /// // After building my_blas
+    /// let mut my_graph = RenderGraph::new();
+    /// let blas_node = my_graph.bind_node(&my_blas);
+    /// let query_pool_node = my_graph.bind_node(&query_pool);
+    /// my_graph.reset_queries(query_pool_node, 0..1)
+    ///         .write_accel_struct_serialization_size(blas_node, query_pool_node, 0);
+    /// my_graph.resolve().submit(&mut pool, 0, 0)?.wait_until_executed()?;
+    ///
+    /// let mut size = [0];
+    /// QueryPool::results(&query_pool, 0, 1, &mut size)?;
+    ///
+    /// let buf_info = BufferInfo::device_mem(
+    ///     size[0],
+    ///     vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS | vk::BufferUsageFlags::TRANSFER_SRC,
+    /// )
+    /// .to_builder()
+    /// .alignment(256);
+    /// let mut my_graph = RenderGraph::new();
+    /// let blas_node = my_graph.bind_node(my_blas);
+    /// let buf_node = my_graph.bind_node(Buffer::create(&device, buf_info)?);
+    ///
+    /// my_graph.begin_pass("serialize my blas")
+    ///         .access_node(blas_node, AccessType::AccelerationStructureBuildRead)
+    ///         .access_node(buf_node, AccessType::AccelerationStructureBuildWrite)
+    ///         .record_acceleration(move |acceleration, _| {
+    ///             acceleration.serialize_structure(blas_node, buf_node);
+    ///         });
+    ///
+    /// let readback = my_graph.read_buffer(buf_node);
+    /// my_graph.resolve().submit(&mut pool, 0, 0)?;
+    ///
+    /// std::fs::write("my_blas.bin", readback.wait()?).unwrap();
+    /// # Ok(()) }
+    /// ```
+    pub fn serialize_structure(
+        &self,
+        src_accel_node: impl Into<AnyAccelerationStructureNode>,
+        dst_buf_node: impl Into<AnyBufferNode>,
+    ) {
+        let src_accel_node = src_accel_node.into();
+        let dst_buf_node = dst_buf_node.into();
+        let dst_addr = Buffer::device_address(&self.bindings[dst_buf_node]);
+
+        debug_assert_eq!(
+            dst_addr % 256,
+            0,
+            "Destination address must be aligned to 256"
+        );
+
+        unsafe {
+            self.device
+                .accel_struct_ext
+                .as_ref()
+                .expect("ray tracing feature must be enabled")
+                .cmd_copy_acceleration_structure_to_memory(
+                    self.cmd_buf,
+                    &vk::CopyAccelerationStructureToMemoryInfoKHR::builder()
+                        .src(*self.bindings[src_accel_node])
+                        .dst(vk::DeviceOrHostAddressKHR {
+                            device_address: dst_addr,
+                        })
+                        .mode(vk::CopyAccelerationStructureModeKHR::SERIALIZE),
+                );
+        }
+    }
 }
 
 /// Associated type trait which enables default values for read and write methods.
@@ -384,15 +641,32 @@ bind!(RayTrace);
 #[derive(Clone, Copy, Debug)]
 pub struct Bindings<'a> {
     bindings: &'a [Binding],
+    conditional_dispatch: Option<&'a ConditionalDispatch>,
     exec: &'a Execution,
 }
 
 impl<'a> Bindings<'a> {
-    pub(super) fn new(bindings: &'a [Binding], exec: &'a Execution) -> Self {
-        Self { bindings, exec }
+    pub(super) fn new(
+        bindings: &'a [Binding],
+        exec: &'a Execution,
+        conditional_dispatch: Option<&'a ConditionalDispatch>,
+    ) -> Self {
+        Self {
+            bindings,
+            conditional_dispatch,
+            exec,
+        }
     }
 
     fn binding_ref(&self, node_idx: usize) -> &Binding {
+        // Executions appended from a sub-graph were recorded using the nodes of the sub-graph
+        let node_idx = self
+            .exec
+            .node_indices
+            .as_ref()
+            .map(|node_indices| node_indices[node_idx])
+            .unwrap_or(node_idx);
+
         // You must have called read or write for this node on this execution before indexing
         // into the bindings data!
         debug_assert!(
@@ -412,7 +686,7 @@ macro_rules! index {
                 type Output = $handle;
 
                 fn index(&self, node: [<$name Node>]) -> &Self::Output {
-                    &*self.binding_ref(node.idx).[<as_ $name:snake>]().unwrap()
+                    self.binding_ref(node.idx).[<as_driver_ $handle:snake>]().unwrap()
                 }
             }
         }
@@ -427,26 +701,19 @@ index!(Buffer, Buffer);
 index!(BufferLease, Buffer);
 index!(Image, Image);
 index!(ImageLease, Image);
+index!(QueryPool, QueryPool);
+index!(QueryPoolLease, QueryPool);
 index!(SwapchainImage, Image);
+index!(TransientBuffer, Buffer);
+index!(TransientImage, Image);
 
 impl<'a> Index<AnyAccelerationStructureNode> for Bindings<'a> {
     type Output = AccelerationStructure;
 
     fn index(&self, node: AnyAccelerationStructureNode) -> &Self::Output {
-        let node_idx = match node {
-            AnyAccelerationStructureNode::AccelerationStructure(node) => node.idx,
-            AnyAccelerationStructureNode::AccelerationStructureLease(node) => node.idx,
-        };
-        let binding = self.binding_ref(node_idx);
-
-        match node {
-            AnyAccelerationStructureNode::AccelerationStructure(_) => {
-                binding.as_acceleration_structure().unwrap()
-            }
-            AnyAccelerationStructureNode::AccelerationStructureLease(_) => {
-                binding.as_acceleration_structure_lease().unwrap()
-            }
-        }
+        self.binding_ref(node.index())
+            .as_driver_acceleration_structure()
+            .unwrap()
     }
 }
 
@@ -454,16 +721,7 @@ impl<'a> Index<AnyBufferNode> for Bindings<'a> {
     type Output = Buffer;
 
     fn index(&self, node: AnyBufferNode) -> &Self::Output {
-        let node_idx = match node {
-            AnyBufferNode::Buffer(node) => node.idx,
-            AnyBufferNode::BufferLease(node) => node.idx,
-        };
-        let binding = self.binding_ref(node_idx);
-
-        match node {
-            AnyBufferNode::Buffer(_) => binding.as_buffer().unwrap(),
-            AnyBufferNode::BufferLease(_) => binding.as_buffer_lease().unwrap(),
-        }
+        self.binding_ref(node.index()).as_driver_buffer().unwrap()
     }
 }
 
@@ -471,18 +729,17 @@ impl<'a> Index<AnyImageNode> for Bindings<'a> {
     type Output = Image;
 
     fn index(&self, node: AnyImageNode) -> &Self::Output {
-        let node_idx = match node {
-            AnyImageNode::Image(node) => node.idx,
-            AnyImageNode::ImageLease(node) => node.idx,
-            AnyImageNode::SwapchainImage(node) => node.idx,
-        };
-        let binding = self.binding_ref(node_idx);
+        self.binding_ref(node.index()).as_driver_image().unwrap()
+    }
+}
 
-        match node {
-            AnyImageNode::Image(_) => binding.as_image().unwrap(),
-            AnyImageNode::ImageLease(_) => binding.as_image_lease().unwrap(),
-            AnyImageNode::SwapchainImage(_) => binding.as_swapchain_image().unwrap(),
-        }
+impl<'a> Index<AnyQueryPoolNode> for Bindings<'a> {
+    type Output = QueryPool;
+
+    fn index(&self, node: AnyQueryPoolNode) -> &Self::Output {
+        self.binding_ref(node.index())
+            .as_driver_query_pool()
+            .unwrap()
     }
 }
 
@@ -527,6 +784,72 @@ pub struct Compute<'a> {
 }
 
 impl<'a> Compute<'a> {
+    /// Begins a [query] which counts the pipeline statistics of the following dispatches.
+    ///
+    /// The query must have been reset and must be ended using [`Compute::end_query`] within the
+    /// same execution. The query pool node must be written by the pass which records this
+    /// command.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+    /// # use screen_13::driver::compute::{ComputePipeline, ComputePipelineInfo};
+    /// # use screen_13::driver::query::{QueryPool, QueryPoolInfo};
+    /// # use screen_13::driver::shader::{Shader};
+    /// # use screen_13::graph::RenderGraph;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let info = ComputePipelineInfo::default();
+    /// # let shader = Shader::new_compute([0u8; 1].as_slice());
+    /// # let my_compute_pipeline = Arc::new(ComputePipeline::create(&device, info, shader)?);
+    /// # let buf_info = BufferInfo::device_mem(8, vk::BufferUsageFlags::TRANSFER_DST);
+    /// # let my_buf = Buffer::create(&device, buf_info)?;
+    /// let stats = vk::QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS;
+    /// let info = QueryPoolInfo::pipeline_statistics(1, stats);
+    /// let my_query_pool = QueryPool::create(&device, info)?;
+    ///
+    /// let mut my_graph = RenderGraph::new();
+    /// let my_query_pool = my_graph.bind_node(my_query_pool);
+    /// let my_buf = my_graph.bind_node(my_buf);
+    ///
+    /// my_graph.reset_queries(my_query_pool, 0..1);
+    /// my_graph.begin_pass("count invocations")
+    ///         .bind_pipeline(&my_compute_pipeline)
+    ///         .write_node(my_query_pool)
+    ///         .record_compute(move |compute, bindings| {
+    ///             compute.begin_query(my_query_pool, 0, vk::QueryControlFlags::empty())
+    ///                    .dispatch(128, 64, 32)
+    ///                    .end_query(my_query_pool, 0);
+    ///         });
+    /// my_graph.copy_query_results(my_query_pool, 0..1, my_buf, 0, vk::QueryResultFlags::TYPE_64);
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [query]: https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCmdBeginQuery.html
+    #[profiling::function]
+    pub fn begin_query(
+        &self,
+        query_pool: impl Into<AnyQueryPoolNode>,
+        query: u32,
+        flags: vk::QueryControlFlags,
+    ) -> &Self {
+        let query_pool = query_pool.into();
+
+        unsafe {
+            self.device
+                .cmd_begin_query(self.cmd_buf, *self.bindings[query_pool], query, flags);
+        }
+
+        self
+    }
+
     /// [Dispatch] compute work items.
     ///
     /// When the command is executed, a global workgroup consisting of
@@ -581,6 +904,28 @@ impl<'a> Compute<'a> {
     /// [Dispatch]: https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCmdDispatch.html
     #[profiling::function]
     pub fn dispatch(&self, group_count_x: u32, group_count_y: u32, group_count_z: u32) -> &Self {
+        if let Some(conditional_dispatch) = self.bindings.conditional_dispatch {
+            let mut group_counts = [0; 12];
+            group_counts[0..4].copy_from_slice(&group_count_x.to_ne_bytes());
+            group_counts[4..8].copy_from_slice(&group_count_y.to_ne_bytes());
+            group_counts[8..12].copy_from_slice(&group_count_z.to_ne_bytes());
+
+            conditional_dispatch.dispatch_indirect(
+                self.device,
+                self.cmd_buf,
+                |args_buf, args_offset| unsafe {
+                    self.device.cmd_update_buffer(
+                        self.cmd_buf,
+                        args_buf,
+                        args_offset,
+                        &group_counts,
+                    );
+                },
+            );
+
+            return self;
+        }
+
         unsafe {
             self.device
                 .cmd_dispatch(self.cmd_buf, group_count_x, group_count_y, group_count_z);
@@ -599,6 +944,12 @@ impl<'a> Compute<'a> {
     /// [`Compute::dispatch`] is equivalent to
     /// `dispatch_base(0, 0, 0, group_count_x, group_count_y, group_count_z)`.
     ///
+    /// # Panics
+    ///
+    /// Panics if the pass is conditional and the device does not support
+    /// `VK_EXT_conditional_rendering`, because there is no indirect form of this command. See
+    /// [`PassRef::set_condition`].
+    ///
     /// [Dispatch]: https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCmdDispatchBase.html
     #[profiling::function]
     pub fn dispatch_base(
@@ -610,6 +961,11 @@ impl<'a> Compute<'a> {
         group_count_y: u32,
         group_count_z: u32,
     ) -> &Self {
+        assert!(
+            self.bindings.conditional_dispatch.is_none(),
+            "conditional dispatch_base requires VK_EXT_conditional_rendering"
+        );
+
         unsafe {
             self.device.cmd_dispatch_base(
                 self.cmd_buf,
@@ -666,13 +1022,13 @@ impl<'a> Compute<'a> {
     ///     std::slice::from_raw_parts(&cmd as *const _ as *const _, CMD_SIZE)
     /// };
     ///
-    /// let args_buf_flags = vk::BufferUsageFlags::STORAGE_BUFFER;
+    /// let args_buf_flags = vk::BufferUsageFlags::INDIRECT_BUFFER;
     /// let args_buf = Buffer::create_from_slice(&device, args_buf_flags, cmd_data)?;
     /// let args_buf_node = my_graph.bind_node(args_buf);
     ///
     /// my_graph.begin_pass("fill my_buf_node with data")
     ///         .bind_pipeline(&my_compute_pipeline)
-    ///         .read_node(args_buf_node)
+    ///         .read_indirect_node(args_buf_node)
     ///         .write_descriptor(0, my_buf_node)
     ///         .record_compute(move |compute, bindings| {
     ///             compute.dispatch_indirect(args_buf_node, 0);
@@ -688,8 +1044,31 @@ impl<'a> Compute<'a> {
         args_buf: impl Into<AnyBufferNode>,
         args_offset: vk::DeviceSize,
     ) -> &Self {
+        use std::slice::from_ref;
+
         let args_buf = args_buf.into();
 
+        if let Some(conditional_dispatch) = self.bindings.conditional_dispatch {
+            conditional_dispatch.dispatch_indirect(
+                self.device,
+                self.cmd_buf,
+                |conditional_args_buf, conditional_args_offset| unsafe {
+                    self.device.cmd_copy_buffer(
+                        self.cmd_buf,
+                        *self.bindings[args_buf],
+                        conditional_args_buf,
+                        from_ref(&vk::BufferCopy {
+                            src_offset: args_offset,
+                            dst_offset: conditional_args_offset,
+                            size: 12,
+                        }),
+                    );
+                },
+            );
+
+            return self;
+        }
+
         unsafe {
             self.device
                 .cmd_dispatch_indirect(self.cmd_buf, *self.bindings[args_buf], args_offset);
@@ -698,6 +1077,19 @@ impl<'a> Compute<'a> {
         self
     }
 
+    /// Ends a query which was begun using [`Compute::begin_query`].
+    #[profiling::function]
+    pub fn end_query(&self, query_pool: impl Into<AnyQueryPoolNode>, query: u32) -> &Self {
+        let query_pool = query_pool.into();
+
+        unsafe {
+            self.device
+                .cmd_end_query(self.cmd_buf, *self.bindings[query_pool], query);
+        }
+
+        self
+    }
+
     /// Updates push constants.
     ///
     /// Push constants represent a high speed path to modify constant data in pipelines that is
@@ -855,6 +1247,145 @@ impl<'a> Compute<'a> {
     }
 }
 
+/// Records the dispatches of a conditional compute pass on devices which do not support
+/// `VK_EXT_conditional_rendering`.
+///
+/// Each dispatch is replaced by an indirect dispatch which reads its group counts from the element
+/// `args_idx` of `args_buf`. Before the dispatch the group counts and the condition are copied into
+/// that element and the internal conditional dispatch pipeline zeroes it if the condition is zero.
+#[derive(Debug)]
+pub(super) struct ConditionalDispatch {
+    pub args_buf: vk::Buffer,
+    pub args_idx: u32,
+    pub condition_buf: vk::Buffer,
+    pub condition_offset: vk::DeviceSize,
+    pub descriptor_set: vk::DescriptorSet,
+    pub exec_descriptor_sets: Vec<vk::DescriptorSet>,
+    pub exec_layout: vk::PipelineLayout,
+    pub exec_pipeline: vk::Pipeline,
+    pub layout: vk::PipelineLayout,
+    pub pipeline: vk::Pipeline,
+}
+
+impl ConditionalDispatch {
+    /// The size of each `uvec4` element of the arguments buffer.
+    pub const ARGS_STRIDE: vk::DeviceSize = 16;
+
+    fn dispatch_indirect(
+        &self,
+        device: &Device,
+        cmd_buf: vk::CommandBuffer,
+        write_group_counts: impl FnOnce(vk::Buffer, vk::DeviceSize),
+    ) {
+        use std::slice::from_ref;
+
+        fn memory_barrier(
+            device: &Device,
+            cmd_buf: vk::CommandBuffer,
+            src_stage_mask: vk::PipelineStageFlags,
+            src_access_mask: vk::AccessFlags,
+            dst_stage_mask: vk::PipelineStageFlags,
+            dst_access_mask: vk::AccessFlags,
+        ) {
+            unsafe {
+                device.cmd_pipeline_barrier(
+                    cmd_buf,
+                    src_stage_mask,
+                    dst_stage_mask,
+                    vk::DependencyFlags::empty(),
+                    from_ref(&vk::MemoryBarrier {
+                        src_access_mask,
+                        dst_access_mask,
+                        ..Default::default()
+                    }),
+                    &[],
+                    &[],
+                );
+            }
+        }
+
+        trace!("    conditional dispatch [{}]", self.args_idx);
+
+        let args_offset = self.args_idx as vk::DeviceSize * Self::ARGS_STRIDE;
+
+        // The arguments may still be in use by the previous dispatch; the condition and any
+        // indirect arguments copied below were made visible to the indirect stage by the graph
+        memory_barrier(
+            device,
+            cmd_buf,
+            vk::PipelineStageFlags::DRAW_INDIRECT | vk::PipelineStageFlags::COMPUTE_SHADER,
+            vk::AccessFlags::SHADER_WRITE,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::AccessFlags::TRANSFER_READ | vk::AccessFlags::TRANSFER_WRITE,
+        );
+
+        write_group_counts(self.args_buf, args_offset);
+
+        unsafe {
+            device.cmd_copy_buffer(
+                cmd_buf,
+                self.condition_buf,
+                self.args_buf,
+                from_ref(&vk::BufferCopy {
+                    src_offset: self.condition_offset,
+                    dst_offset: args_offset + 12,
+                    size: 4,
+                }),
+            );
+        }
+
+        memory_barrier(
+            device,
+            cmd_buf,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::AccessFlags::TRANSFER_WRITE,
+            vk::PipelineStageFlags::COMPUTE_SHADER,
+            vk::AccessFlags::SHADER_READ | vk::AccessFlags::SHADER_WRITE,
+        );
+
+        unsafe {
+            device.cmd_bind_pipeline(cmd_buf, vk::PipelineBindPoint::COMPUTE, self.pipeline);
+            device.cmd_bind_descriptor_sets(
+                cmd_buf,
+                vk::PipelineBindPoint::COMPUTE,
+                self.layout,
+                0,
+                from_ref(&self.descriptor_set),
+                &[],
+            );
+            device.cmd_dispatch_base(cmd_buf, self.args_idx, 0, 0, 1, 1, 1);
+        }
+
+        memory_barrier(
+            device,
+            cmd_buf,
+            vk::PipelineStageFlags::COMPUTE_SHADER,
+            vk::AccessFlags::SHADER_WRITE,
+            vk::PipelineStageFlags::DRAW_INDIRECT,
+            vk::AccessFlags::INDIRECT_COMMAND_READ,
+        );
+
+        // Binding a pipeline does not disturb push constants, but the descriptor sets of the
+        // execution were disturbed and must be bound again
+        unsafe {
+            device.cmd_bind_pipeline(cmd_buf, vk::PipelineBindPoint::COMPUTE, self.exec_pipeline);
+
+            if !self.exec_descriptor_sets.is_empty() {
+                device.cmd_bind_descriptor_sets(
+                    cmd_buf,
+                    vk::PipelineBindPoint::COMPUTE,
+                    self.exec_layout,
+                    0,
+                    &self.exec_descriptor_sets,
+                    &[],
+                );
+            }
+
+            device.cmd_dispatch_indirect(cmd_buf, self.args_buf, args_offset);
+        }
+    }
+}
+
 /// Describes the SPIR-V binding index, and optionally a specific descriptor set
 /// and array index.
 ///
@@ -928,6 +1459,9 @@ impl From<(DescriptorSetIndex, BindingIndex, [BindingOffset; 1])> for Descriptor
 /// [`PipelinePassRef::record_subpass`] which may be accessed by binding a [`GraphicPipeline`] to a
 /// render pass.
 ///
+/// Dynamic state, such as the stencil reference set by [`Self::set_stencil_reference`], is reset
+/// to the values of the pipeline info and [`DepthStencilMode`] each time a pipeline is bound.
+///
 /// # Examples
 ///
 /// Basic usage:
@@ -969,7 +1503,11 @@ pub struct Draw<'a> {
 }
 
 impl<'a> Draw<'a> {
-    /// Bind an index buffer to the current pass.
+    /// Begins a [query] which counts the samples which pass the depth and stencil tests of the
+    /// following draws, or which counts the pipeline statistics of the following draws.
+    ///
+    /// The query must have been reset and must be ended using [`Draw::end_query`] within the same
+    /// subpass. The query pool node must be written by the pass which records this command.
     ///
     /// # Examples
     ///
@@ -983,6 +1521,7 @@ impl<'a> Draw<'a> {
     /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
     /// # use screen_13::driver::graphic::{GraphicPipeline, GraphicPipelineInfo};
     /// # use screen_13::driver::image::{Image, ImageInfo};
+    /// # use screen_13::driver::query::{QueryPool, QueryPoolInfo};
     /// # use screen_13::driver::shader::Shader;
     /// # use screen_13::graph::RenderGraph;
     /// # fn main() -> Result<(), DriverError> {
@@ -994,9 +1533,72 @@ impl<'a> Draw<'a> {
     /// # let info = GraphicPipelineInfo::default();
     /// # let my_graphic_pipeline = Arc::new(GraphicPipeline::create(&device, info, [vert, frag])?);
     /// # let mut my_graph = RenderGraph::new();
-    /// # let info = ImageInfo::image_2d(32, 32, vk::Format::R8G8B8A8_UNORM, vk::ImageUsageFlags::SAMPLED);
-    /// # let swapchain_image = my_graph.bind_node(Image::create(&device, info)?);
-    /// # let buf_info = BufferInfo::device_mem(8, vk::BufferUsageFlags::INDEX_BUFFER);
+    /// # let info = ImageInfo::image_2d(32, 32, vk::Format::D32_SFLOAT, vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT);
+    /// # let my_depth_image = my_graph.bind_node(Image::create(&device, info)?);
+    /// # let buf_info = BufferInfo::device_mem(8, vk::BufferUsageFlags::TRANSFER_DST);
+    /// # let my_buf = my_graph.bind_node(Buffer::create(&device, buf_info)?);
+    /// let my_query_pool = my_graph.bind_node(QueryPool::create(&device, QueryPoolInfo::occlusion(1))?);
+    ///
+    /// my_graph.reset_queries(my_query_pool, 0..1);
+    /// my_graph.begin_pass("occlusion test")
+    ///         .bind_pipeline(&my_graphic_pipeline)
+    ///         .load_depth_stencil(my_depth_image)
+    ///         .write_node(my_query_pool)
+    ///         .record_subpass(move |subpass, bindings| {
+    ///             subpass.begin_query(my_query_pool, 0, vk::QueryControlFlags::empty())
+    ///                    .draw(36, 1, 0, 0)
+    ///                    .end_query(my_query_pool, 0);
+    ///         });
+    /// my_graph.copy_query_results(my_query_pool, 0..1, my_buf, 0, vk::QueryResultFlags::TYPE_64);
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [query]: https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCmdBeginQuery.html
+    #[profiling::function]
+    pub fn begin_query(
+        &self,
+        query_pool: impl Into<AnyQueryPoolNode>,
+        query: u32,
+        flags: vk::QueryControlFlags,
+    ) -> &Self {
+        let query_pool = query_pool.into();
+
+        unsafe {
+            self.device
+                .cmd_begin_query(self.cmd_buf, *self.bindings[query_pool], query, flags);
+        }
+
+        self
+    }
+
+    /// Bind an index buffer to the current pass.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+    /// # use screen_13::driver::graphic::{GraphicPipeline, GraphicPipelineInfo};
+    /// # use screen_13::driver::image::{Image, ImageInfo};
+    /// # use screen_13::driver::shader::Shader;
+    /// # use screen_13::graph::RenderGraph;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let my_frag_code = [0u8; 1];
+    /// # let my_vert_code = [0u8; 1];
+    /// # let vert = Shader::new_vertex(my_vert_code.as_slice());
+    /// # let frag = Shader::new_fragment(my_frag_code.as_slice());
+    /// # let info = GraphicPipelineInfo::default();
+    /// # let my_graphic_pipeline = Arc::new(GraphicPipeline::create(&device, info, [vert, frag])?);
+    /// # let mut my_graph = RenderGraph::new();
+    /// # let info = ImageInfo::image_2d(32, 32, vk::Format::R8G8B8A8_UNORM, vk::ImageUsageFlags::SAMPLED);
+    /// # let swapchain_image = my_graph.bind_node(Image::create(&device, info)?);
+    /// # let buf_info = BufferInfo::device_mem(8, vk::BufferUsageFlags::INDEX_BUFFER);
     /// # let my_idx_buf = Buffer::create(&device, buf_info)?;
     /// # let buf_info = BufferInfo::device_mem(8, vk::BufferUsageFlags::VERTEX_BUFFER);
     /// # let my_vtx_buf = Buffer::create(&device, buf_info)?;
@@ -1272,7 +1874,7 @@ impl<'a> Draw<'a> {
     ///     std::slice::from_raw_parts(&cmd as *const _ as *const _, CMD_SIZE)
     /// };
     ///
-    /// let buf_flags = vk::BufferUsageFlags::STORAGE_BUFFER;
+    /// let buf_flags = vk::BufferUsageFlags::INDIRECT_BUFFER;
     /// let buf = Buffer::create_from_slice(&device, buf_flags, cmd_data)?;
     /// let buf_node = my_graph.bind_node(buf);
     ///
@@ -1281,7 +1883,7 @@ impl<'a> Draw<'a> {
     ///         .store_color(0, swapchain_image)
     ///         .read_node(my_idx_buf)
     ///         .read_node(my_vtx_buf)
-    ///         .read_node(buf_node)
+    ///         .read_indirect_node(buf_node)
     ///         .record_subpass(move |subpass, bindings| {
     ///             subpass.bind_index_buffer(my_idx_buf, vk::IndexType::UINT16)
     ///                    .bind_vertex_buffer(my_vtx_buf)
@@ -1409,6 +2011,231 @@ impl<'a> Draw<'a> {
         self
     }
 
+    /// Draw mesh task work items.
+    ///
+    /// When the command is executed, a global workgroup consisting of
+    /// `group_count_x * group_count_y * group_count_z` local workgroups is assembled. If the bound
+    /// pipeline has a task shader the workgroups are task shader invocations which launch mesh
+    /// shader workgroups, otherwise they are mesh shader invocations.
+    ///
+    /// The bound pipeline must have been created with a mesh shader. See
+    /// [`GraphicPipeline::create`].
+    ///
+    /// # Panics
+    ///
+    /// If the device does not support `VK_EXT_mesh_shader`.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::graphic::{GraphicPipeline, GraphicPipelineInfo};
+    /// # use screen_13::driver::image::{Image, ImageInfo};
+    /// # use screen_13::driver::shader::Shader;
+    /// # use screen_13::graph::RenderGraph;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let my_frag_code = [0u8; 1];
+    /// # let my_mesh_code = [0u8; 1];
+    /// let mesh = Shader::new_mesh(my_mesh_code.as_slice());
+    /// let frag = Shader::new_fragment(my_frag_code.as_slice());
+    /// let info = GraphicPipelineInfo::default();
+    /// let my_mesh_pipeline = Arc::new(GraphicPipeline::create(&device, info, [mesh, frag])?);
+    /// # let mut my_graph = RenderGraph::new();
+    /// # let info = ImageInfo::image_2d(32, 32, vk::Format::R8G8B8A8_UNORM, vk::ImageUsageFlags::SAMPLED);
+    /// # let swapchain_image = my_graph.bind_node(Image::create(&device, info)?);
+    ///
+    /// my_graph.begin_pass("draw meshlets")
+    ///         .bind_pipeline(&my_mesh_pipeline)
+    ///         .store_color(0, swapchain_image)
+    ///         .record_subpass(move |subpass, bindings| {
+    ///             subpass.draw_mesh_tasks(64, 1, 1);
+    ///         });
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    pub fn draw_mesh_tasks(
+        &self,
+        group_count_x: u32,
+        group_count_y: u32,
+        group_count_z: u32,
+    ) -> &Self {
+        unsafe {
+            Self::mesh_shader_ext(self.device).cmd_draw_mesh_tasks(
+                self.cmd_buf,
+                group_count_x,
+                group_count_y,
+                group_count_z,
+            );
+        }
+
+        self
+    }
+
+    /// Draw mesh task work items with indirect parameters.
+    ///
+    /// `draw_mesh_tasks_indirect` behaves similarly to `draw_mesh_tasks` except that the parameters
+    /// are read by the device from `buffer` during execution. `draw_count` draws are executed by
+    /// the command, with parameters taken from `buffer` starting at `offset` and increasing by
+    /// `stride` bytes for each successive draw. The parameters of each draw are encoded in an array
+    /// of [`vk::DrawMeshTasksIndirectCommandEXT`] structures.
+    ///
+    /// The buffer must be read by the pass using [`PipelinePassRef::read_indirect_node`]. If
+    /// `draw_count` is less than or equal to one, `stride` is ignored.
+    ///
+    /// # Panics
+    ///
+    /// If the device does not support `VK_EXT_mesh_shader`.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use std::mem::size_of;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+    /// # use screen_13::driver::graphic::{GraphicPipeline, GraphicPipelineInfo};
+    /// # use screen_13::driver::image::{Image, ImageInfo};
+    /// # use screen_13::driver::shader::Shader;
+    /// # use screen_13::graph::RenderGraph;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let my_frag_code = [0u8; 1];
+    /// # let my_mesh_code = [0u8; 1];
+    /// # let mesh = Shader::new_mesh(my_mesh_code.as_slice());
+    /// # let frag = Shader::new_fragment(my_frag_code.as_slice());
+    /// # let info = GraphicPipelineInfo::default();
+    /// # let my_mesh_pipeline = Arc::new(GraphicPipeline::create(&device, info, [mesh, frag])?);
+    /// # let mut my_graph = RenderGraph::new();
+    /// # let info = ImageInfo::image_2d(32, 32, vk::Format::R8G8B8A8_UNORM, vk::ImageUsageFlags::SAMPLED);
+    /// # let swapchain_image = my_graph.bind_node(Image::create(&device, info)?);
+    /// const CMD_SIZE: usize = size_of::<vk::DrawMeshTasksIndirectCommandEXT>();
+    ///
+    /// let cmd = vk::DrawMeshTasksIndirectCommandEXT {
+    ///     group_count_x: 64,
+    ///     group_count_y: 1,
+    ///     group_count_z: 1,
+    /// };
+    /// let cmd_data = unsafe {
+    ///     std::slice::from_raw_parts(&cmd as *const _ as *const _, CMD_SIZE)
+    /// };
+    ///
+    /// let buf_flags = vk::BufferUsageFlags::INDIRECT_BUFFER;
+    /// let buf = Buffer::create_from_slice(&device, buf_flags, cmd_data)?;
+    /// let buf_node = my_graph.bind_node(buf);
+    ///
+    /// my_graph.begin_pass("draw meshlets")
+    ///         .bind_pipeline(&my_mesh_pipeline)
+    ///         .store_color(0, swapchain_image)
+    ///         .read_indirect_node(buf_node)
+    ///         .record_subpass(move |subpass, bindings| {
+    ///             subpass.draw_mesh_tasks_indirect(buf_node, 0, 1, 0);
+    ///         });
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    pub fn draw_mesh_tasks_indirect(
+        &self,
+        buffer: impl Into<AnyBufferNode>,
+        offset: vk::DeviceSize,
+        draw_count: u32,
+        stride: u32,
+    ) -> &Self {
+        let buffer = buffer.into();
+
+        unsafe {
+            Self::mesh_shader_ext(self.device).cmd_draw_mesh_tasks_indirect(
+                self.cmd_buf,
+                *self.bindings[buffer],
+                offset,
+                draw_count,
+                stride,
+            );
+        }
+
+        self
+    }
+
+    /// Draw mesh task work items with indirect parameters and draw count.
+    ///
+    /// `draw_mesh_tasks_indirect_count` behaves similarly to `draw_mesh_tasks_indirect` except that
+    /// the draw count is read by the device from `count_buf` during execution. The command will
+    /// read an unsigned 32-bit integer from `count_buf` located at `count_buf_offset` and use this
+    /// as the draw count.
+    ///
+    /// `max_draw_count` specifies the maximum number of draws that will be executed. The actual
+    /// number of executed draw calls is the minimum of the count specified in `count_buf` and
+    /// `max_draw_count`.
+    ///
+    /// Both buffers must be read by the pass using [`PipelinePassRef::read_indirect_node`].
+    ///
+    /// # Panics
+    ///
+    /// If the device does not support `VK_EXT_mesh_shader`.
+    #[profiling::function]
+    pub fn draw_mesh_tasks_indirect_count(
+        &self,
+        buffer: impl Into<AnyBufferNode>,
+        offset: vk::DeviceSize,
+        count_buf: impl Into<AnyBufferNode>,
+        count_buf_offset: vk::DeviceSize,
+        max_draw_count: u32,
+        stride: u32,
+    ) -> &Self {
+        let buffer = buffer.into();
+        let count_buf = count_buf.into();
+
+        unsafe {
+            Self::mesh_shader_ext(self.device).cmd_draw_mesh_tasks_indirect_count(
+                self.cmd_buf,
+                *self.bindings[buffer],
+                offset,
+                *self.bindings[count_buf],
+                count_buf_offset,
+                max_draw_count,
+                stride,
+            );
+        }
+
+        self
+    }
+
+    /// Ends a query which was begun using [`Draw::begin_query`].
+    #[profiling::function]
+    pub fn end_query(&self, query_pool: impl Into<AnyQueryPoolNode>, query: u32) -> &Self {
+        let query_pool = query_pool.into();
+
+        unsafe {
+            self.device
+                .cmd_end_query(self.cmd_buf, *self.bindings[query_pool], query);
+        }
+
+        self
+    }
+
+    fn extended_dynamic_state_ext(device: &Device) -> &ext::ExtendedDynamicState {
+        device
+            .extended_dynamic_state_ext
+            .as_ref()
+            .expect("VK_EXT_extended_dynamic_state not supported")
+    }
+
+    fn mesh_shader_ext(device: &Device) -> &ext::MeshShader {
+        device
+            .mesh_shader_ext
+            .as_ref()
+            .expect("VK_EXT_mesh_shader not supported")
+    }
+
     /// Updates push constants.
     ///
     /// Push constants represent a high speed path to modify constant data in pipelines that is
@@ -1480,100 +2307,277 @@ impl<'a> Draw<'a> {
         self.push_constants_offset(0, data)
     }
 
-    /// Updates push constants starting at the given `offset`.
+    /// Updates push constants starting at the given `offset`.
+    ///
+    /// Behaves similary to [`Draw::push_constants`] except that `offset` describes the position at
+    /// which `data` updates the push constants of the currently bound pipeline. This may be used to
+    /// update a subset or single field of previously set push constant data.
+    ///
+    /// # Device limitations
+    ///
+    /// See
+    /// [`device.physical_device.props.limits.max_push_constants_size`](vk::PhysicalDeviceLimits)
+    /// for the limits of the current device. You may also check [gpuinfo.org] for a listing of
+    /// reported limits on other devices.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// # inline_spirv::inline_spirv!(r#"
+    /// #version 450
+    ///
+    /// layout(push_constant) uniform PushConstants {
+    ///     layout(offset = 0) uint some_val1;
+    ///     layout(offset = 4) uint some_val2;
+    /// } push_constants;
+    ///
+    /// void main()
+    /// {
+    ///     // TODO: Add code!
+    /// }
+    /// # "#, vert);
+    /// ```
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::graphic::{GraphicPipeline, GraphicPipelineInfo};
+    /// # use screen_13::driver::image::{Image, ImageInfo};
+    /// # use screen_13::graph::RenderGraph;
+    /// # use screen_13::driver::shader::Shader;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let my_frag_code = [0u8; 1];
+    /// # let my_vert_code = [0u8; 1];
+    /// # let vert = Shader::new_vertex(my_vert_code.as_slice());
+    /// # let frag = Shader::new_fragment(my_frag_code.as_slice());
+    /// # let info = GraphicPipelineInfo::default();
+    /// # let my_graphic_pipeline = Arc::new(GraphicPipeline::create(&device, info, [vert, frag])?);
+    /// # let info = ImageInfo::image_2d(32, 32, vk::Format::R8G8B8A8_UNORM, vk::ImageUsageFlags::SAMPLED);
+    /// # let swapchain_image = Image::create(&device, info)?;
+    /// # let mut my_graph = RenderGraph::new();
+    /// # let swapchain_image = my_graph.bind_node(swapchain_image);
+    /// my_graph.begin_pass("draw a quad")
+    ///         .bind_pipeline(&my_graphic_pipeline)
+    ///         .store_color(0, swapchain_image)
+    ///         .record_subpass(move |subpass, bindings| {
+    ///             subpass.push_constants(&[0x00, 0x00])
+    ///                    .draw(6, 1, 0, 0)
+    ///                    .push_constants_offset(4, &[0xff])
+    ///                    .draw(6, 1, 0, 0);
+    ///         });
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [gpuinfo.org]: https://vulkan.gpuinfo.org/displaydevicelimit.php?name=maxPushConstantsSize&platform=all
+    #[profiling::function]
+    pub fn push_constants_offset(&self, offset: u32, data: &[u8]) -> &Self {
+        for push_const in self.push_constants.iter() {
+            // Determine the range of the overall pipline push constants which overlap with `data`
+            let push_const_end = push_const.offset + push_const.size;
+            let data_end = offset + data.len() as u32;
+            let end = data_end.min(push_const_end);
+            let start = offset.max(push_const.offset);
+
+            if end > start {
+                trace!(
+                    "      push constants {:?} {}..{}",
+                    push_const.stage_flags,
+                    start,
+                    end
+                );
+
+                unsafe {
+                    self.device.cmd_push_constants(
+                        self.cmd_buf,
+                        self.layout,
+                        push_const.stage_flags,
+                        start,
+                        &data[(start - offset) as usize..(end - offset) as usize],
+                    );
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Set the blend constants dynamically for a pass.
+    ///
+    /// The constants are used by blend factors such as `vk::BlendFactor::CONSTANT_COLOR` and are
+    /// zero when the pipeline is bound.
+    #[profiling::function]
+    pub fn set_blend_constants(&self, blend_constants: [f32; 4]) -> &Self {
+        unsafe {
+            self.device
+                .cmd_set_blend_constants(self.cmd_buf, &blend_constants);
+        }
+
+        self
+    }
+
+    /// Set the cull mode dynamically for a pass.
+    ///
+    /// The cull mode is [`GraphicPipelineInfo::cull_mode`] when the pipeline is bound.
+    ///
+    /// # Panics
+    ///
+    /// If the device does not support `VK_EXT_extended_dynamic_state`.
+    ///
+    /// [`GraphicPipelineInfo::cull_mode`]: crate::driver::graphic::GraphicPipelineInfo::cull_mode
+    #[profiling::function]
+    pub fn set_cull_mode(&self, cull_mode: vk::CullModeFlags) -> &Self {
+        unsafe {
+            Self::extended_dynamic_state_ext(self.device)
+                .cmd_set_cull_mode(self.cmd_buf, cull_mode);
+        }
+
+        self
+    }
+
+    /// Set the depth bias factors dynamically for a pass.
+    ///
+    /// The pipeline must have been created with [`GraphicPipelineInfo::depth_bias`] enabled. The
+    /// factors are zero when the pipeline is bound.
+    ///
+    /// [`GraphicPipelineInfo::depth_bias`]: crate::driver::graphic::GraphicPipelineInfo::depth_bias
+    #[profiling::function]
+    pub fn set_depth_bias(&self, constant_factor: f32, clamp: f32, slope_factor: f32) -> &Self {
+        unsafe {
+            self.device
+                .cmd_set_depth_bias(self.cmd_buf, constant_factor, clamp, slope_factor);
+        }
+
+        self
+    }
+
+    /// Set the depth bounds dynamically for a pass.
+    ///
+    /// The bounds are those of the [`DepthStencilMode`] of the pass when the pipeline is bound.
+    #[profiling::function]
+    pub fn set_depth_bounds(&self, bounds: Range<f32>) -> &Self {
+        unsafe {
+            self.device
+                .cmd_set_depth_bounds(self.cmd_buf, bounds.start, bounds.end);
+        }
+
+        self
+    }
+
+    /// Enable or disable the depth bounds test dynamically for a pass.
+    ///
+    /// # Panics
+    ///
+    /// If the device does not support `VK_EXT_extended_dynamic_state`.
+    #[profiling::function]
+    pub fn set_depth_bounds_test(&self, enable: bool) -> &Self {
+        unsafe {
+            Self::extended_dynamic_state_ext(self.device)
+                .cmd_set_depth_bounds_test_enable(self.cmd_buf, enable);
+        }
+
+        self
+    }
+
+    /// Set the depth comparison operator dynamically for a pass.
+    ///
+    /// # Panics
+    ///
+    /// If the device does not support `VK_EXT_extended_dynamic_state`.
+    #[profiling::function]
+    pub fn set_depth_compare_op(&self, compare_op: vk::CompareOp) -> &Self {
+        unsafe {
+            Self::extended_dynamic_state_ext(self.device)
+                .cmd_set_depth_compare_op(self.cmd_buf, compare_op);
+        }
+
+        self
+    }
+
+    /// Enable or disable the depth test dynamically for a pass.
+    ///
+    /// # Panics
+    ///
+    /// If the device does not support `VK_EXT_extended_dynamic_state`.
+    #[profiling::function]
+    pub fn set_depth_test(&self, enable: bool) -> &Self {
+        unsafe {
+            Self::extended_dynamic_state_ext(self.device)
+                .cmd_set_depth_test_enable(self.cmd_buf, enable);
+        }
+
+        self
+    }
+
+    /// Enable or disable depth writes dynamically for a pass.
+    ///
+    /// # Panics
+    ///
+    /// If the device does not support `VK_EXT_extended_dynamic_state`.
+    #[profiling::function]
+    pub fn set_depth_write(&self, enable: bool) -> &Self {
+        unsafe {
+            Self::extended_dynamic_state_ext(self.device)
+                .cmd_set_depth_write_enable(self.cmd_buf, enable);
+        }
+
+        self
+    }
+
+    /// Set the front-facing orientation of polygons dynamically for a pass.
     ///
-    /// Behaves similary to [`Draw::push_constants`] except that `offset` describes the position at
-    /// which `data` updates the push constants of the currently bound pipeline. This may be used to
-    /// update a subset or single field of previously set push constant data.
+    /// The orientation is [`GraphicPipelineInfo::front_face`] when the pipeline is bound.
     ///
-    /// # Device limitations
+    /// # Panics
     ///
-    /// See
-    /// [`device.physical_device.props.limits.max_push_constants_size`](vk::PhysicalDeviceLimits)
-    /// for the limits of the current device. You may also check [gpuinfo.org] for a listing of
-    /// reported limits on other devices.
+    /// If the device does not support `VK_EXT_extended_dynamic_state`.
     ///
-    /// # Examples
+    /// [`GraphicPipelineInfo::front_face`]: crate::driver::graphic::GraphicPipelineInfo::front_face
+    #[profiling::function]
+    pub fn set_front_face(&self, front_face: vk::FrontFace) -> &Self {
+        unsafe {
+            Self::extended_dynamic_state_ext(self.device)
+                .cmd_set_front_face(self.cmd_buf, front_face);
+        }
+
+        self
+    }
+
+    /// Set the width of rasterized lines dynamically for a pass.
     ///
-    /// Basic usage:
+    /// The width is `1.0` when the pipeline is bound. Other widths require the
+    /// [`Vulkan10Features::wide_lines`] feature.
     ///
-    /// ```
-    /// # inline_spirv::inline_spirv!(r#"
-    /// #version 450
+    /// [`Vulkan10Features::wide_lines`]: crate::driver::physical_device::Vulkan10Features::wide_lines
+    #[profiling::function]
+    pub fn set_line_width(&self, line_width: f32) -> &Self {
+        unsafe {
+            self.device.cmd_set_line_width(self.cmd_buf, line_width);
+        }
+
+        self
+    }
+
+    /// Set the primitive topology dynamically for a pass.
     ///
-    /// layout(push_constant) uniform PushConstants {
-    ///     layout(offset = 0) uint some_val1;
-    ///     layout(offset = 4) uint some_val2;
-    /// } push_constants;
+    /// The topology is [`GraphicPipelineInfo::topology`] when the pipeline is bound and must be of
+    /// the same topology class, such as triangles or lines. Mesh shader pipelines do not have a
+    /// primitive topology.
     ///
-    /// void main()
-    /// {
-    ///     // TODO: Add code!
-    /// }
-    /// # "#, vert);
-    /// ```
+    /// # Panics
     ///
-    /// ```no_run
-    /// # use std::sync::Arc;
-    /// # use ash::vk;
-    /// # use screen_13::driver::DriverError;
-    /// # use screen_13::driver::device::{Device, DeviceInfo};
-    /// # use screen_13::driver::graphic::{GraphicPipeline, GraphicPipelineInfo};
-    /// # use screen_13::driver::image::{Image, ImageInfo};
-    /// # use screen_13::graph::RenderGraph;
-    /// # use screen_13::driver::shader::Shader;
-    /// # fn main() -> Result<(), DriverError> {
-    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
-    /// # let my_frag_code = [0u8; 1];
-    /// # let my_vert_code = [0u8; 1];
-    /// # let vert = Shader::new_vertex(my_vert_code.as_slice());
-    /// # let frag = Shader::new_fragment(my_frag_code.as_slice());
-    /// # let info = GraphicPipelineInfo::default();
-    /// # let my_graphic_pipeline = Arc::new(GraphicPipeline::create(&device, info, [vert, frag])?);
-    /// # let info = ImageInfo::image_2d(32, 32, vk::Format::R8G8B8A8_UNORM, vk::ImageUsageFlags::SAMPLED);
-    /// # let swapchain_image = Image::create(&device, info)?;
-    /// # let mut my_graph = RenderGraph::new();
-    /// # let swapchain_image = my_graph.bind_node(swapchain_image);
-    /// my_graph.begin_pass("draw a quad")
-    ///         .bind_pipeline(&my_graphic_pipeline)
-    ///         .store_color(0, swapchain_image)
-    ///         .record_subpass(move |subpass, bindings| {
-    ///             subpass.push_constants(&[0x00, 0x00])
-    ///                    .draw(6, 1, 0, 0)
-    ///                    .push_constants_offset(4, &[0xff])
-    ///                    .draw(6, 1, 0, 0);
-    ///         });
-    /// # Ok(()) }
-    /// ```
+    /// If the device does not support `VK_EXT_extended_dynamic_state`.
     ///
-    /// [gpuinfo.org]: https://vulkan.gpuinfo.org/displaydevicelimit.php?name=maxPushConstantsSize&platform=all
+    /// [`GraphicPipelineInfo::topology`]: crate::driver::graphic::GraphicPipelineInfo::topology
     #[profiling::function]
-    pub fn push_constants_offset(&self, offset: u32, data: &[u8]) -> &Self {
-        for push_const in self.push_constants.iter() {
-            // Determine the range of the overall pipline push constants which overlap with `data`
-            let push_const_end = push_const.offset + push_const.size;
-            let data_end = offset + data.len() as u32;
-            let end = data_end.min(push_const_end);
-            let start = offset.max(push_const.offset);
-
-            if end > start {
-                trace!(
-                    "      push constants {:?} {}..{}",
-                    push_const.stage_flags,
-                    start,
-                    end
-                );
-
-                unsafe {
-                    self.device.cmd_push_constants(
-                        self.cmd_buf,
-                        self.layout,
-                        push_const.stage_flags,
-                        start,
-                        &data[(start - offset) as usize..(end - offset) as usize],
-                    );
-                }
-            }
+    pub fn set_primitive_topology(&self, topology: vk::PrimitiveTopology) -> &Self {
+        unsafe {
+            Self::extended_dynamic_state_ext(self.device)
+                .cmd_set_primitive_topology(self.cmd_buf, topology);
         }
 
         self
@@ -1626,6 +2630,145 @@ impl<'a> Draw<'a> {
         self
     }
 
+    /// Set the stencil compare mask of the faces given by `face_mask` dynamically for a pass.
+    ///
+    /// The masks are those of the [`DepthStencilMode`] of the pass when the pipeline is bound.
+    #[profiling::function]
+    pub fn set_stencil_compare_mask(
+        &self,
+        face_mask: vk::StencilFaceFlags,
+        compare_mask: u32,
+    ) -> &Self {
+        unsafe {
+            self.device
+                .cmd_set_stencil_compare_mask(self.cmd_buf, face_mask, compare_mask);
+        }
+
+        self
+    }
+
+    /// Set the stencil test operations of the faces given by `face_mask` dynamically for a pass.
+    ///
+    /// # Panics
+    ///
+    /// If the device does not support `VK_EXT_extended_dynamic_state`.
+    #[profiling::function]
+    pub fn set_stencil_op(
+        &self,
+        face_mask: vk::StencilFaceFlags,
+        fail_op: vk::StencilOp,
+        pass_op: vk::StencilOp,
+        depth_fail_op: vk::StencilOp,
+        compare_op: vk::CompareOp,
+    ) -> &Self {
+        unsafe {
+            Self::extended_dynamic_state_ext(self.device).cmd_set_stencil_op(
+                self.cmd_buf,
+                face_mask,
+                fail_op,
+                pass_op,
+                depth_fail_op,
+                compare_op,
+            );
+        }
+
+        self
+    }
+
+    /// Set the stencil reference value of the faces given by `face_mask` dynamically for a pass.
+    ///
+    /// The values are those of the [`DepthStencilMode`] of the pass when the pipeline is bound.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::graphic::{DepthStencilMode, GraphicPipeline, GraphicPipelineInfo, StencilMode};
+    /// # use screen_13::driver::image::{Image, ImageInfo};
+    /// # use screen_13::driver::shader::Shader;
+    /// # use screen_13::graph::RenderGraph;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let my_frag_code = [0u8; 1];
+    /// # let my_vert_code = [0u8; 1];
+    /// # let vert = Shader::new_vertex(my_vert_code.as_slice());
+    /// # let frag = Shader::new_fragment(my_frag_code.as_slice());
+    /// # let info = GraphicPipelineInfo::default();
+    /// # let my_graphic_pipeline = Arc::new(GraphicPipeline::create(&device, info, [vert, frag])?);
+    /// # let mut my_graph = RenderGraph::new();
+    /// # let info = ImageInfo::image_2d(32, 32, vk::Format::D24_UNORM_S8_UINT, vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT);
+    /// # let my_stencil_image = my_graph.bind_node(Image::create(&device, info)?);
+    /// let stencil = StencilMode {
+    ///     compare_op: vk::CompareOp::EQUAL,
+    ///     compare_mask: 0xff,
+    ///     ..StencilMode::IGNORE
+    /// };
+    /// let depth_stencil = DepthStencilMode {
+    ///     back: stencil,
+    ///     front: stencil,
+    ///     stencil_test: true,
+    ///     ..DepthStencilMode::IGNORE
+    /// };
+    ///
+    /// my_graph.begin_pass("draw each stencilled layer")
+    ///         .bind_pipeline(&my_graphic_pipeline)
+    ///         .set_depth_stencil(depth_stencil)
+    ///         .load_depth_stencil(my_stencil_image)
+    ///         .record_subpass(move |subpass, bindings| {
+    ///             for layer in 0..4 {
+    ///                 subpass.set_stencil_reference(vk::StencilFaceFlags::FRONT_AND_BACK, layer)
+    ///                        .draw(6, 1, 0, 0);
+    ///             }
+    ///         });
+    /// # Ok(()) }
+    /// ```
+    #[profiling::function]
+    pub fn set_stencil_reference(&self, face_mask: vk::StencilFaceFlags, reference: u32) -> &Self {
+        unsafe {
+            self.device
+                .cmd_set_stencil_reference(self.cmd_buf, face_mask, reference);
+        }
+
+        self
+    }
+
+    /// Enable or disable the stencil test dynamically for a pass.
+    ///
+    /// # Panics
+    ///
+    /// If the device does not support `VK_EXT_extended_dynamic_state`.
+    #[profiling::function]
+    pub fn set_stencil_test(&self, enable: bool) -> &Self {
+        unsafe {
+            Self::extended_dynamic_state_ext(self.device)
+                .cmd_set_stencil_test_enable(self.cmd_buf, enable);
+        }
+
+        self
+    }
+
+    /// Set the stencil write mask of the faces given by `face_mask` dynamically for a pass.
+    ///
+    /// The masks are those of the [`DepthStencilMode`] of the pass when the pipeline is bound.
+    #[profiling::function]
+    pub fn set_stencil_write_mask(
+        &self,
+        face_mask: vk::StencilFaceFlags,
+        write_mask: u32,
+    ) -> &Self {
+        unsafe {
+            self.device
+                .cmd_set_stencil_write_mask(self.cmd_buf, face_mask, write_mask);
+        }
+
+        self
+    }
+
     /// Set the viewport dynamically for a pass.
     #[profiling::function]
     pub fn set_viewport(
@@ -1700,8 +2843,10 @@ impl<'a> PassRef<'a> {
     pub(super) fn new(graph: &'a mut RenderGraph, name: String) -> PassRef<'a> {
         let pass_idx = graph.passes.len();
         graph.passes.push(Pass {
+            condition: None,
             execs: vec![Default::default()], // We start off with a default execution!
             name,
+            queue: QueueType::default(),
             render_area: None,
         });
 
@@ -1736,10 +2881,10 @@ impl<'a> PassRef<'a> {
         let binding = &self.graph.bindings[idx];
 
         let mut node_access_range = None;
-        if let Some(buf) = binding.as_driver_buffer() {
-            node_access_range = Some(Subresource::Buffer((0..buf.info.size).into()));
-        } else if let Some(image) = binding.as_driver_image() {
-            node_access_range = Some(Subresource::Image(image.info.default_view_info().into()))
+        if let Some(buf_info) = binding.buffer_info() {
+            node_access_range = Some(Subresource::Buffer((0..buf_info.size).into()));
+        } else if let Some(image_info) = binding.image_info() {
+            node_access_range = Some(Subresource::Image(image_info.default_view_info().into()))
         }
 
         self.push_node_access(node, access, node_access_range);
@@ -1923,6 +3068,68 @@ impl<'a> PassRef<'a> {
         self
     }
 
+    /// Predicates the draw and dispatch commands of this pass on the 32-bit value stored at
+    /// `offset` bytes into `buffer`: the commands are skipped when the value is zero.
+    ///
+    /// The value is read by the device when the pass executes, so shaders of earlier passes may
+    /// write it, for example to skip the work of occluded objects without reading the results back
+    /// to the host. `offset` must be a multiple of four and the buffer must have been created with
+    /// the `CONDITIONAL_RENDERING_EXT` usage flag; the buffer must not otherwise be accessed by
+    /// this pass.
+    ///
+    /// Passes are predicated using `VK_EXT_conditional_rendering` where the device supports it
+    /// (see [`PhysicalDevice::conditional_rendering_features`]). Other devices support conditional
+    /// compute passes only: each dispatch is recorded as an indirect dispatch whose group counts
+    /// are zeroed when the value is zero, which requires the buffer to also have the
+    /// `TRANSFER_SRC` usage flag. Resolving a conditional graphic pass on such a device returns
+    /// [`DriverError::Unsupported`].
+    ///
+    /// Copy, clear and other transfer commands recorded by the pass are not predicated.
+    ///
+    /// [`DriverError::Unsupported`]: crate::driver::DriverError::Unsupported
+    /// [`PhysicalDevice::conditional_rendering_features`]: crate::driver::physical_device::PhysicalDevice::conditional_rendering_features
+    pub fn set_condition(
+        mut self,
+        buffer: impl Into<AnyBufferNode>,
+        offset: vk::DeviceSize,
+    ) -> Self {
+        let buffer = buffer.into();
+
+        debug_assert_eq!(offset % 4, 0, "unaligned condition offset");
+
+        self.assert_bound_graph_node(buffer);
+
+        // The condition is read before the first execution of the pass; the resolver extends this
+        // access to the stages which actually read the value
+        let node_idx = buffer.index();
+        let access = SubresourceAccess {
+            access: AccessType::IndirectBuffer,
+            subresource: Some(Subresource::Buffer((offset..offset + 4).into())),
+        };
+        let pass = self.as_mut();
+        pass.execs[0]
+            .accesses
+            .entry(node_idx)
+            .or_insert([access, access]);
+        pass.condition = Some(Condition { node_idx, offset });
+
+        self
+    }
+
+    /// Sets the kind of device queue which executes this pass.
+    ///
+    /// Compute, ray trace, and general passes may execute on a dedicated queue family so that
+    /// their work overlaps with graphic work; passes which use a graphic pipeline always execute on
+    /// the queue the graph is submitted to. The resolver orders the queues using semaphores
+    /// wherever passes on different queues access a common node.
+    ///
+    /// Passes execute on [`QueueType::Graphics`] by default.
+    pub fn set_queue(mut self, queue: QueueType) -> Self {
+        self.as_mut().queue = queue;
+
+        self
+    }
+
     /// Finalize the recording of this pass and return to the `RenderGraph` where you may record
     /// additional passes.
     pub fn submit_pass(self) -> &'a mut RenderGraph {
@@ -2068,10 +3275,10 @@ where
         let binding = &self.pass.graph.bindings[idx];
 
         let mut node_access_range = None;
-        if let Some(buf) = binding.as_driver_buffer() {
-            node_access_range = Some(Subresource::Buffer((0..buf.info.size).into()));
-        } else if let Some(image) = binding.as_driver_image() {
-            node_access_range = Some(Subresource::Image(image.info.default_view_info().into()))
+        if let Some(buf_info) = binding.buffer_info() {
+            node_access_range = Some(Subresource::Buffer((0..buf_info.size).into()));
+        } else if let Some(image_info) = binding.image_info() {
+            node_access_range = Some(Subresource::Image(image_info.default_view_info().into()))
         }
 
         self.pass.push_node_access(node, access, node_access_range);
@@ -2223,6 +3430,19 @@ where
         self.access_descriptor_subrange(descriptor, node, access, view_info, subresource)
     }
 
+    /// Informs the pass that the next recorded command buffer will read the given buffer `node` as
+    /// the parameters or draw count of indirect commands, using [`AccessType::IndirectBuffer`].
+    ///
+    /// The resolver synchronizes the read with the indirect command stage and extends the usage of
+    /// graph-owned and transient buffers with `vk::BufferUsageFlags::INDIRECT_BUFFER`.
+    ///
+    /// This function must be called for `node` before it is read within a `record` function by
+    /// commands such as [`Draw::draw_indirect`], [`Draw::draw_mesh_tasks_indirect`] or
+    /// [`Compute::dispatch_indirect`].
+    pub fn read_indirect_node(self, node: impl Node + Information) -> Self {
+        self.access_node(node, AccessType::IndirectBuffer)
+    }
+
     /// Informs the pass that the next recorded command buffer will read the given `node`.
     ///
     /// The [`AccessType`] is inferred by the currently bound pipeline. See [`Access`] for details.
@@ -2277,6 +3497,29 @@ where
         self.access_node_subrange_mut(node, access, subresource);
     }
 
+    /// Predicates the draw and dispatch commands of this pass on the 32-bit value stored at
+    /// `offset` bytes into `buffer`.
+    ///
+    /// See [`PassRef::set_condition`].
+    pub fn set_condition(
+        mut self,
+        buffer: impl Into<AnyBufferNode>,
+        offset: vk::DeviceSize,
+    ) -> Self {
+        self.pass = self.pass.set_condition(buffer, offset);
+
+        self
+    }
+
+    /// Sets the kind of device queue which executes this pass.
+    ///
+    /// See [`PassRef::set_queue`].
+    pub fn set_queue(mut self, queue: QueueType) -> Self {
+        self.pass.as_mut().queue = queue;
+
+        self
+    }
+
     /// Finalizes a pass and returns the render graph so that additional passes may be added.
     pub fn submit_pass(self) -> &'a mut RenderGraph {
         self.pass.submit_pass()
@@ -2909,10 +4152,7 @@ impl<'a> PipelinePassRef<'a, GraphicPipeline> {
     }
 
     fn image_info(&self, node_idx: NodeIndex) -> (vk::Format, SampleCount) {
-        let image_info = self.pass.graph.bindings[node_idx]
-            .as_driver_image()
-            .unwrap()
-            .info;
+        let image_info = *self.pass.graph.bindings[node_idx].image_info().unwrap();
 
         (image_info.fmt, image_info.sample_count)
     }
@@ -3886,7 +5126,8 @@ impl<'a> RayTrace<'a> {
     /// Ray traces using the currently-bound [`RayTracePipeline`] and the given shader binding
     /// tables.
     ///
-    /// Shader binding tables must be constructed according to this [example].
+    /// Shader binding tables may be created using [`ShaderBindingTable`] and passed to
+    /// [`Self::trace_rays_with_table`], or constructed manually according to this [example].
     ///
     /// # Examples
     ///
@@ -3995,10 +5236,53 @@ impl<'a> RayTrace<'a> {
 
         self
     }
+
+    /// Ray traces using the currently-bound [`RayTracePipeline`] and the regions of the given
+    /// shader binding table.
+    ///
+    /// See [`Self::trace_rays_indirect`].
+    #[profiling::function]
+    pub fn trace_rays_indirect_with_table(
+        &self,
+        shader_binding_table: &ShaderBindingTable,
+        indirect_device_address: vk::DeviceAddress,
+    ) -> &Self {
+        self.trace_rays_indirect(
+            &shader_binding_table.ray_gen,
+            &shader_binding_table.miss,
+            &shader_binding_table.hit,
+            &shader_binding_table.callable,
+            indirect_device_address,
+        )
+    }
+
+    /// Ray traces using the currently-bound [`RayTracePipeline`] and the regions of the given
+    /// shader binding table.
+    ///
+    /// The buffer of the table must be accessed by the pass; see [`ShaderBindingTable`] for an
+    /// example.
+    #[profiling::function]
+    pub fn trace_rays_with_table(
+        &self,
+        shader_binding_table: &ShaderBindingTable,
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) -> &Self {
+        self.trace_rays(
+            &shader_binding_table.ray_gen,
+            &shader_binding_table.miss,
+            &shader_binding_table.hit,
+            &shader_binding_table.callable,
+            width,
+            height,
+            depth,
+        )
+    }
 }
 
 /// Describes a portion of a resource which is bound.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Subresource {
     /// Acceleration structures are bound whole.
     AccelerationStructure,
@@ -4115,6 +5399,16 @@ impl View for SwapchainImageNode {
     type Subresource = ImageSubresource;
 }
 
+impl View for TransientBufferNode {
+    type Information = BufferSubresource;
+    type Subresource = BufferSubresource;
+}
+
+impl View for TransientImageNode {
+    type Information = ImageViewInfo;
+    type Subresource = ImageSubresource;
+}
+
 /// Describes the interpretation of a resource.
 #[derive(Debug)]
 pub enum ViewType {
@@ -0,0 +1,375 @@
+//! Host access to data copied from render graph nodes after execution.
+
+use {
+    crate::driver::{buffer::Buffer, device::Device, DriverError},
+    ash::vk,
+    log::warn,
+    std::{
+        future::Future,
+        ops::{Deref, DerefMut},
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Poll, Waker},
+        thread::spawn,
+    },
+};
+
+#[cfg(feature = "parking_lot")]
+use parking_lot::{Mutex, RwLock};
+
+#[cfg(not(feature = "parking_lot"))]
+use std::sync::{Mutex, RwLock};
+
+/// A handle to data which a render graph copies into host-visible memory.
+///
+/// Readbacks are returned by [`RenderGraph::read_buffer`](super::RenderGraph::read_buffer) and
+/// [`RenderGraph::read_image`](super::RenderGraph::read_image). The data is available once the
+/// command buffer returned by [`Resolver::submit`](super::Resolver::submit), or a similar function,
+/// has executed. Readbacks may be polled using [`Self::try_read`], blocked on using [`Self::wait`],
+/// or awaited as a [`Future`].
+///
+/// The data is copied into a staging buffer created by the graph. The buffer is cached by the
+/// submitted command buffer and is reused once the command buffer has executed and every clone of
+/// this handle has been dropped.
+///
+/// Readbacks of graphs which are dropped without being submitted, such as graphs which are only
+/// recorded using [`Resolver::record_node`](super::Resolver::record_node), return
+/// [`DriverError::InvalidData`].
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use ash::vk;
+/// # use screen_13::driver::DriverError;
+/// # use screen_13::driver::device::{Device, DeviceInfo};
+/// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+/// # use screen_13::graph::RenderGraph;
+/// # use screen_13::pool::lazy::LazyPool;
+/// # fn main() -> Result<(), DriverError> {
+/// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+/// # let mut pool = LazyPool::new(&device);
+/// # let info = BufferInfo::device_mem(16, vk::BufferUsageFlags::TRANSFER_DST);
+/// # let buf = Buffer::create(&device, info)?;
+/// let mut my_graph = RenderGraph::new();
+/// let my_buf = my_graph.bind_node(buf);
+/// my_graph.fill_buffer(my_buf, 0x01);
+///
+/// let readback = my_graph.read_buffer(my_buf);
+/// my_graph.resolve().submit(&mut pool, 0, 0)?;
+///
+/// assert_eq!(readback.wait()?, [1, 0, 0, 0].repeat(4));
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Readback {
+    state: Arc<ReadbackState>,
+}
+
+impl Readback {
+    pub(super) fn new(len: usize) -> (Self, PendingReadback) {
+        let state = Arc::new(ReadbackState {
+            fence: RwLock::new(None),
+            inner: Mutex::new(ReadbackInner {
+                buffer: None,
+                is_waiting: false,
+                len,
+                status: ReadbackStatus::Recording,
+            }),
+            waker: Default::default(),
+        });
+
+        (
+            Self {
+                state: Arc::clone(&state),
+            },
+            PendingReadback { state },
+        )
+    }
+
+    /// Returns `true` after the GPU has executed the submitted graph.
+    ///
+    /// See [`Self::wait`] to block while checking.
+    #[profiling::function]
+    pub fn has_executed(&self) -> Result<bool, DriverError> {
+        self.state.update(false)
+    }
+
+    /// Returns a copy of the data if the GPU has executed the submitted graph.
+    ///
+    /// See [`Self::wait`] to block while checking.
+    pub fn try_read(&self) -> Result<Option<Vec<u8>>, DriverError> {
+        self.try_read_with(<[u8]>::to_vec)
+    }
+
+    /// Calls `f` with the mapped data if the GPU has executed the submitted graph.
+    ///
+    /// The data is aligned to at least the alignment of the texel or buffer element types which
+    /// were copied, so it may be cast to a typed slice.
+    #[profiling::function]
+    pub fn try_read_with<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<Option<R>, DriverError> {
+        Ok(self
+            .state
+            .update(false)?
+            .then(|| f(self.state.lock().data())))
+    }
+
+    /// Stalls by blocking the current thread until the GPU has executed the submitted graph and
+    /// returns a copy of the data.
+    ///
+    /// Returns [`DriverError::InvalidData`] if the graph has not been submitted.
+    pub fn wait(&self) -> Result<Vec<u8>, DriverError> {
+        self.wait_with(<[u8]>::to_vec)
+    }
+
+    /// Stalls by blocking the current thread until the GPU has executed the submitted graph and
+    /// calls `f` with the mapped data.
+    ///
+    /// See [`Self::try_read_with`].
+    #[profiling::function]
+    pub fn wait_with<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<R, DriverError> {
+        if self.state.update(true)? {
+            Ok(f(self.state.lock().data()))
+        } else {
+            warn!("render graph has not been submitted");
+
+            Err(DriverError::InvalidData)
+        }
+    }
+}
+
+impl Future for Readback {
+    type Output = Result<Vec<u8>, DriverError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        *self.state.lock_waker() = Some(cx.waker().clone());
+
+        match self.state.update(false) {
+            Err(err) => Poll::Ready(Err(err)),
+            Ok(true) => Poll::Ready(Ok(self.state.lock().data().to_vec())),
+            Ok(false) => {
+                // Submitted readbacks are waited on by one thread, which is shared by every clone
+                // and wakes the most recent poll once the fence has been signalled
+                let mut inner = self.state.lock();
+                if matches!(inner.status, ReadbackStatus::Submitted) && !inner.is_waiting {
+                    inner.is_waiting = true;
+
+                    let state = Arc::clone(&self.state);
+                    spawn(move || {
+                        if let Err(err) = state.update(true) {
+                            warn!("unable to wait for readback: {err}");
+                        }
+
+                        state.wake();
+                    });
+                }
+
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ReadbackInner {
+    buffer: Option<Arc<Buffer>>,
+    is_waiting: bool,
+    len: usize,
+    status: ReadbackStatus,
+}
+
+impl ReadbackInner {
+    fn data(&self) -> &[u8] {
+        &Buffer::mapped_slice(self.buffer.as_ref().unwrap())[0..self.len]
+    }
+}
+
+#[derive(Debug)]
+struct ReadbackState {
+    /// The fence of the submitted command buffer, which is cleared once the command buffer drops
+    /// its [`ReadbackFence`]. Waiting on the fence holds a read lock so that the fence remains valid
+    /// without blocking the other functions of the readback.
+    fence: RwLock<Option<(Arc<Device>, vk::Fence)>>,
+    inner: Mutex<ReadbackInner>,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl ReadbackState {
+    fn lock(&self) -> impl DerefMut<Target = ReadbackInner> + '_ {
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let inner = self.inner.lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let inner = inner.unwrap();
+
+        inner
+    }
+
+    fn lock_waker(&self) -> impl DerefMut<Target = Option<Waker>> + '_ {
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let waker = self.waker.lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let waker = waker.unwrap();
+
+        waker
+    }
+
+    fn read_fence(&self) -> impl Deref<Target = Option<(Arc<Device>, vk::Fence)>> + '_ {
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let fence = self.fence.read();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let fence = fence.unwrap();
+
+        fence
+    }
+
+    // Returns `true` once the GPU has executed the submitted graph, optionally waiting for it
+    fn update(&self, wait: bool) -> Result<bool, DriverError> {
+        match self.lock().status {
+            ReadbackStatus::Recording => return Ok(false),
+            ReadbackStatus::Abandoned => {
+                warn!("render graph was dropped without being submitted");
+
+                return Err(DriverError::InvalidData);
+            }
+            ReadbackStatus::Submitted => (),
+            ReadbackStatus::Executed => return Ok(true),
+        }
+
+        // The fence is set before the status, so submitted readbacks without a fence have already
+        // been dropped by the command buffer
+        let is_executed = match self.read_fence().as_ref() {
+            Some((device, fence)) if wait => {
+                Device::wait_for_fence(device, fence)?;
+
+                true
+            }
+            Some((device, fence)) => unsafe { device.get_fence_status(*fence) }.map_err(|err| {
+                warn!("{err}");
+
+                DriverError::InvalidData
+            })?,
+            None => true,
+        };
+
+        if is_executed {
+            self.lock().status = ReadbackStatus::Executed;
+        }
+
+        Ok(is_executed)
+    }
+
+    fn wake(&self) {
+        if let Some(waker) = self.lock_waker().take() {
+            waker.wake();
+        }
+    }
+
+    fn write_fence(&self) -> impl DerefMut<Target = Option<(Arc<Device>, vk::Fence)>> + '_ {
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let fence = self.fence.write();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let fence = fence.unwrap();
+
+        fence
+    }
+}
+
+#[derive(Debug)]
+enum ReadbackStatus {
+    /// The graph has not yet been submitted.
+    Recording,
+
+    /// The graph was dropped without being submitted.
+    Abandoned,
+
+    /// The graph has been submitted; the fence remains valid until the command buffer drops its
+    /// [`ReadbackFence`], which happens after the fence has been signalled.
+    Submitted,
+
+    /// The GPU has executed the graph.
+    Executed,
+}
+
+/// The side of a [`Readback`] which is held by a render graph until it is submitted.
+#[derive(Debug)]
+pub(super) struct PendingReadback {
+    state: Arc<ReadbackState>,
+}
+
+impl PendingReadback {
+    /// Provides the staging buffer and the fence which is signalled once it has been written. The
+    /// returned value must be dropped by the command buffer after the fence has been signalled.
+    pub fn submit(
+        self,
+        buffer: Arc<Buffer>,
+        device: &Arc<Device>,
+        fence: vk::Fence,
+    ) -> ReadbackFence {
+        *self.state.write_fence() = Some((Arc::clone(device), fence));
+
+        {
+            let mut inner = self.state.lock();
+            inner.buffer = Some(buffer);
+            inner.status = ReadbackStatus::Submitted;
+        }
+
+        self.state.wake();
+
+        ReadbackFence {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+impl Drop for PendingReadback {
+    fn drop(&mut self) {
+        {
+            let mut inner = self.state.lock();
+            if matches!(inner.status, ReadbackStatus::Recording) {
+                inner.status = ReadbackStatus::Abandoned;
+            }
+        }
+
+        self.state.wake();
+    }
+}
+
+/// Marks a [`Readback`] as executed when dropped by the command buffer which wrote it.
+#[derive(Debug)]
+pub(super) struct ReadbackFence {
+    state: Arc<ReadbackState>,
+}
+
+impl Drop for ReadbackFence {
+    fn drop(&mut self) {
+        // Waits for any thread which is still waiting on the fence before it may be destroyed
+        *self.state.write_fence() = None;
+
+        self.state.lock().status = ReadbackStatus::Executed;
+        self.state.wake();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn readback_is_abandoned_without_submission() {
+        let (readback, pending) = Readback::new(4);
+
+        assert!(!readback.has_executed().unwrap());
+        assert!(readback.try_read().unwrap().is_none());
+
+        drop(pending);
+
+        assert!(readback.has_executed().is_err());
+        assert!(readback.wait().is_err());
+    }
+}
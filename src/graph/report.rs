@@ -0,0 +1,533 @@
+//! Inspection of render graph schedules and execution.
+
+use {
+    super::{pass_ref::Subresource, NodeIndex},
+    crate::driver::is_write_access,
+    std::{fmt::Write, time::Duration},
+    vk_sync::AccessType,
+};
+
+/// The way a [`BarrierReport`] would be recorded.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BarrierKind {
+    /// A barrier which makes the memory of a transient node available after it was used by
+    /// earlier transient nodes which shared it.
+    Alias,
+
+    /// A pipeline barrier recorded immediately before the execution.
+    Pipeline,
+
+    /// A barrier which waits on an event signalled after the pass which previously accessed the
+    /// node, allowing the passes recorded in between to overlap with it.
+    SplitWait,
+}
+
+/// A barrier which would be recorded before an execution of a pass.
+#[derive(Clone, Debug)]
+pub struct BarrierReport {
+    /// The index of the execution within the pass which requires this barrier.
+    pub exec_idx: usize,
+
+    /// The way the barrier would be recorded.
+    pub kind: BarrierKind,
+
+    /// The access which the barrier makes available.
+    pub next_access: AccessType,
+
+    /// The index of the node which the barrier applies to.
+    pub node_idx: NodeIndex,
+
+    /// The index of the pass which requires this barrier.
+    pub pass_idx: usize,
+
+    /// The access which the barrier waits on.
+    pub prev_access: AccessType,
+
+    /// The portion of the node which the barrier applies to, or `None` for a global barrier.
+    pub subresource: Option<Subresource>,
+}
+
+/// A node access made by an execution of a pass.
+#[derive(Clone, Debug)]
+pub struct NodeAccessReport {
+    /// The access at the start of the execution.
+    pub early: AccessType,
+
+    /// The index of the execution within the pass which makes this access.
+    pub exec_idx: usize,
+
+    /// The access at the end of the execution.
+    pub late: AccessType,
+
+    /// The index of the node which is accessed.
+    pub node_idx: NodeIndex,
+
+    /// The portion of the node which is accessed.
+    pub subresource: Option<Subresource>,
+}
+
+/// A resource which has been bound to a render graph.
+#[derive(Clone, Debug)]
+pub struct NodeReport {
+    /// The type of resource, such as `"Image"` or `"BufferLease"`.
+    pub kind: &'static str,
+
+    /// The debugging name of the resource, if any.
+    pub name: Option<String>,
+}
+
+/// A pass of a render graph.
+#[derive(Clone, Debug)]
+pub struct PassReport {
+    /// The accesses made by each execution of this pass, sorted by execution and node.
+    pub accesses: Vec<NodeAccessReport>,
+
+    /// The name given to [`RenderGraph::begin_pass`](super::RenderGraph::begin_pass).
+    pub name: String,
+}
+
+/// The GPU execution time of a physical pass.
+#[derive(Clone, Debug)]
+pub struct PassTimestampReport {
+    /// The time between the start of the pass, including any pipeline barriers, and the end of the
+    /// pass.
+    pub duration: Duration,
+
+    /// The name of the pass, or the names of merged passes joined by `" + "`.
+    pub name: String,
+
+    /// The queue family which executed the pass.
+    pub queue_family_index: u32,
+}
+
+/// A description of the work a [`Resolver`](super::Resolver) would record, produced without
+/// recording or submitting anything.
+///
+/// Use [`ScheduleReport::to_dot`] or [`ScheduleReport::to_json`] to inspect the result or to store
+/// it in a snapshot test.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use ash::vk;
+/// # use screen_13::driver::DriverError;
+/// # use screen_13::driver::device::{Device, DeviceInfo};
+/// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+/// # use screen_13::graph::RenderGraph;
+/// # fn main() -> Result<(), DriverError> {
+/// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+/// # let info = BufferInfo::device_mem(1024, vk::BufferUsageFlags::TRANSFER_DST);
+/// # let buf = Buffer::create(&device, info)?;
+/// let mut my_graph = RenderGraph::new();
+/// let my_buf = my_graph.bind_node(buf);
+/// my_graph.fill_buffer(my_buf, 0);
+///
+/// let resolver = my_graph.resolve();
+/// let report = resolver.schedule_report();
+///
+/// assert_eq!(report.schedule, vec![0]);
+/// println!("{}", report.to_dot());
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ScheduleReport {
+    /// The barriers which would be recorded, in recording order.
+    pub barriers: Vec<BarrierReport>,
+
+    /// The resources bound to the graph, indexed by node index.
+    pub nodes: Vec<NodeReport>,
+
+    /// The passes of the graph, indexed by pass index.
+    pub passes: Vec<PassReport>,
+
+    /// Groups of pass indices, in recording order, where each group is recorded as one physical
+    /// pass.
+    ///
+    /// Groups with more than one pass have been merged into subpasses of a single render pass.
+    pub physical_passes: Vec<Vec<usize>>,
+
+    /// The indices of the passes which would be recorded, in recording order.
+    ///
+    /// Passes not found in this list are not required and would not be recorded.
+    pub schedule: Vec<usize>,
+}
+
+impl ScheduleReport {
+    /// Returns the position of the given pass in the schedule, if it is scheduled.
+    fn schedule_position(&self, pass_idx: usize) -> Option<usize> {
+        self.schedule
+            .iter()
+            .position(|&scheduled_idx| scheduled_idx == pass_idx)
+    }
+
+    /// Formats this report as a [Graphviz](https://graphviz.org/) DOT graph.
+    ///
+    /// Passes are drawn as boxes and nodes as ellipses; edges point from nodes to the passes which
+    /// read them and from passes to the nodes they write. Merged passes are grouped into clusters
+    /// and dashed edges show the recording order. Passes which are not scheduled are grayed out.
+    pub fn to_dot(&self) -> String {
+        let mut res = String::new();
+
+        res.push_str("digraph {\n");
+        res.push_str("    rankdir=LR;\n");
+        res.push_str("    node [fontname=\"monospace\"];\n");
+
+        for (node_idx, node) in self.nodes.iter().enumerate() {
+            let label = if let Some(name) = &node.name {
+                format!("{node_idx}: {}\\n{}", node.kind, dot_escape(name))
+            } else {
+                format!("{node_idx}: {}", node.kind)
+            };
+
+            writeln!(
+                res,
+                "    node_{node_idx} [shape=ellipse, label=\"{label}\"];"
+            )
+            .unwrap();
+        }
+
+        for (physical_idx, physical_pass) in self.physical_passes.iter().enumerate() {
+            writeln!(res, "    subgraph cluster_{physical_idx} {{").unwrap();
+            writeln!(res, "        label=\"physical pass {physical_idx}\";").unwrap();
+
+            for pass_idx in physical_pass {
+                writeln!(res, "        pass_{pass_idx};").unwrap();
+            }
+
+            res.push_str("    }\n");
+        }
+
+        for (pass_idx, pass) in self.passes.iter().enumerate() {
+            let mut label = format!("[{pass_idx}: {}]", dot_escape(&pass.name));
+
+            if let Some(position) = self.schedule_position(pass_idx) {
+                write!(label, "\\nscheduled #{position}").unwrap();
+            } else {
+                label.push_str("\\nnot scheduled");
+            }
+
+            for barrier in self
+                .barriers
+                .iter()
+                .filter(|barrier| barrier.pass_idx == pass_idx)
+            {
+                write!(
+                    label,
+                    "\\lbarrier node {}: {:?} -> {:?}",
+                    barrier.node_idx, barrier.prev_access, barrier.next_access
+                )
+                .unwrap();
+
+                match barrier.kind {
+                    BarrierKind::Alias => label.push_str(" (alias)"),
+                    BarrierKind::Pipeline => (),
+                    BarrierKind::SplitWait => label.push_str(" (split)"),
+                }
+            }
+
+            let style = if self.schedule_position(pass_idx).is_some() {
+                ""
+            } else {
+                ", style=dashed, color=gray, fontcolor=gray"
+            };
+
+            writeln!(
+                res,
+                "    pass_{pass_idx} [shape=box, label=\"{label}\\l\"{style}];"
+            )
+            .unwrap();
+
+            for access in &pass.accesses {
+                if is_write_access(access.late) {
+                    writeln!(
+                        res,
+                        "    pass_{pass_idx} -> node_{} [label=\"{:?}\"];",
+                        access.node_idx, access.late
+                    )
+                    .unwrap();
+                } else {
+                    writeln!(
+                        res,
+                        "    node_{} -> pass_{pass_idx} [label=\"{:?}\"];",
+                        access.node_idx, access.early
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        for pair in self.schedule.windows(2) {
+            writeln!(
+                res,
+                "    pass_{} -> pass_{} [style=dashed, color=gray, constraint=false];",
+                pair[0], pair[1]
+            )
+            .unwrap();
+        }
+
+        res.push_str("}\n");
+
+        res
+    }
+
+    /// Formats this report as a JSON document.
+    ///
+    /// The output is stable for a given graph and is suitable for snapshot testing.
+    pub fn to_json(&self) -> String {
+        fn write_subresource(res: &mut String, subresource: Option<Subresource>) {
+            match subresource {
+                None => res.push_str("null"),
+                Some(Subresource::AccelerationStructure) => {
+                    res.push_str("{\"type\": \"AccelerationStructure\"}")
+                }
+                Some(Subresource::Buffer(range)) => write!(
+                    res,
+                    "{{\"type\": \"Buffer\", \"start\": {}, \"end\": {}}}",
+                    range.start, range.end
+                )
+                .unwrap(),
+                Some(Subresource::Image(range)) => write!(
+                    res,
+                    "{{\"type\": \"Image\", \"aspect_mask\": {}, \"base_mip_level\": {}, \
+                    \"mip_level_count\": {}, \"base_array_layer\": {}, \"array_layer_count\": {}}}",
+                    range.aspect_mask.as_raw(),
+                    range.base_mip_level,
+                    json_option(range.mip_level_count),
+                    range.base_array_layer,
+                    json_option(range.array_layer_count),
+                )
+                .unwrap(),
+            }
+        }
+
+        let mut res = String::new();
+
+        res.push_str("{\n  \"nodes\": [");
+
+        for (node_idx, node) in self.nodes.iter().enumerate() {
+            if node_idx > 0 {
+                res.push(',');
+            }
+
+            write!(
+                res,
+                "\n    {{\"index\": {node_idx}, \"kind\": \"{}\", \"name\": ",
+                node.kind
+            )
+            .unwrap();
+
+            if let Some(name) = &node.name {
+                write!(res, "\"{}\"}}", json_escape(name)).unwrap();
+            } else {
+                res.push_str("null}");
+            }
+        }
+
+        res.push_str("\n  ],\n  \"passes\": [");
+
+        for (pass_idx, pass) in self.passes.iter().enumerate() {
+            if pass_idx > 0 {
+                res.push(',');
+            }
+
+            write!(
+                res,
+                "\n    {{\"index\": {pass_idx}, \"name\": \"{}\", \"accesses\": [",
+                json_escape(&pass.name)
+            )
+            .unwrap();
+
+            for (access_idx, access) in pass.accesses.iter().enumerate() {
+                if access_idx > 0 {
+                    res.push(',');
+                }
+
+                write!(
+                    res,
+                    "\n      {{\"exec\": {}, \"node\": {}, \"early\": \"{:?}\", \"late\": \"{:?}\", \
+                    \"subresource\": ",
+                    access.exec_idx, access.node_idx, access.early, access.late
+                )
+                .unwrap();
+                write_subresource(&mut res, access.subresource);
+                res.push('}');
+            }
+
+            if !pass.accesses.is_empty() {
+                res.push_str("\n    ");
+            }
+
+            res.push_str("]}");
+        }
+
+        res.push_str("\n  ],\n  \"schedule\": [");
+
+        for (idx, pass_idx) in self.schedule.iter().enumerate() {
+            if idx > 0 {
+                res.push_str(", ");
+            }
+
+            write!(res, "{pass_idx}").unwrap();
+        }
+
+        res.push_str("],\n  \"physical_passes\": [");
+
+        for (physical_idx, physical_pass) in self.physical_passes.iter().enumerate() {
+            if physical_idx > 0 {
+                res.push(',');
+            }
+
+            res.push_str("\n    [");
+
+            for (idx, pass_idx) in physical_pass.iter().enumerate() {
+                if idx > 0 {
+                    res.push_str(", ");
+                }
+
+                write!(res, "{pass_idx}").unwrap();
+            }
+
+            res.push(']');
+        }
+
+        res.push_str("\n  ],\n  \"barriers\": [");
+
+        for (barrier_idx, barrier) in self.barriers.iter().enumerate() {
+            if barrier_idx > 0 {
+                res.push(',');
+            }
+
+            write!(
+                res,
+                "\n    {{\"pass\": {}, \"exec\": {}, \"node\": {}, \"kind\": \"{:?}\", \
+                \"prev\": \"{:?}\", \"next\": \"{:?}\", \"subresource\": ",
+                barrier.pass_idx,
+                barrier.exec_idx,
+                barrier.node_idx,
+                barrier.kind,
+                barrier.prev_access,
+                barrier.next_access
+            )
+            .unwrap();
+            write_subresource(&mut res, barrier.subresource);
+            res.push('}');
+        }
+
+        res.push_str("\n  ]\n}\n");
+
+        res
+    }
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn json_escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(res, "\\u{:04x}", c as u32).unwrap(),
+            c => res.push(c),
+        }
+    }
+
+    res
+}
+
+fn json_option(val: Option<u32>) -> String {
+    val.map(|val| val.to_string())
+        .unwrap_or_else(|| "null".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::driver::buffer::BufferSubresource};
+
+    fn report() -> ScheduleReport {
+        ScheduleReport {
+            barriers: vec![BarrierReport {
+                exec_idx: 0,
+                kind: BarrierKind::SplitWait,
+                next_access: AccessType::TransferWrite,
+                node_idx: 0,
+                pass_idx: 1,
+                prev_access: AccessType::Nothing,
+                subresource: Some(Subresource::Buffer(BufferSubresource { start: 0, end: 64 })),
+            }],
+            nodes: vec![NodeReport {
+                kind: "Buffer",
+                name: Some("my \"buffer\"".to_string()),
+            }],
+            passes: vec![
+                PassReport {
+                    accesses: vec![],
+                    name: "unused".to_string(),
+                },
+                PassReport {
+                    accesses: vec![NodeAccessReport {
+                        early: AccessType::TransferWrite,
+                        exec_idx: 0,
+                        late: AccessType::TransferWrite,
+                        node_idx: 0,
+                        subresource: Some(Subresource::Buffer(BufferSubresource {
+                            start: 0,
+                            end: 64,
+                        })),
+                    }],
+                    name: "fill buffer".to_string(),
+                },
+            ],
+            physical_passes: vec![vec![1]],
+            schedule: vec![1],
+        }
+    }
+
+    #[test]
+    pub fn schedule_report_dot() {
+        let dot = report().to_dot();
+
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.contains("node_0 [shape=ellipse, label=\"0: Buffer\\nmy \\\"buffer\\\"\"];"));
+        assert!(dot.contains("pass_1 -> node_0 [label=\"TransferWrite\"];"));
+        assert!(dot.contains("subgraph cluster_0 {"));
+        assert!(dot.contains("\\nnot scheduled"));
+        assert!(dot.contains("\\lbarrier node 0: Nothing -> TransferWrite (split)"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    pub fn schedule_report_json() {
+        assert_eq!(
+            report().to_json(),
+            r#"{
+  "nodes": [
+    {"index": 0, "kind": "Buffer", "name": "my \"buffer\""}
+  ],
+  "passes": [
+    {"index": 0, "name": "unused", "accesses": []},
+    {"index": 1, "name": "fill buffer", "accesses": [
+      {"exec": 0, "node": 0, "early": "TransferWrite", "late": "TransferWrite", "subresource": {"type": "Buffer", "start": 0, "end": 64}}
+    ]}
+  ],
+  "schedule": [1],
+  "physical_passes": [
+    [1]
+  ],
+  "barriers": [
+    {"pass": 1, "exec": 0, "node": 0, "kind": "SplitWait", "prev": "Nothing", "next": "TransferWrite", "subresource": {"type": "Buffer", "start": 0, "end": 64}}
+  ]
+}
+"#
+        );
+    }
+}
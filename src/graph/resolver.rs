@@ -1,38 +1,116 @@
 use {
     super::{
-        Area, Attachment, Binding, Bindings, Edge, Execution, ExecutionPipeline, Node, Pass,
-        RenderGraph, Unbind,
+        pass_ref::{ConditionalDispatch, Subresource, SubresourceAccess},
+        template::{shape_key, CompiledGraph, CompiledPass, GraphTemplate},
+        upload::Upload,
+        Area, Attachment, BarrierKind, BarrierReport, Binding, Bindings, Edge, Execution,
+        ExecutionFunction, ExecutionPipeline, Node, NodeAccessReport, NodeIndex, NodeReport, Pass,
+        PassReport, PassTimestampReport, QueueType, RenderGraph, ScheduleReport, Unbind,
     },
     crate::{
         driver::{
             accel_struct::AccelerationStructure,
-            buffer::Buffer,
+            buffer::{Buffer, BufferInfo},
             device::Device,
             format_aspect_mask,
-            graphic::DepthStencilMode,
+            graphic::{DepthStencilMode, GraphicPipeline},
             image::{Image, ImageViewInfo},
-            image_access_layout, is_framebuffer_access, is_read_access, is_write_access,
-            pipeline_stage_access_flags, AttachmentInfo, AttachmentRef, CommandBuffer,
-            CommandBufferInfo, DescriptorBinding, DescriptorInfo, DescriptorPool,
-            DescriptorPoolInfo, DescriptorSet, DriverError, FramebufferAttachmentImageInfo,
-            FramebufferInfo, RenderPass, RenderPassInfo, SubpassDependency, SubpassInfo,
+            image_access_layout, is_framebuffer_access, is_integer_format, is_read_access,
+            is_write_access, pipeline_stage_access_flags,
+            query::{QueryPool, QueryPoolInfo},
+            render_pass::ResolveMode,
+            sync::TimelineSemaphore,
+            AttachmentInfo, AttachmentRef, CommandBuffer, CommandBufferInfo, DescriptorBinding,
+            DescriptorInfo, DescriptorPool, DescriptorPoolInfo, DescriptorSet, DriverError,
+            FramebufferAttachmentImageInfo, FramebufferInfo, RenderPass, RenderPassInfo,
+            RenderingFormats, SecondaryCommandPool, SubpassDependency, SubpassInfo,
         },
         pool::{Lease, Pool},
     },
     ash::vk,
     log::{
-        debug, log_enabled, trace,
+        debug, log_enabled, trace, warn,
         Level::{Debug, Trace},
     },
     std::{
         cell::RefCell,
-        collections::{HashMap, VecDeque},
+        collections::{BTreeMap, HashMap, VecDeque},
         iter::repeat,
+        mem::replace,
         ops::Range,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, OnceLock,
+        },
+        time::Duration,
+    },
+    vk_sync::{
+        cmd::{pipeline_barrier, set_event, wait_events},
+        get_buffer_memory_barrier, get_image_memory_barrier, get_memory_barrier, AccessType,
+        BufferBarrier, GlobalBarrier, ImageBarrier,
     },
-    vk_sync::{cmd::pipeline_barrier, AccessType, BufferBarrier, GlobalBarrier, ImageBarrier},
 };
 
+// Returns the buffer and image usage required by an access which does not use a descriptor
+fn access_usage(access: AccessType) -> (vk::BufferUsageFlags, vk::ImageUsageFlags) {
+    match access {
+        AccessType::AccelerationStructureBuildRead => (
+            vk::BufferUsageFlags::ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR
+                | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+            vk::ImageUsageFlags::empty(),
+        ),
+        AccessType::AccelerationStructureBuildWrite => (
+            vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+            vk::ImageUsageFlags::empty(),
+        ),
+        AccessType::AnyShaderReadUniformBufferOrVertexBuffer => (
+            vk::BufferUsageFlags::UNIFORM_BUFFER | vk::BufferUsageFlags::VERTEX_BUFFER,
+            vk::ImageUsageFlags::empty(),
+        ),
+        AccessType::ColorAttachmentRead
+        | AccessType::ColorAttachmentReadWrite
+        | AccessType::ColorAttachmentWrite => (
+            vk::BufferUsageFlags::empty(),
+            vk::ImageUsageFlags::COLOR_ATTACHMENT,
+        ),
+        AccessType::DepthAttachmentWriteStencilReadOnly
+        | AccessType::DepthStencilAttachmentRead
+        | AccessType::DepthStencilAttachmentWrite
+        | AccessType::StencilAttachmentWriteDepthReadOnly => (
+            vk::BufferUsageFlags::empty(),
+            vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+        ),
+        AccessType::FragmentShaderReadColorInputAttachment
+        | AccessType::FragmentShaderReadDepthStencilInputAttachment
+        | AccessType::RayTracingShaderReadColorInputAttachment
+        | AccessType::RayTracingShaderReadDepthStencilInputAttachment => (
+            vk::BufferUsageFlags::empty(),
+            vk::ImageUsageFlags::INPUT_ATTACHMENT,
+        ),
+        AccessType::IndexBuffer => (
+            vk::BufferUsageFlags::INDEX_BUFFER,
+            vk::ImageUsageFlags::empty(),
+        ),
+        AccessType::IndirectBuffer => (
+            vk::BufferUsageFlags::INDIRECT_BUFFER,
+            vk::ImageUsageFlags::empty(),
+        ),
+        AccessType::TransferRead => (
+            vk::BufferUsageFlags::TRANSFER_SRC,
+            vk::ImageUsageFlags::TRANSFER_SRC,
+        ),
+        AccessType::TransferWrite => (
+            vk::BufferUsageFlags::TRANSFER_DST,
+            vk::ImageUsageFlags::TRANSFER_DST,
+        ),
+        AccessType::VertexBuffer => (
+            vk::BufferUsageFlags::VERTEX_BUFFER,
+            vk::ImageUsageFlags::empty(),
+        ),
+        _ => (vk::BufferUsageFlags::empty(), vk::ImageUsageFlags::empty()),
+    }
+}
+
 fn align_up(val: u32, atom: u32) -> u32 {
     (val + atom - 1) & !(atom - 1)
 }
@@ -137,11 +215,37 @@ impl AccessCache {
     }
 }
 
+/// The resources used to skip the dispatches of a conditional compute pass on devices which do not
+/// support `VK_EXT_conditional_rendering`.
+#[derive(Debug)]
+struct ConditionalDispatchArgs {
+    descriptor_set: DescriptorSet,
+    _descriptor_pool: Lease<DescriptorPool>,
+    buffer: Arc<Buffer>,
+    layout: vk::PipelineLayout,
+    pipeline: vk::Pipeline,
+}
+
 #[derive(Debug)]
 struct PhysicalPass {
+    conditional_dispatch_args: Option<ConditionalDispatchArgs>,
     descriptor_pool: Option<Lease<DescriptorPool>>,
     exec_descriptor_sets: HashMap<usize, Vec<DescriptorSet>>,
     render_pass: Option<Lease<RenderPass>>,
+    rendering: Option<Rendering>,
+}
+
+impl PhysicalPass {
+    fn is_graphic(&self) -> bool {
+        self.render_pass.is_some() || self.rendering.is_some()
+    }
+
+    fn render_pass_info(&self) -> Option<&RenderPassInfo> {
+        self.render_pass
+            .as_ref()
+            .map(|render_pass| &render_pass.info)
+            .or_else(|| self.rendering.as_ref().map(|rendering| &rendering.info))
+    }
 }
 
 impl Drop for PhysicalPass {
@@ -151,28 +255,131 @@ impl Drop for PhysicalPass {
     }
 }
 
+/// The attachments of a graphic pass which is recorded using dynamic rendering instead of render
+/// pass and framebuffer objects.
+#[derive(Debug)]
+struct Rendering {
+    formats: RenderingFormats,
+    info: RenderPassInfo,
+    samples: vk::SampleCountFlags,
+}
+
+impl Rendering {
+    fn new(info: RenderPassInfo) -> Self {
+        debug_assert_eq!(info.subpasses.len(), 1);
+
+        let subpass = &info.subpasses[0];
+        let color_formats = subpass
+            .color_attachments
+            .iter()
+            .map(|attachment| {
+                if attachment.attachment == vk::ATTACHMENT_UNUSED {
+                    vk::Format::UNDEFINED
+                } else {
+                    info.attachments[attachment.attachment as usize].fmt
+                }
+            })
+            .collect();
+        let (depth_format, stencil_format) = subpass
+            .depth_stencil_attachment
+            .map(|attachment| {
+                let fmt = info.attachments[attachment.attachment as usize].fmt;
+                let aspect_mask = format_aspect_mask(fmt);
+                let format_if = |aspect| {
+                    if aspect_mask.contains(aspect) {
+                        fmt
+                    } else {
+                        vk::Format::UNDEFINED
+                    }
+                };
+
+                (
+                    format_if(vk::ImageAspectFlags::DEPTH),
+                    format_if(vk::ImageAspectFlags::STENCIL),
+                )
+            })
+            .unwrap_or((vk::Format::UNDEFINED, vk::Format::UNDEFINED));
+        let samples = subpass
+            .color_attachments
+            .iter()
+            .chain(subpass.depth_stencil_attachment.as_ref())
+            .find(|attachment| attachment.attachment != vk::ATTACHMENT_UNUSED)
+            .map(|attachment| {
+                info.attachments[attachment.attachment as usize]
+                    .sample_count
+                    .into_vk()
+            })
+            .unwrap_or(vk::SampleCountFlags::TYPE_1);
+        let formats = RenderingFormats {
+            color_formats,
+            depth_format,
+            stencil_format,
+            view_mask: subpass.view_mask,
+        };
+
+        Self {
+            formats,
+            info,
+            samples,
+        }
+    }
+
+    // vk_sync transitions the depth and stencil aspects of images together, so separate layouts
+    // are replaced by the combined layouts the images are actually in
+    fn layout(layout: vk::ImageLayout) -> vk::ImageLayout {
+        match layout {
+            vk::ImageLayout::DEPTH_ATTACHMENT_OPTIMAL
+            | vk::ImageLayout::STENCIL_ATTACHMENT_OPTIMAL => {
+                vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL
+            }
+            vk::ImageLayout::DEPTH_READ_ONLY_OPTIMAL
+            | vk::ImageLayout::STENCIL_READ_ONLY_OPTIMAL => {
+                vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL
+            }
+            layout => layout,
+        }
+    }
+}
+
 /// A structure which can read and execute render graphs. This pattern was derived from:
 ///
 /// <http://themaister.net/blog/2017/08/15/render-graphs-and-vulkan-a-deep-dive/>
 /// <https://github.com/EmbarkStudios/kajiya>
 #[derive(Debug)]
 pub struct Resolver {
+    barrier_state: BarrierState,
     pub(super) graph: RenderGraph,
     physical_passes: Vec<PhysicalPass>,
+    recording_threads: usize,
+    template: Option<GraphTemplate>,
+    timestamps: Option<PassTimestamps>,
 }
 
 impl Resolver {
-    pub(super) fn new(graph: RenderGraph) -> Self {
+    pub(super) fn new(mut graph: RenderGraph) -> Self {
+        Self::infer_transient_usage(&mut graph);
+
         let physical_passes = Vec::with_capacity(graph.passes.len());
 
         Self {
+            barrier_state: Default::default(),
             graph,
             physical_passes,
+            recording_threads: 1,
+            template: None,
+            timestamps: None,
         }
     }
 
     #[profiling::function]
     fn allow_merge_passes(lhs: &Pass, rhs: &Pass) -> bool {
+        // Conditional passes must be predicated on their own
+        if lhs.condition.is_some() || rhs.condition.is_some() {
+            trace!("  {} or {} is conditional", lhs.name, rhs.name);
+
+            return false;
+        }
+
         let lhs_pipeline = lhs
             .execs
             .first()
@@ -385,18 +592,104 @@ impl Resolver {
         }
     }
 
+    /// Returns a handle which counts the resource barriers recorded or elided by this instance, and
+    /// the number of pipeline barrier commands they were batched into.
+    ///
+    /// The handle remains valid after this instance has been submitted.
+    ///
+    /// Barriers are elided when an earlier barrier recorded into the same command buffer has already
+    /// made a read access of the same subresource visible and the resource has not been written
+    /// since.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+    /// # use screen_13::graph::RenderGraph;
+    /// # use screen_13::pool::lazy::LazyPool;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let mut pool = LazyPool::new(&device);
+    /// # let info = BufferInfo::device_mem(1024, vk::BufferUsageFlags::TRANSFER_SRC);
+    /// # let src_buf = Buffer::create(&device, info)?;
+    /// # let info = BufferInfo::device_mem(1024, vk::BufferUsageFlags::TRANSFER_DST);
+    /// # let dst_buf1 = Buffer::create(&device, info)?;
+    /// # let dst_buf2 = Buffer::create(&device, info)?;
+    /// let mut my_graph = RenderGraph::new();
+    /// let src_buf = my_graph.bind_node(src_buf);
+    /// let dst_buf1 = my_graph.bind_node(dst_buf1);
+    /// let dst_buf2 = my_graph.bind_node(dst_buf2);
+    ///
+    /// // The second copy reads the source buffer again; it does not require another barrier
+    /// my_graph.copy_buffer(src_buf, dst_buf1);
+    /// my_graph.copy_buffer(src_buf, dst_buf2);
+    ///
+    /// let resolver = my_graph.resolve();
+    /// let barrier_counter = resolver.barrier_counter();
+    /// resolver.submit(&mut pool, 0, 0)?;
+    ///
+    /// assert_eq!(barrier_counter.counts().elided, 1);
+    /// # Ok(()) }
+    /// ```
+    pub fn barrier_counter(&self) -> BarrierCounter {
+        self.barrier_state.counter.clone()
+    }
+
     #[profiling::function]
+    // Predicates the commands of a pass on its condition; the barriers of the first execution have
+    // already made the condition visible to the indirect stage
+    fn begin_conditional_rendering(cmd_buf: &CommandBuffer, bindings: &[Binding], pass: &Pass) {
+        use std::slice::from_ref;
+
+        let condition = pass.condition.unwrap();
+        let buffer = **bindings[condition.node_idx].as_driver_buffer().unwrap();
+        let conditional_rendering_ext = cmd_buf.device.conditional_rendering_ext.as_ref().unwrap();
+
+        trace!(
+            "  begin conditional rendering {buffer:?} +{}",
+            condition.offset
+        );
+
+        unsafe {
+            cmd_buf.device.cmd_pipeline_barrier(
+                **cmd_buf,
+                vk::PipelineStageFlags::DRAW_INDIRECT,
+                vk::PipelineStageFlags::CONDITIONAL_RENDERING_EXT,
+                vk::DependencyFlags::empty(),
+                from_ref(&vk::MemoryBarrier {
+                    dst_access_mask: vk::AccessFlags::CONDITIONAL_RENDERING_READ_EXT,
+                    ..Default::default()
+                }),
+                &[],
+                &[],
+            );
+            (conditional_rendering_ext.cmd_begin_conditional_rendering_ext)(
+                **cmd_buf,
+                &vk::ConditionalRenderingBeginInfoEXT::builder()
+                    .buffer(buffer)
+                    .offset(condition.offset)
+                    .build(),
+            );
+        }
+    }
+
     fn begin_render_pass(
         cmd_buf: &CommandBuffer,
         bindings: &[Binding],
         pass: &Pass,
         physical_pass: &mut PhysicalPass,
         render_area: Area,
+        contents: vk::SubpassContents,
     ) -> Result<(), DriverError> {
         trace!("  begin render pass");
 
-        let render_pass = physical_pass.render_pass.as_mut().unwrap();
-        let attachment_count = render_pass.info.attachments.len();
+        let attachment_count = physical_pass.render_pass_info().unwrap().attachments.len();
 
         let mut attachments = Vec::with_capacity(attachment_count);
         attachments.resize(
@@ -601,6 +894,27 @@ impl Resolver {
                 }
             }
 
+            if let Some(rendering) = physical_pass.rendering.as_ref() {
+                let layer_count = attachments
+                    .iter()
+                    .map(|attachment| attachment.layer_count)
+                    .filter(|layer_count| *layer_count > 0)
+                    .min()
+                    .unwrap_or(1);
+                Self::begin_rendering(
+                    cmd_buf,
+                    rendering,
+                    render_area,
+                    layer_count,
+                    clear_values,
+                    image_views,
+                    contents,
+                );
+
+                return Ok(());
+            }
+
+            let render_pass = physical_pass.render_pass.as_mut().unwrap();
             let framebuffer = RenderPass::framebuffer(
                 render_pass,
                 FramebufferInfo {
@@ -631,7 +945,7 @@ impl Resolver {
                             &mut vk::RenderPassAttachmentBeginInfoKHR::builder()
                                 .attachments(image_views),
                         ),
-                    vk::SubpassContents::INLINE,
+                    contents,
                 );
             }
 
@@ -639,9 +953,151 @@ impl Resolver {
         })
     }
 
+    // Begins a dynamic render pass instance using the attachments of the only subpass of a graphic
+    // pass; the attachments have already been transitioned into their layouts by barriers
     #[profiling::function]
-    fn bind_descriptor_sets(
+    fn begin_rendering(
         cmd_buf: &CommandBuffer,
+        rendering: &Rendering,
+        render_area: Area,
+        layer_count: u32,
+        clear_values: &[vk::ClearValue],
+        image_views: &[vk::ImageView],
+        contents: vk::SubpassContents,
+    ) {
+        trace!("  begin rendering");
+
+        let info = &rendering.info;
+        let subpass = &info.subpasses[0];
+        let color_attachments = subpass
+            .color_attachments
+            .iter()
+            .zip(&subpass.color_resolve_attachments)
+            .map(|(attachment, resolve_attachment)| {
+                if attachment.attachment == vk::ATTACHMENT_UNUSED {
+                    return vk::RenderingAttachmentInfo::default();
+                }
+
+                let attachment_idx = attachment.attachment as usize;
+                let attachment_info = &info.attachments[attachment_idx];
+                let mut rendering_attachment = vk::RenderingAttachmentInfo::builder()
+                    .image_view(image_views[attachment_idx])
+                    .image_layout(attachment.layout)
+                    .load_op(attachment_info.load_op)
+                    .store_op(attachment_info.store_op)
+                    .clear_value(clear_values[attachment_idx]);
+
+                if resolve_attachment.attachment != vk::ATTACHMENT_UNUSED {
+                    // Render passes resolve integer formats using sample zero
+                    let resolve_mode = if is_integer_format(attachment_info.fmt) {
+                        vk::ResolveModeFlags::SAMPLE_ZERO
+                    } else {
+                        vk::ResolveModeFlags::AVERAGE
+                    };
+
+                    rendering_attachment = rendering_attachment
+                        .resolve_mode(resolve_mode)
+                        .resolve_image_view(image_views[resolve_attachment.attachment as usize])
+                        .resolve_image_layout(resolve_attachment.layout);
+                }
+
+                rendering_attachment.build()
+            })
+            .collect::<Box<[_]>>();
+        let (depth_attachment, stencil_attachment) = subpass
+            .depth_stencil_attachment
+            .map(|attachment| {
+                let attachment_idx = attachment.attachment as usize;
+                let attachment_info = &info.attachments[attachment_idx];
+                let aspect_mask = format_aspect_mask(attachment_info.fmt);
+                let rendering_attachment = |load_op, store_op, resolve_mode| {
+                    let mut rendering_attachment = vk::RenderingAttachmentInfo::builder()
+                        .image_view(image_views[attachment_idx])
+                        .image_layout(Rendering::layout(attachment.layout))
+                        .load_op(load_op)
+                        .store_op(store_op)
+                        .clear_value(clear_values[attachment_idx]);
+
+                    if let Some((resolve_attachment, ..)) = subpass.depth_stencil_resolve_attachment
+                    {
+                        rendering_attachment = rendering_attachment
+                            .resolve_mode(ResolveMode::into_vk(resolve_mode))
+                            .resolve_image_view(image_views[resolve_attachment.attachment as usize])
+                            .resolve_image_layout(Rendering::layout(resolve_attachment.layout));
+                    }
+
+                    rendering_attachment.build()
+                };
+                let (depth_resolve_mode, stencil_resolve_mode) = subpass
+                    .depth_stencil_resolve_attachment
+                    .map(|(_, depth_resolve_mode, stencil_resolve_mode)| {
+                        (depth_resolve_mode, stencil_resolve_mode)
+                    })
+                    .unwrap_or_default();
+
+                (
+                    aspect_mask.contains(vk::ImageAspectFlags::DEPTH).then(|| {
+                        rendering_attachment(
+                            attachment_info.load_op,
+                            attachment_info.store_op,
+                            depth_resolve_mode,
+                        )
+                    }),
+                    aspect_mask
+                        .contains(vk::ImageAspectFlags::STENCIL)
+                        .then(|| {
+                            rendering_attachment(
+                                attachment_info.stencil_load_op,
+                                attachment_info.stencil_store_op,
+                                stencil_resolve_mode,
+                            )
+                        }),
+                )
+            })
+            .unwrap_or_default();
+        let flags = if contents == vk::SubpassContents::SECONDARY_COMMAND_BUFFERS {
+            vk::RenderingFlags::CONTENTS_SECONDARY_COMMAND_BUFFERS
+        } else {
+            vk::RenderingFlags::empty()
+        };
+        let mut rendering_info = vk::RenderingInfo::builder()
+            .flags(flags)
+            .render_area(vk::Rect2D {
+                offset: vk::Offset2D {
+                    x: render_area.x,
+                    y: render_area.y,
+                },
+                extent: vk::Extent2D {
+                    width: render_area.width,
+                    height: render_area.height,
+                },
+            })
+            .layer_count(layer_count)
+            .view_mask(subpass.view_mask)
+            .color_attachments(&color_attachments);
+
+        if let Some(depth_attachment) = depth_attachment.as_ref() {
+            rendering_info = rendering_info.depth_attachment(depth_attachment);
+        }
+
+        if let Some(stencil_attachment) = stencil_attachment.as_ref() {
+            rendering_info = rendering_info.stencil_attachment(stencil_attachment);
+        }
+
+        unsafe {
+            cmd_buf
+                .device
+                .dynamic_rendering_ext
+                .as_ref()
+                .unwrap()
+                .cmd_begin_rendering(**cmd_buf, &rendering_info);
+        }
+    }
+
+    #[profiling::function]
+    fn bind_descriptor_sets(
+        device: &Device,
+        cmd_buf: vk::CommandBuffer,
         pipeline: &ExecutionPipeline,
         physical_pass: &PhysicalPass,
         exec_idx: usize,
@@ -666,8 +1122,8 @@ impl Resolver {
                 trace!("    bind descriptor sets {:?}", descriptor_sets);
 
                 unsafe {
-                    cmd_buf.device.cmd_bind_descriptor_sets(
-                        **cmd_buf,
+                    device.cmd_bind_descriptor_sets(
+                        cmd_buf,
                         pipeline.bind_point(),
                         pipeline.layout(),
                         0,
@@ -679,14 +1135,295 @@ impl Resolver {
         }
     }
 
+    // Creates the arguments of a conditional compute pass on devices which do not support
+    // conditional rendering; the argument buffer is cached by the command buffer
+    fn create_conditional_dispatch_args<P>(
+        cmd_buf: &mut CommandBuffer,
+        pool: &mut P,
+        pass: &Pass,
+    ) -> Result<ConditionalDispatchArgs, DriverError>
+    where
+        P: Pool<DescriptorPoolInfo, DescriptorPool> + ?Sized,
+    {
+        use std::slice::from_ref;
+
+        if !pass
+            .execs
+            .iter()
+            .all(|exec| matches!(exec.pipeline, Some(ExecutionPipeline::Compute(_))))
+        {
+            warn!(
+                "conditional pass {} requires VK_EXT_conditional_rendering",
+                pass.name
+            );
+
+            return Err(DriverError::Unsupported);
+        }
+
+        let device = Arc::clone(&cmd_buf.device);
+        let conditional_dispatch_pipeline = Device::conditional_dispatch_pipeline(&device)?;
+        let buffer = CommandBuffer::next_transient_buffer(
+            cmd_buf,
+            BufferInfo::device_mem(
+                pass.execs.len() as vk::DeviceSize * ConditionalDispatch::ARGS_STRIDE,
+                vk::BufferUsageFlags::INDIRECT_BUFFER
+                    | vk::BufferUsageFlags::STORAGE_BUFFER
+                    | vk::BufferUsageFlags::TRANSFER_DST,
+            ),
+            None,
+        )?;
+        let descriptor_pool = pool.lease(DescriptorPoolInfo {
+            max_sets: 1,
+            storage_buffer_count: 1,
+            ..Default::default()
+        })?;
+        let descriptor_set = DescriptorPool::allocate_descriptor_set(
+            &descriptor_pool,
+            &conditional_dispatch_pipeline.descriptor_set_layout,
+        )?;
+
+        unsafe {
+            device.update_descriptor_sets(
+                from_ref(
+                    &vk::WriteDescriptorSet::builder()
+                        .dst_set(*descriptor_set)
+                        .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+                        .buffer_info(from_ref(&vk::DescriptorBufferInfo {
+                            buffer: **buffer,
+                            offset: 0,
+                            range: vk::WHOLE_SIZE,
+                        })),
+                ),
+                &[],
+            );
+        }
+
+        Ok(ConditionalDispatchArgs {
+            descriptor_set,
+            _descriptor_pool: descriptor_pool,
+            buffer,
+            layout: conditional_dispatch_pipeline.layout,
+            pipeline: conditional_dispatch_pipeline.pipeline,
+        })
+    }
+
+    // Creates the transient resources accessed by the scheduled passes. Resources which are never
+    // accessed at the same time share memory, and the first barrier of each resource waits on the
+    // last accesses of the resources which previously used the same memory.
     #[profiling::function]
-    fn bind_pipeline(
+    fn create_scheduled_transients(
+        &mut self,
         cmd_buf: &mut CommandBuffer,
+        schedule: &[usize],
+        queue: Option<(usize, usize)>,
+    ) -> Result<(), DriverError> {
+        struct Lifetime {
+            first: usize,
+            last: usize,
+            last_access: AccessType,
+            queue: Option<(usize, usize)>,
+        }
+
+        let device = Arc::clone(&cmd_buf.device);
+
+        // Lifetimes are measured in scheduled passes; after merging these are the first passes
+        let mut lifetimes = BTreeMap::<NodeIndex, Lifetime>::new();
+        for (position, pass) in self.graph.passes[0..schedule.len()].iter().enumerate() {
+            // Passes recorded into a single command buffer (no queue) all share one queue
+            let pass_queue = queue.map(|queue| Self::pass_queue(&device, pass, queue));
+
+            for exec in &pass.execs {
+                for (&node_idx, [_, late]) in &exec.accesses {
+                    if !matches!(
+                        self.graph.bindings[node_idx],
+                        Binding::TransientBuffer(_, None) | Binding::TransientImage(_, None)
+                    ) {
+                        continue;
+                    }
+
+                    let lifetime = lifetimes.entry(node_idx).or_insert(Lifetime {
+                        first: position,
+                        last: position,
+                        last_access: late.access,
+                        queue: pass_queue,
+                    });
+
+                    if lifetime.last < position {
+                        lifetime.last = position;
+                        lifetime.last_access = late.access;
+                    }
+
+                    // Memory is only shared by resources which are accessed on the same queue
+                    if lifetime.queue != pass_queue {
+                        lifetime.first = 0;
+                        lifetime.last = usize::MAX;
+                    }
+                }
+            }
+        }
+
+        if lifetimes.is_empty() {
+            return Ok(());
+        }
+
+        // Resources accessed by passes which have not been scheduled must outlive the schedule
+        for exec in self.graph.passes[schedule.len()..]
+            .iter()
+            .flat_map(|pass| pass.execs.iter())
+        {
+            for node_idx in exec.accesses.keys() {
+                if let Some(lifetime) = lifetimes.get_mut(node_idx) {
+                    lifetime.last = usize::MAX;
+                }
+            }
+        }
+
+        // Resources are grouped into blocks of memory which hold compatible resources; mappable
+        // buffers, such as the staging buffers of readbacks, own their memory
+        let mut blocks = BTreeMap::<_, Vec<(NodeIndex, vk::MemoryRequirements)>>::new();
+        for (&node_idx, lifetime) in &lifetimes {
+            let (requirements, linear) = match &mut self.graph.bindings[node_idx] {
+                Binding::TransientBuffer(info, buffer) if info.mappable => {
+                    *buffer = Some(CommandBuffer::next_transient_buffer(cmd_buf, *info, None)?);
+
+                    continue;
+                }
+                Binding::TransientBuffer(info, _) => (
+                    CommandBuffer::transient_buffer_requirements(cmd_buf, *info)?,
+                    true,
+                ),
+                Binding::TransientImage(info, _) => (
+                    CommandBuffer::transient_image_requirements(cmd_buf, *info)?,
+                    info.tiling == vk::ImageTiling::LINEAR,
+                ),
+                _ => unreachable!(),
+            };
+
+            blocks
+                .entry((linear, lifetime.queue, requirements.memory_type_bits))
+                .or_default()
+                .push((node_idx, requirements));
+        }
+
+        for ((linear, _, memory_type_bits), resources) in blocks {
+            let (placements, block_requirements) = TransientPlacement::place(
+                resources.iter().map(|&(node_idx, requirements)| {
+                    let Lifetime { first, last, .. } = lifetimes[&node_idx];
+
+                    (node_idx, first..last.saturating_add(1), requirements)
+                }),
+                memory_type_bits,
+            );
+
+            trace!(
+                "placed {} transient resources in {} of {} bytes",
+                placements.len(),
+                block_requirements.size,
+                resources
+                    .iter()
+                    .map(|(_, requirements)| requirements.size)
+                    .sum::<vk::DeviceSize>()
+            );
+
+            // Memory and resources are reused once earlier submissions of the command buffer have
+            // executed
+            let memory_block =
+                CommandBuffer::next_transient_memory(cmd_buf, block_requirements, linear)?;
+
+            for placement in &placements {
+                // Earlier resources which used the same memory must be finished with it
+                let aliases = placements
+                    .iter()
+                    .filter(|other| {
+                        other.lifetime.end <= placement.lifetime.start && other.overlaps(placement)
+                    })
+                    .map(|other| lifetimes[&other.node_idx].last_access)
+                    .fold(vec![], |mut aliases, access| {
+                        // HACK: It would be nice if AccessType was PartialOrd..
+                        if !aliases.contains(&access) {
+                            aliases.push(access);
+                        }
+
+                        aliases
+                    });
+
+                if !aliases.is_empty() {
+                    self.barrier_state
+                        .aliases
+                        .insert(placement.node_idx, aliases);
+                }
+
+                match &mut self.graph.bindings[placement.node_idx] {
+                    Binding::TransientBuffer(info, buffer) => {
+                        *buffer = Some(CommandBuffer::next_transient_buffer(
+                            cmd_buf,
+                            *info,
+                            Some((&memory_block, placement.offset)),
+                        )?);
+                    }
+                    Binding::TransientImage(info, image) => {
+                        *image = Some(CommandBuffer::next_transient_image(
+                            cmd_buf,
+                            *info,
+                            &memory_block,
+                            placement.offset,
+                        )?);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn end_conditional_rendering(cmd_buf: &CommandBuffer) {
+        let conditional_rendering_ext = cmd_buf.device.conditional_rendering_ext.as_ref().unwrap();
+
+        trace!("  end conditional rendering");
+
+        unsafe {
+            (conditional_rendering_ext.cmd_end_conditional_rendering_ext)(**cmd_buf);
+
+            // The graph synchronizes later accesses of the condition with the indirect stage only
+            cmd_buf.device.cmd_pipeline_barrier(
+                **cmd_buf,
+                vk::PipelineStageFlags::CONDITIONAL_RENDERING_EXT,
+                vk::PipelineStageFlags::DRAW_INDIRECT,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &[],
+            );
+        }
+    }
+
+    fn end_render_pass(cmd_buf: &CommandBuffer, physical_pass: &PhysicalPass) {
+        trace!("  end render pass");
+
+        unsafe {
+            if physical_pass.rendering.is_some() {
+                cmd_buf
+                    .device
+                    .dynamic_rendering_ext
+                    .as_ref()
+                    .unwrap()
+                    .cmd_end_rendering(**cmd_buf);
+            } else {
+                cmd_buf.device.cmd_end_render_pass(**cmd_buf);
+            }
+        }
+    }
+
+    // Returns the pipeline which an execution binds; graphic pipelines are created as required by
+    // the render pass or attachment formats
+    #[profiling::function]
+    fn exec_pipeline(
         physical_pass: &mut PhysicalPass,
         exec_idx: usize,
         pipeline: &mut ExecutionPipeline,
         depth_stencil: Option<DepthStencilMode>,
-    ) -> Result<(), DriverError> {
+    ) -> Result<vk::Pipeline, DriverError> {
         if log_enabled!(Trace) {
             let (ty, name, vk_pipeline) = match pipeline {
                 ExecutionPipeline::Compute(pipeline) => {
@@ -706,34 +1443,26 @@ impl Resolver {
             }
         }
 
-        // We store a shared reference to this pipeline inside the command buffer!
-        let pipeline_bind_point = pipeline.bind_point();
-        let pipeline = match pipeline {
+        Ok(match pipeline {
             ExecutionPipeline::Compute(pipeline) => ***pipeline,
-            ExecutionPipeline::Graphic(pipeline) => RenderPass::graphic_pipeline(
-                physical_pass.render_pass.as_mut().unwrap(),
-                pipeline,
-                depth_stencil,
-                exec_idx as _,
-            )?,
+            ExecutionPipeline::Graphic(pipeline) => {
+                if let Some(rendering) = physical_pass.rendering.as_ref() {
+                    GraphicPipeline::rendering_pipeline(
+                        pipeline,
+                        depth_stencil,
+                        &rendering.formats,
+                    )?
+                } else {
+                    RenderPass::graphic_pipeline(
+                        physical_pass.render_pass.as_mut().unwrap(),
+                        pipeline,
+                        depth_stencil,
+                        exec_idx as _,
+                    )?
+                }
+            }
             ExecutionPipeline::RayTrace(pipeline) => ***pipeline,
-        };
-
-        unsafe {
-            cmd_buf
-                .device
-                .cmd_bind_pipeline(**cmd_buf, pipeline_bind_point, pipeline);
-        }
-
-        Ok(())
-    }
-
-    fn end_render_pass(&mut self, cmd_buf: &CommandBuffer) {
-        trace!("  end render pass");
-
-        unsafe {
-            cmd_buf.device.cmd_end_render_pass(**cmd_buf);
-        }
+        })
     }
 
     /// Returns `true` when all recorded passes have been submitted to a driver command buffer.
@@ -745,8 +1474,20 @@ impl Resolver {
         self.graph.passes.is_empty()
     }
 
-    #[allow(clippy::type_complexity)]
-    #[profiling::function]
+    // Returns `true` if the passes submitted to a queue family are timed: queries are reset from the
+    // host when the device supports it, otherwise by commands which transfer queues do not support
+    fn is_timed_queue_family(device: &Device, queue_family_index: usize) -> bool {
+        let queue_family = &device.physical_device.queue_families[queue_family_index];
+
+        queue_family.timestamp_valid_bits > 0
+            && (device.physical_device.features_v1_2.host_query_reset
+                || queue_family
+                    .queue_flags
+                    .intersects(vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE))
+    }
+
+    #[allow(clippy::type_complexity)]
+    #[profiling::function]
     fn lease_descriptor_pool<P>(
         pool: &mut P,
         pass: &Pass,
@@ -844,14 +1585,7 @@ impl Resolver {
     }
 
     #[profiling::function]
-    fn lease_render_pass<P>(
-        &self,
-        pool: &mut P,
-        pass_idx: usize,
-    ) -> Result<Lease<RenderPass>, DriverError>
-    where
-        P: Pool<RenderPassInfo, RenderPass> + ?Sized,
-    {
+    fn render_pass_info(&self, pass_idx: usize) -> RenderPassInfo {
         let pass = &self.graph.passes[pass_idx];
         let (mut color_attachment_count, mut depth_stencil_attachment_count) = (0, 0);
         for exec in &pass.execs {
@@ -1685,23 +2419,117 @@ impl Resolver {
                 dependencies.into_values().collect::<Vec<_>>()
             };
 
-        pool.lease(RenderPassInfo {
+        RenderPassInfo {
             attachments,
             dependencies,
             subpasses,
-        })
+        }
+    }
+
+    // Adds the usage flags required by the passes of a graph to the information of its transient
+    // resources, which do not exist until the graph is resolved
+    #[profiling::function]
+    fn infer_transient_usage(graph: &mut RenderGraph) {
+        let mut buffer_usage = HashMap::<NodeIndex, vk::BufferUsageFlags>::new();
+        let mut image_usage = HashMap::<NodeIndex, vk::ImageUsageFlags>::new();
+
+        for pass in &graph.passes {
+            // Conditions are read by conditional rendering or copied into an indirect dispatch
+            if let Some(condition) = &pass.condition {
+                *buffer_usage.entry(condition.node_idx).or_default() |=
+                    vk::BufferUsageFlags::CONDITIONAL_RENDERING_EXT
+                        | vk::BufferUsageFlags::TRANSFER_SRC;
+            }
+
+            for exec in &pass.execs {
+                for (&node_idx, accesses) in &exec.accesses {
+                    for access in accesses {
+                        let (buffer, image) = access_usage(access.access);
+                        *buffer_usage.entry(node_idx).or_default() |= buffer;
+                        *image_usage.entry(node_idx).or_default() |= image;
+                    }
+                }
+
+                let Some(pipeline) = &exec.pipeline else {
+                    continue;
+                };
+
+                for (descriptor, &(node_idx, _)) in &exec.bindings {
+                    let (descriptor_set_idx, dst_binding, _) = descriptor.into_tuple();
+                    let Some((descriptor_info, _)) = pipeline
+                        .descriptor_bindings()
+                        .get(&DescriptorBinding(descriptor_set_idx, dst_binding))
+                    else {
+                        continue;
+                    };
+
+                    let (buffer, image) = match descriptor_info {
+                        DescriptorInfo::CombinedImageSampler(..)
+                        | DescriptorInfo::SampledImage(_) => {
+                            (vk::BufferUsageFlags::empty(), vk::ImageUsageFlags::SAMPLED)
+                        }
+                        DescriptorInfo::InputAttachment(..) => (
+                            vk::BufferUsageFlags::empty(),
+                            vk::ImageUsageFlags::INPUT_ATTACHMENT,
+                        ),
+                        DescriptorInfo::StorageBuffer(_) => (
+                            vk::BufferUsageFlags::STORAGE_BUFFER,
+                            vk::ImageUsageFlags::empty(),
+                        ),
+                        DescriptorInfo::StorageImage(_) => {
+                            (vk::BufferUsageFlags::empty(), vk::ImageUsageFlags::STORAGE)
+                        }
+                        DescriptorInfo::StorageTexelBuffer(_) => (
+                            vk::BufferUsageFlags::STORAGE_TEXEL_BUFFER,
+                            vk::ImageUsageFlags::empty(),
+                        ),
+                        DescriptorInfo::UniformBuffer(_) => (
+                            vk::BufferUsageFlags::UNIFORM_BUFFER,
+                            vk::ImageUsageFlags::empty(),
+                        ),
+                        DescriptorInfo::UniformTexelBuffer(_) => (
+                            vk::BufferUsageFlags::UNIFORM_TEXEL_BUFFER,
+                            vk::ImageUsageFlags::empty(),
+                        ),
+                        DescriptorInfo::AccelerationStructure(_) | DescriptorInfo::Sampler(..) => {
+                            continue
+                        }
+                    };
+
+                    *buffer_usage.entry(node_idx).or_default() |= buffer;
+                    *image_usage.entry(node_idx).or_default() |= image;
+                }
+            }
+        }
+
+        for (node_idx, binding) in graph.bindings.iter_mut().enumerate() {
+            match binding {
+                Binding::TransientBuffer(info, None) => {
+                    info.usage |= buffer_usage.get(&node_idx).copied().unwrap_or_default();
+                }
+                Binding::TransientImage(info, None) => {
+                    info.usage |= image_usage.get(&node_idx).copied().unwrap_or_default();
+                }
+                _ => (),
+            }
+        }
     }
 
     #[profiling::function]
     fn lease_scheduled_resources<P>(
         &mut self,
+        cmd_buf: &mut CommandBuffer,
         pool: &mut P,
         schedule: &[usize],
+        compiled_passes: Option<&[CompiledPass]>,
     ) -> Result<(), DriverError>
     where
         P: Pool<DescriptorPoolInfo, DescriptorPool> + Pool<RenderPassInfo, RenderPass> + ?Sized,
     {
-        for pass_idx in schedule.iter().copied() {
+        for (schedule_idx, pass_idx) in schedule.iter().copied().enumerate() {
+            let compiled_pass =
+                compiled_passes.map(|compiled_passes| &compiled_passes[schedule_idx]);
+
             // At the time this function runs the pass will already have been optimized into a
             // larger pass made out of anything that might have been merged into it - so we
             // only care about one pass at a time here
@@ -1709,7 +2537,15 @@ impl Resolver {
 
             trace!("leasing [{pass_idx}: {}]", pass.name);
 
-            let descriptor_pool = Self::lease_descriptor_pool(pool, pass)?;
+            let descriptor_pool = if let Some(compiled_pass) = compiled_pass {
+                compiled_pass
+                    .descriptor_pool_info
+                    .clone()
+                    .map(|info| pool.lease(info))
+                    .transpose()?
+            } else {
+                Self::lease_descriptor_pool(pool, pass)?
+            };
             let mut exec_descriptor_sets = HashMap::with_capacity(
                 descriptor_pool
                     .as_ref()
@@ -1737,6 +2573,14 @@ impl Resolver {
                 }
             }
 
+            // Conditional passes are predicated by the device when it supports conditional rendering
+            let conditional_dispatch_args =
+                if pass.condition.is_some() && cmd_buf.device.conditional_rendering_ext.is_none() {
+                    Some(Self::create_conditional_dispatch_args(cmd_buf, pool, pass)?)
+                } else {
+                    None
+                };
+
             // Note that as a side effect of merging compatible passes all input passes should
             // be globbed onto their preceeding passes by now. This allows subpasses to use
             // input attachments without really doing anything, so we are provided a pass that
@@ -1758,22 +2602,38 @@ impl Resolver {
                         .is_none()
             );
 
-            // Also the renderpass may just be None if the pass contained no graphic ops.
-            let render_pass = if pass.execs[0]
+            // Also the renderpass may just be None if the pass contained no graphic ops. Passes with
+            // a single subpass use dynamic rendering when the device supports it; subpasses which
+            // read input attachments require VK_KHR_dynamic_rendering_local_read, which is not
+            // supported, so those passes (and merged passes) use render pass objects instead.
+            let (render_pass, rendering) = if pass.execs[0]
                 .pipeline
                 .as_ref()
                 .map(|pipeline| pipeline.is_graphic())
                 .unwrap_or_default()
             {
-                Some(self.lease_render_pass(pool, pass_idx)?)
+                let info = compiled_pass
+                    .and_then(|compiled_pass| compiled_pass.render_pass_info.clone())
+                    .unwrap_or_else(|| self.render_pass_info(pass_idx));
+
+                if cmd_buf.device.dynamic_rendering_ext.is_some()
+                    && info.subpasses.len() == 1
+                    && info.subpasses[0].input_attachments.is_empty()
+                {
+                    (None, Some(Rendering::new(info)))
+                } else {
+                    (Some(pool.lease(info)?), None)
+                }
             } else {
-                None
+                (None, None)
             };
 
             self.physical_passes.push(PhysicalPass {
+                conditional_dispatch_args,
                 descriptor_pool,
                 exec_descriptor_sets,
                 render_pass,
+                rendering,
             });
         }
 
@@ -1781,13 +2641,21 @@ impl Resolver {
     }
 
     // Merges passes which are graphic with common-ish attachments - note that scheduled pass order
-    // is final during this function and so we must merge contiguous groups of passes
+    // is final during this function and so we must merge contiguous groups of passes. Returns the
+    // number of scheduled passes in each merged pass, which may be given instead of searching for
+    // compatible passes.
     #[profiling::function]
-    fn merge_scheduled_passes(&mut self, schedule: &mut Vec<usize>) {
+    fn merge_scheduled_passes(
+        &mut self,
+        schedule: &mut Vec<usize>,
+        merged_pass_counts: Option<&[usize]>,
+    ) -> Vec<usize> {
         thread_local! {
             static PASSES: RefCell<Vec<Option<Pass>>> = Default::default();
         }
 
+        let mut res = Vec::with_capacity(schedule.len());
+
         PASSES.with_borrow_mut(|passes| {
             debug_assert!(passes.is_empty());
 
@@ -1803,7 +2671,12 @@ impl Resolver {
                 // Find candidates
                 let start = idx + 1;
                 let mut end = start;
-                while end < schedule.len() {
+
+                if let Some(merged_pass_counts) = merged_pass_counts {
+                    end += merged_pass_counts[res.len()] - 1;
+                }
+
+                while merged_pass_counts.is_none() && end < schedule.len() {
                     let other = passes[schedule[end]].as_ref().unwrap();
                     debug!(
                         "attempting to merge [{idx}: {}] with [{end}: {}]",
@@ -1842,6 +2715,7 @@ impl Resolver {
                 }
 
                 self.graph.passes.push(pass);
+                res.push(1 + end - start);
                 idx += 1 + end - start;
             }
 
@@ -1857,15 +2731,33 @@ impl Resolver {
                 self.graph.passes.push(pass);
             }
         });
+
+        res
+    }
+
+    // Gives the debugging names of the buffers and images accessed by the scheduled passes to their
+    // Vulkan objects
+    fn name_scheduled_resources(&self, schedule: &[usize]) {
+        for exec in self.graph.passes[0..schedule.len()]
+            .iter()
+            .flat_map(|pass| pass.execs.iter())
+        {
+            for &node_idx in exec.accesses.keys() {
+                let binding = &self.graph.bindings[node_idx];
+                if let Some(buffer) = binding.as_driver_buffer() {
+                    Buffer::name_object(buffer);
+                } else if let Some(image) = binding.as_driver_image() {
+                    Image::name_object(image);
+                }
+            }
+        }
     }
 
-    fn next_subpass(cmd_buf: &CommandBuffer) {
+    fn next_subpass(cmd_buf: &CommandBuffer, contents: vk::SubpassContents) {
         trace!("next_subpass");
 
         unsafe {
-            cmd_buf
-                .device
-                .cmd_next_subpass(**cmd_buf, vk::SubpassContents::INLINE);
+            cmd_buf.device.cmd_next_subpass(**cmd_buf, contents);
         }
     }
 
@@ -1904,19 +2796,236 @@ impl Resolver {
         res
     }
 
+    /// Returns a report of the work [`Self::record_node`] would record for the given node, without
+    /// recording anything.
+    ///
+    /// Passes which the node does not depend on are reported but not scheduled.
+    #[profiling::function]
+    pub fn node_schedule_report(&self, node: impl Node) -> ScheduleReport {
+        let node_idx = node.index();
+
+        debug_assert!(self.graph.bindings.get(node_idx).is_some());
+
+        let end_pass_idx = self.graph.passes.len();
+        let mut schedule = Schedule::default();
+
+        if end_pass_idx > 0 {
+            schedule.access_cache.update(&self.graph, end_pass_idx);
+            self.schedule_node_passes(node_idx, end_pass_idx, &mut schedule);
+        }
+
+        self.report_scheduled_passes(schedule, end_pass_idx)
+    }
+
+    // Returns the queue family and queue index which executes the given pass
+    fn pass_queue(device: &Device, pass: &Pass, queue: (usize, usize)) -> (usize, usize) {
+        let (is_graphic, has_pipeline) =
+            pass.execs
+                .iter()
+                .fold((false, false), |(is_graphic, has_pipeline), exec| {
+                    (
+                        is_graphic
+                            || exec
+                                .pipeline
+                                .as_ref()
+                                .map(|pipeline| pipeline.is_graphic())
+                                .unwrap_or_default(),
+                        has_pipeline || exec.pipeline.is_some(),
+                    )
+                });
+
+        if is_graphic {
+            return queue;
+        }
+
+        let find_queue_family = |flags: vk::QueueFlags, excluded_flags: vk::QueueFlags| {
+            device
+                .physical_device
+                .queue_families
+                .iter()
+                .position(|queue_family| {
+                    queue_family.queue_count > 0
+                        && queue_family.queue_flags.contains(flags)
+                        && !queue_family.queue_flags.intersects(excluded_flags)
+                })
+        };
+        let compute_queue_family =
+            || find_queue_family(vk::QueueFlags::COMPUTE, vk::QueueFlags::GRAPHICS);
+
+        match pass.queue {
+            QueueType::Graphics => None,
+            QueueType::AsyncCompute => compute_queue_family(),
+            QueueType::Transfer if has_pipeline => compute_queue_family(),
+            QueueType::Transfer => find_queue_family(
+                vk::QueueFlags::TRANSFER,
+                vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE,
+            )
+            .or_else(compute_queue_family),
+        }
+        .filter(|&queue_family_index| queue_family_index != queue.0)
+        .map(|queue_family_index| (queue_family_index, 0))
+        .unwrap_or(queue)
+    }
+
+    // Optimizes the schedule and leases the resources it requires; afterwards the scheduled passes
+    // are the first passes of the graph
+    #[profiling::function]
+    fn prepare_scheduled_passes<P>(
+        &mut self,
+        pool: &mut P,
+        cmd_buf: &mut CommandBuffer,
+        queue: Option<(usize, usize)>,
+        schedule: &mut Schedule,
+        end_pass_idx: usize,
+    ) -> Result<(), DriverError>
+    where
+        P: Pool<DescriptorPoolInfo, DescriptorPool> + Pool<RenderPassInfo, RenderPass> + ?Sized,
+    {
+        // Print some handy details or hit a breakpoint if you set the flag
+        #[cfg(debug_assertions)]
+        if log_enabled!(Debug) && self.graph.debug {
+            debug!("resolving the following graph:\n\n{:#?}\n\n", self.graph);
+        }
+
+        debug_assert!(
+            schedule.passes.windows(2).all(|w| w[0] <= w[1]),
+            "Unsorted schedule"
+        );
+
+        let device = Arc::clone(&cmd_buf.device);
+
+        for upload in &mut self.graph.uploads {
+            Upload::stage(upload, &device)?;
+        }
+
+        let template = self.template.clone().map(|template| {
+            let key = shape_key(&self.graph, &schedule.passes, end_pass_idx);
+            let compiled = template.compiled(key);
+
+            (template, key, compiled)
+        });
+
+        // Graphs with the same shape as the compiled template reuse its results
+        if let Some((_, _, Some(compiled))) = &template {
+            debug!("using compiled graph template");
+
+            schedule.passes.clone_from(&compiled.schedule);
+            self.merge_scheduled_passes(&mut schedule.passes, Some(&compiled.merged_pass_counts));
+            self.create_scheduled_transients(cmd_buf, &schedule.passes, queue)?;
+            self.name_scheduled_resources(&schedule.passes);
+            self.lease_scheduled_resources(
+                cmd_buf,
+                pool,
+                &schedule.passes,
+                Some(&compiled.passes),
+            )?;
+
+            return Ok(());
+        }
+
+        // Optimize the schedule; leasing the required stuff it needs
+        Self::reorder_scheduled_passes(schedule, end_pass_idx);
+
+        let reordered_schedule = template.as_ref().map(|_| schedule.passes.clone());
+        let merged_pass_counts = self.merge_scheduled_passes(&mut schedule.passes, None);
+
+        self.create_scheduled_transients(cmd_buf, &schedule.passes, queue)?;
+        self.name_scheduled_resources(&schedule.passes);
+        self.lease_scheduled_resources(cmd_buf, pool, &schedule.passes, None)?;
+
+        if let Some(((template, key, _), reordered_schedule)) = template.zip(reordered_schedule) {
+            debug!("compiling graph template");
+
+            let compiled_passes = self
+                .physical_passes
+                .iter()
+                .map(|physical_pass| CompiledPass {
+                    descriptor_pool_info: physical_pass
+                        .descriptor_pool
+                        .as_ref()
+                        .map(|descriptor_pool| descriptor_pool.info.clone()),
+                    render_pass_info: physical_pass.render_pass_info().cloned(),
+                })
+                .collect();
+            template.compile(CompiledGraph::new(
+                &self.graph,
+                key,
+                reordered_schedule,
+                merged_pass_counts,
+                compiled_passes,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Enables GPU timestamp queries around each physical pass recorded by the submission of this
+    /// instance and returns a handle to the results.
+    ///
+    /// The results become available once the command buffer returned by [`Self::submit`], or
+    /// a similar function, has executed. Passes which are recorded without submitting, such as by
+    /// [`Self::record_node`], are not timed.
+    ///
+    /// Passes submitted to queue families which do not support timestamps are not reported, and
+    /// neither are passes submitted to transfer-only queue families of devices which do not support
+    /// [`Vulkan12Features::host_query_reset`](crate::driver::physical_device::Vulkan12Features::host_query_reset).
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+    /// # use screen_13::graph::RenderGraph;
+    /// # use screen_13::pool::lazy::LazyPool;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let mut pool = LazyPool::new(&device);
+    /// # let info = BufferInfo::device_mem(1024, vk::BufferUsageFlags::TRANSFER_DST);
+    /// # let buf = Buffer::create(&device, info)?;
+    /// let mut my_graph = RenderGraph::new();
+    /// let my_buf = my_graph.bind_node(buf);
+    /// my_graph.fill_buffer(my_buf, 0);
+    ///
+    /// let mut resolver = my_graph.resolve();
+    /// let timestamps = resolver.query_timestamps();
+    /// let cmd_buf = resolver.submit(&mut pool, 0, 0)?;
+    ///
+    /// cmd_buf.wait_until_executed()?;
+    ///
+    /// for pass in timestamps.report()?.unwrap_or_default() {
+    ///     println!("{}: {:?}", pass.name, pass.duration);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn query_timestamps(&mut self) -> PassTimestamps {
+        self.timestamps
+            .get_or_insert_with(|| PassTimestamps {
+                queries: Default::default(),
+            })
+            .clone()
+    }
+
+    /// Records the barriers required before the given executions, which are recorded together.
+    ///
+    /// Barriers which are made redundant by earlier barriers recorded into the same command buffer
+    /// are elided; the remaining barriers are batched into a single pipeline barrier unless a node is
+    /// accessed differently by more than one of the executions.
     #[profiling::function]
     fn record_execution_barriers(
         trace_pad: &'static str,
         cmd_buf: &CommandBuffer,
         bindings: &mut [Binding],
-        exec: &mut Execution,
-        record_framebuffer_access: bool,
+        barrier_state: &mut BarrierState,
+        execs: &mut [Execution],
+        split_barrier: Option<&SplitBarrierWait>,
     ) {
         use std::slice::from_ref;
 
-        // TODO: Notice the very common case where we have previously barriered on something which
-        // has not had any access since the previous barrier
-
         // We store a Barriers in TLS to save an alloc; contents are POD
         thread_local! {
             static BARRIERS: RefCell<Barriers> = Default::default();
@@ -1924,18 +3033,166 @@ impl Resolver {
 
         struct Barrier<T> {
             next_access: AccessType,
-            prev_access: AccessType,
+            prev_accesses: Range<usize>,
             resource: T,
         }
 
         #[derive(Default)]
-        struct Barriers {
+        struct BarrierSet {
+            accesses: Vec<AccessType>,
             buffers: Vec<Barrier<BufferResource>>,
             images: Vec<Barrier<ImageResource>>,
             next_accesses: Vec<AccessType>,
+            nodes: Vec<NodeIndex>,
             prev_accesses: Vec<AccessType>,
         }
 
+        impl BarrierSet {
+            fn clear(&mut self) {
+                self.accesses.clear();
+                self.buffers.clear();
+                self.images.clear();
+                self.next_accesses.clear();
+                self.nodes.clear();
+                self.prev_accesses.clear();
+            }
+
+            fn is_empty(&self) -> bool {
+                self.nodes.is_empty()
+            }
+
+            fn push(
+                &mut self,
+                node_idx: NodeIndex,
+                next_access: AccessType,
+                prev_accesses: impl Iterator<Item = AccessType>,
+                resource: Option<Resource>,
+            ) {
+                self.nodes.push(node_idx);
+
+                match resource {
+                    Some(Resource::Buffer(resource)) => {
+                        let start = self.accesses.len();
+                        self.accesses.extend(prev_accesses);
+                        self.buffers.push(Barrier {
+                            next_access,
+                            prev_accesses: start..self.accesses.len(),
+                            resource,
+                        });
+                    }
+                    Some(Resource::Image(resource)) => {
+                        let start = self.accesses.len();
+                        self.accesses.extend(prev_accesses);
+                        self.images.push(Barrier {
+                            next_access,
+                            prev_accesses: start..self.accesses.len(),
+                            resource,
+                        });
+                    }
+                    None => {
+                        // HACK: It would be nice if AccessType was PartialOrd..
+                        if !self.next_accesses.contains(&next_access) {
+                            self.next_accesses.push(next_access);
+                        }
+
+                        for prev_access in prev_accesses {
+                            if !self.prev_accesses.contains(&prev_access) {
+                                self.prev_accesses.push(prev_access);
+                            }
+                        }
+                    }
+                }
+            }
+
+            fn global_barrier(&self) -> Option<GlobalBarrier<'_>> {
+                // No resource attached - we use a global barrier for these
+                (!self.next_accesses.is_empty()).then_some(GlobalBarrier {
+                    next_accesses: self.next_accesses.as_slice(),
+                    previous_accesses: self.prev_accesses.as_slice(),
+                })
+            }
+
+            fn buffer_barriers(&self) -> Box<[BufferBarrier<'_>]> {
+                self.buffers
+                    .iter()
+                    .map(
+                        |Barrier {
+                             next_access,
+                             prev_accesses,
+                             resource,
+                         }| {
+                            let BufferResource {
+                                buffer,
+                                offset,
+                                size,
+                            } = *resource;
+                            BufferBarrier {
+                                next_accesses: from_ref(next_access),
+                                previous_accesses: &self.accesses[prev_accesses.clone()],
+                                src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+                                dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+                                buffer,
+                                offset,
+                                size,
+                            }
+                        },
+                    )
+                    .collect()
+            }
+
+            fn image_barriers(&self) -> Box<[ImageBarrier<'_>]> {
+                self.images
+                    .iter()
+                    .map(
+                        |Barrier {
+                             next_access,
+                             prev_accesses,
+                             resource,
+                         }| {
+                            let ImageResource { image, range } = *resource;
+                            let previous_accesses = &self.accesses[prev_accesses.clone()];
+                            let prev_access = previous_accesses[0];
+                            ImageBarrier {
+                                next_accesses: from_ref(next_access),
+                                next_layout: image_access_layout(*next_access),
+                                previous_accesses,
+                                previous_layout: image_access_layout(prev_access),
+                                discard_contents: prev_access == AccessType::Nothing
+                                    || is_write_access(*next_access),
+                                src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+                                dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+                                image,
+                                range,
+                            }
+                        },
+                    )
+                    .collect()
+            }
+
+            fn record(&self, cmd_buf: &CommandBuffer, counter: &BarrierCounterInner) {
+                if self.is_empty() {
+                    return;
+                }
+
+                counter.pipeline_barriers.fetch_add(1, Ordering::Relaxed);
+
+                Resolver::record_pipeline_barrier(
+                    cmd_buf,
+                    self.global_barrier(),
+                    &self.buffer_barriers(),
+                    &self.image_barriers(),
+                );
+            }
+        }
+
+        /// Barriers recorded using a pipeline barrier and, separately, barriers which wait on the
+        /// events of a split barrier
+        #[derive(Default)]
+        struct Barriers {
+            pipeline: BarrierSet,
+            split: BarrierSet,
+        }
+
         struct BufferResource {
             buffer: vk::Buffer,
             offset: usize,
@@ -1952,20 +3209,23 @@ impl Resolver {
             Image(ImageResource),
         }
 
+        barrier_state.begin(**cmd_buf);
+
+        let counter = Arc::clone(&barrier_state.counter.0);
+
         BARRIERS.with_borrow_mut(|barriers| {
             // Initialize TLS from a previous call
-            barriers.buffers.clear();
-            barriers.images.clear();
-            barriers.next_accesses.clear();
-            barriers.prev_accesses.clear();
+            barriers.pipeline.clear();
+            barriers.split.clear();
 
             // Map remaining accesses into vk_sync barriers (some accesses may have been removed by the
             // render pass leasing function)
-            let barriers = exec
-                .accesses
-                .iter()
-                .filter_map(|(node_idx, [early, late])| {
-                    let binding = &mut bindings[*node_idx];
+            for (exec_idx, exec) in execs.iter_mut().enumerate() {
+                // Framebuffer accesses of later executions are handled by subpass dependencies
+                let record_framebuffer_access = exec_idx == 0;
+
+                for (node_idx, [early, late]) in Self::sorted_accesses(exec) {
+                    let binding = &mut bindings[node_idx];
                     let next_access = early.access;
                     let prev_access = if let Some(buffer) = binding.as_driver_buffer() {
                         Buffer::access(buffer, late.access)
@@ -1973,167 +3233,248 @@ impl Resolver {
                         Image::access(image, late.access)
                     } else if let Some(accel_struct) = binding.as_driver_acceleration_structure() {
                         AccelerationStructure::access(accel_struct, late.access)
+                    } else if let Some(query_pool) = binding.as_driver_query_pool() {
+                        QueryPool::access(query_pool, late.access)
                     } else {
                         unimplemented!();
                     };
+                    let is_image = binding.as_driver_image().is_some();
+                    let aliases = barrier_state.aliases.remove(&node_idx);
+                    let is_required = aliases.is_some()
+                        || barrier_state.is_required(
+                            node_idx,
+                            early.subresource,
+                            is_image,
+                            prev_access,
+                            next_access,
+                        );
+
+                    // Image barriers only wait on the previous access because any reads which have
+                    // been elided since then were the same access
+                    let mut prev_accesses = vec![prev_access];
+                    if !is_image {
+                        for read in barrier_state.reads(node_idx) {
+                            // HACK: It would be nice if AccessType was PartialOrd..
+                            if !prev_accesses.contains(&read) {
+                                prev_accesses.push(read);
+                            }
+                        }
+                    }
+
+                    if is_image
+                        && early.subresource.is_some()
+                        && !record_framebuffer_access
+                        && is_framebuffer_access(next_access)
+                    {
+                        barrier_state.access(node_idx, early.subresource, early, late, false);
+
+                        // The render pass transitions the image but shared memory must be available
+                        if let Some(aliases) = aliases {
+                            barriers.pipeline.push(
+                                node_idx,
+                                next_access,
+                                aliases.into_iter(),
+                                None,
+                            );
+                        }
+
+                        continue;
+                    }
+
+                    // The previous access of a transient node which shares memory with earlier
+                    // nodes is nothing, so image barriers discard the contents of the memory
+                    for alias in aliases.into_iter().flatten() {
+                        if !prev_accesses.contains(&alias) {
+                            prev_accesses.push(alias);
+                        }
+                    }
+
+                    barrier_state.access(node_idx, early.subresource, early, late, true);
+
+                    if !is_required {
+                        trace!("{trace_pad}elided {node_idx} {prev_access:?} -> {next_access:?}");
+
+                        counter.elided.fetch_add(1, Ordering::Relaxed);
+
+                        continue;
+                    }
+
+                    counter.recorded.fetch_add(1, Ordering::Relaxed);
+
+                    let split = split_barrier
+                        .map(|split_barrier| split_barrier.nodes.contains(&node_idx))
+                        .unwrap_or_default();
+                    let barrier_set = if split {
+                        &mut barriers.split
+                    } else {
+                        // Nodes accessed by more than one execution require ordered barriers
+                        if barriers.pipeline.nodes.contains(&node_idx) {
+                            barriers.pipeline.record(cmd_buf, &counter);
+                            barriers.pipeline.clear();
+                        }
+
+                        &mut barriers.pipeline
+                    };
 
                     // If we find a subresource then it must have a resource attached
-                    if let Some(subresource) = early.subresource {
+                    let resource = early.subresource.and_then(|subresource| {
                         if let Some(buf) = binding.as_driver_buffer() {
                             let range = subresource.unwrap_buffer();
 
                             trace!(
-                                "{trace_pad}buffer {:?} {}..{} {:?} -> {:?}",
-                                binding.as_driver_buffer().unwrap(),
+                                "{trace_pad}buffer {:?} {}..{} {:?} -> {:?}{}",
+                                buf,
                                 range.start,
                                 range.end,
                                 next_access,
-                                prev_access,
+                                prev_accesses,
+                                if split { " (split)" } else { "" },
                             );
 
-                            return Some(Barrier {
-                                next_access,
-                                prev_access,
-                                resource: Some(Resource::Buffer(BufferResource {
-                                    buffer: **buf,
-                                    offset: range.start as _,
-                                    size: (range.end - range.start) as _,
-                                })),
-                            });
+                            Some(Resource::Buffer(BufferResource {
+                                buffer: **buf,
+                                offset: range.start as _,
+                                size: (range.end - range.start) as _,
+                            }))
                         } else if let Some(image) = binding.as_driver_image() {
                             let range = subresource.unwrap_image().into_vk();
 
-                            if !record_framebuffer_access && is_framebuffer_access(next_access) {
-                                return None;
-                            }
-
                             trace!(
-                                "{trace_pad}image {:?} {:?}-{:?} -> {:?}-{:?}",
-                                binding.as_driver_image().unwrap(),
+                                "{trace_pad}image {:?} {:?}-{:?} -> {:?}-{:?}{}",
+                                image,
                                 prev_access,
                                 image_access_layout(prev_access),
                                 next_access,
                                 image_access_layout(next_access),
+                                if split { " (split)" } else { "" },
                             );
 
-                            return Some(Barrier {
-                                next_access,
-                                prev_access,
-                                resource: Some(Resource::Image(ImageResource {
-                                    image: **image,
-                                    range,
-                                })),
-                            });
+                            Some(Resource::Image(ImageResource {
+                                image: **image,
+                                range,
+                            }))
+                        } else {
+                            None
                         }
+                    });
+
+                    if resource.is_none() {
+                        trace!(
+                            "{trace_pad}barrier {:?} -> {:?}{}",
+                            next_access,
+                            prev_accesses,
+                            if split { " (split)" } else { "" },
+                        );
                     }
 
-                    Some(Barrier {
-                        next_access,
-                        prev_access,
-                        resource: None,
-                    })
-                })
-                .fold(barriers, |barriers, barrier| {
-                    let Barrier {
-                        next_access,
-                        prev_access,
-                        resource,
-                    } = barrier;
-                    match resource {
-                        Some(Resource::Buffer(resource)) => {
-                            barriers.buffers.push(Barrier {
-                                next_access,
-                                prev_access,
-                                resource,
-                            });
-                        }
-                        Some(Resource::Image(resource)) => {
-                            barriers.images.push(Barrier {
-                                next_access,
-                                prev_access,
-                                resource,
-                            });
-                        }
-                        None => {
-                            // HACK: It would be nice if AccessType was PartialOrd..
-                            if !barriers.next_accesses.contains(&next_access) {
-                                barriers.next_accesses.push(next_access);
-                            }
+                    barrier_set.push(node_idx, next_access, prev_accesses.into_iter(), resource);
+                }
+            }
 
-                            if !barriers.prev_accesses.contains(&prev_access) {
-                                barriers.prev_accesses.push(prev_access);
-                            }
-                        }
-                    }
-                    barriers
-                });
-            let global_barrier = if !barriers.next_accesses.is_empty() {
-                // No resource attached - we use a global barrier for these
-                trace!(
-                    "{trace_pad}barrier {:?} -> {:?}",
-                    barriers.next_accesses,
-                    barriers.prev_accesses
-                );
+            barriers.pipeline.record(cmd_buf, &counter);
 
-                Some(GlobalBarrier {
-                    next_accesses: barriers.next_accesses.as_slice(),
-                    previous_accesses: barriers.prev_accesses.as_slice(),
-                })
-            } else {
-                None
-            };
-            let buffer_barriers = barriers.buffers.iter().map(
-                |Barrier {
-                     next_access,
-                     prev_access,
-                     resource,
-                 }| {
-                    let BufferResource {
-                        buffer,
-                        offset,
-                        size,
-                    } = *resource;
-                    BufferBarrier {
-                        next_accesses: from_ref(next_access),
-                        previous_accesses: from_ref(prev_access),
-                        src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
-                        dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
-                        buffer,
-                        offset,
-                        size,
-                    }
-                },
-            );
-            let image_barriers = barriers.images.iter().map(
-                |Barrier {
-                     next_access,
-                     prev_access,
-                     resource,
-                 }| {
-                    let ImageResource { image, range } = *resource;
-                    ImageBarrier {
-                        next_accesses: from_ref(next_access),
-                        next_layout: image_access_layout(*next_access),
-                        previous_accesses: from_ref(prev_access),
-                        previous_layout: image_access_layout(*prev_access),
-                        discard_contents: *prev_access == AccessType::Nothing
-                            || is_write_access(*next_access),
-                        src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
-                        dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
-                        image,
-                        range,
-                    }
-                },
-            );
+            if let Some(split_barrier) = split_barrier.filter(|_| !barriers.split.is_empty()) {
+                trace!("{trace_pad}wait {:?}", split_barrier.events);
+
+                wait_events(
+                    &cmd_buf.device,
+                    **cmd_buf,
+                    &split_barrier.events,
+                    barriers.split.global_barrier(),
+                    &barriers.split.buffer_barriers(),
+                    &barriers.split.image_barriers(),
+                );
+            }
+        });
+    }
 
+    /// Records a pipeline barrier using `vkCmdPipelineBarrier2` if the device supports
+    /// `VK_KHR_synchronization2`, or `vkCmdPipelineBarrier` otherwise.
+    fn record_pipeline_barrier(
+        cmd_buf: &CommandBuffer,
+        global_barrier: Option<GlobalBarrier>,
+        buffer_barriers: &[BufferBarrier],
+        image_barriers: &[ImageBarrier],
+    ) {
+        let Some(synchronization2_ext) = cmd_buf.device.synchronization2_ext.as_ref() else {
             pipeline_barrier(
                 &cmd_buf.device,
                 **cmd_buf,
                 global_barrier,
-                &buffer_barriers.collect::<Box<[_]>>(),
-                &image_barriers.collect::<Box<[_]>>(),
+                buffer_barriers,
+                image_barriers,
             );
-        });
+
+            return;
+        };
+
+        // The first 32 bits of the synchronization2 flags match the original flags
+        fn access_flags2(flags: vk::AccessFlags) -> vk::AccessFlags2 {
+            vk::AccessFlags2::from_raw(flags.as_raw() as _)
+        }
+
+        fn pipeline_stage_flags2(flags: vk::PipelineStageFlags) -> vk::PipelineStageFlags2 {
+            vk::PipelineStageFlags2::from_raw(flags.as_raw() as _)
+        }
+
+        let memory_barriers = global_barrier
+            .iter()
+            .map(|barrier| {
+                let (src_stage_mask, dst_stage_mask, barrier) = get_memory_barrier(barrier);
+
+                vk::MemoryBarrier2::builder()
+                    .src_stage_mask(pipeline_stage_flags2(src_stage_mask))
+                    .src_access_mask(access_flags2(barrier.src_access_mask))
+                    .dst_stage_mask(pipeline_stage_flags2(dst_stage_mask))
+                    .dst_access_mask(access_flags2(barrier.dst_access_mask))
+                    .build()
+            })
+            .collect::<Box<_>>();
+        let buffer_memory_barriers = buffer_barriers
+            .iter()
+            .map(|barrier| {
+                let (src_stage_mask, dst_stage_mask, barrier) = get_buffer_memory_barrier(barrier);
+
+                vk::BufferMemoryBarrier2::builder()
+                    .src_stage_mask(pipeline_stage_flags2(src_stage_mask))
+                    .src_access_mask(access_flags2(barrier.src_access_mask))
+                    .dst_stage_mask(pipeline_stage_flags2(dst_stage_mask))
+                    .dst_access_mask(access_flags2(barrier.dst_access_mask))
+                    .src_queue_family_index(barrier.src_queue_family_index)
+                    .dst_queue_family_index(barrier.dst_queue_family_index)
+                    .buffer(barrier.buffer)
+                    .offset(barrier.offset)
+                    .size(barrier.size)
+                    .build()
+            })
+            .collect::<Box<_>>();
+        let image_memory_barriers = image_barriers
+            .iter()
+            .map(|barrier| {
+                let (src_stage_mask, dst_stage_mask, barrier) = get_image_memory_barrier(barrier);
+
+                vk::ImageMemoryBarrier2::builder()
+                    .src_stage_mask(pipeline_stage_flags2(src_stage_mask))
+                    .src_access_mask(access_flags2(barrier.src_access_mask))
+                    .dst_stage_mask(pipeline_stage_flags2(dst_stage_mask))
+                    .dst_access_mask(access_flags2(barrier.dst_access_mask))
+                    .old_layout(barrier.old_layout)
+                    .new_layout(barrier.new_layout)
+                    .src_queue_family_index(barrier.src_queue_family_index)
+                    .dst_queue_family_index(barrier.dst_queue_family_index)
+                    .image(barrier.image)
+                    .subresource_range(barrier.subresource_range)
+                    .build()
+            })
+            .collect::<Box<_>>();
+        let dependency_info = vk::DependencyInfo::builder()
+            .memory_barriers(&memory_barriers)
+            .buffer_memory_barriers(&buffer_memory_barriers)
+            .image_memory_barriers(&image_memory_barriers);
+
+        unsafe {
+            synchronization2_ext.cmd_pipeline_barrier2(**cmd_buf, &dependency_info);
+        }
     }
 
     /// Records any pending render graph passes that are required by the given node, but does not
@@ -2211,226 +3552,656 @@ impl Resolver {
         })
     }
 
+    // Records the image layout transition of a node which was last accessed by another queue: the
+    // semaphore the command buffer waits on provides the execution and memory dependency, so the
+    // stages of the previous access (which this queue may not support) are not used. Exclusively
+    // owned images are also acquired from the queue family which released them.
     #[profiling::function]
-    fn record_scheduled_passes<P>(
-        &mut self,
-        pool: &mut P,
-        cmd_buf: &mut CommandBuffer,
-        schedule: &mut Schedule,
-        end_pass_idx: usize,
-    ) -> Result<(), DriverError>
-    where
-        P: Pool<DescriptorPoolInfo, DescriptorPool> + Pool<RenderPassInfo, RenderPass> + ?Sized,
-    {
-        if schedule.passes.is_empty() {
-            return Ok(());
-        }
+    fn record_queue_acquire(
+        cmd_buf: &CommandBuffer,
+        binding: &Binding,
+        next: SubresourceAccess,
+        src_queue_family_index: u32,
+    ) {
+        use std::slice::from_ref;
 
-        // Print some handy details or hit a breakpoint if you set the flag
-        #[cfg(debug_assertions)]
-        if log_enabled!(Debug) && self.graph.debug {
-            debug!("resolving the following graph:\n\n{:#?}\n\n", self.graph);
+        let image = match binding {
+            Binding::AccelerationStructure(..) | Binding::AccelerationStructureLease(..) => {
+                let accel_struct = binding.as_driver_acceleration_structure().unwrap();
+                AccelerationStructure::access(accel_struct, next.access);
+
+                return;
+            }
+            Binding::Buffer(..)
+            | Binding::BufferLease(..)
+            | Binding::TransientBuffer(_, Some(_)) => {
+                let buffer = binding.as_driver_buffer().unwrap();
+                Buffer::access(buffer, next.access);
+
+                return;
+            }
+            Binding::QueryPool(..) | Binding::QueryPoolLease(..) => {
+                let query_pool = binding.as_driver_query_pool().unwrap();
+                QueryPool::access(query_pool, next.access);
+
+                return;
+            }
+            Binding::TransientBuffer(_, None) | Binding::TransientImage(_, None) => {
+                // Resources which have not been created have no contents to acquire
+                return;
+            }
+            Binding::Image(..)
+            | Binding::ImageLease(..)
+            | Binding::SwapchainImage(..)
+            | Binding::TransientImage(_, Some(_)) => binding.as_driver_image().unwrap(),
+        };
+
+        let prev_access = Image::access(image, next.access);
+        let dst_queue_family_index = cmd_buf.info.queue_family_index;
+        let (src_queue_family_index, dst_queue_family_index) =
+            if Self::requires_queue_family_transfer(
+                binding,
+                prev_access,
+                next.access,
+                src_queue_family_index,
+                dst_queue_family_index,
+            ) {
+                (src_queue_family_index, dst_queue_family_index)
+            } else {
+                (vk::QUEUE_FAMILY_IGNORED, vk::QUEUE_FAMILY_IGNORED)
+            };
+        let range = next
+            .subresource
+            .map(|subresource| subresource.unwrap_image())
+            .unwrap_or_else(|| image.info.default_view_info().into())
+            .into_vk();
+        let (_, dst_stage_mask, mut image_barrier) = get_image_memory_barrier(&ImageBarrier {
+            next_accesses: from_ref(&next.access),
+            next_layout: image_access_layout(next.access),
+            previous_accesses: from_ref(&prev_access),
+            previous_layout: image_access_layout(prev_access),
+            discard_contents: prev_access == AccessType::Nothing || is_write_access(next.access),
+            src_queue_family_index,
+            dst_queue_family_index,
+            image: **image,
+            range,
+        });
+
+        if image_barrier.old_layout == image_barrier.new_layout
+            && src_queue_family_index == vk::QUEUE_FAMILY_IGNORED
+        {
+            return;
         }
 
-        debug_assert!(
-            schedule.passes.windows(2).all(|w| w[0] <= w[1]),
-            "Unsorted schedule"
+        trace!(
+            "  acquire image {:?} {:?} -> {:?} (queue family {} -> {})",
+            image,
+            image_barrier.old_layout,
+            image_barrier.new_layout,
+            src_queue_family_index,
+            dst_queue_family_index,
         );
 
-        // Optimize the schedule; leasing the required stuff it needs
-        self.reorder_scheduled_passes(schedule, end_pass_idx);
-        self.merge_scheduled_passes(&mut schedule.passes);
-        self.lease_scheduled_resources(pool, &schedule.passes)?;
+        image_barrier.src_access_mask = vk::AccessFlags::empty();
 
-        for pass_idx in schedule.passes.iter().copied() {
-            let pass = &mut self.graph.passes[pass_idx];
+        unsafe {
+            cmd_buf.device.cmd_pipeline_barrier(
+                **cmd_buf,
+                vk::PipelineStageFlags::TOP_OF_PIPE,
+                dst_stage_mask,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                from_ref(&image_barrier),
+            );
+        }
+    }
 
-            profiling::scope!("Pass", &pass.name);
+    // Records the release of an exclusively owned image to the queue family of its next access; the
+    // command buffer of that queue family records the matching acquire using record_queue_acquire
+    #[profiling::function]
+    fn record_queue_release(
+        cmd_buf: &CommandBuffer,
+        binding: &Binding,
+        next: SubresourceAccess,
+        dst_queue_family_index: u32,
+    ) {
+        use std::slice::from_ref;
 
-            let physical_pass = &mut self.physical_passes[pass_idx];
-            let is_graphic = physical_pass.render_pass.is_some();
+        let image = binding.as_driver_image().unwrap();
+        let prev_access = Image::peek_access(image);
+        let src_queue_family_index = cmd_buf.info.queue_family_index;
+
+        if !Self::requires_queue_family_transfer(
+            binding,
+            prev_access,
+            next.access,
+            src_queue_family_index,
+            dst_queue_family_index,
+        ) {
+            return;
+        }
 
-            trace!("recording pass [{}: {}]", pass_idx, pass.name);
+        let range = next
+            .subresource
+            .map(|subresource| subresource.unwrap_image())
+            .unwrap_or_else(|| image.info.default_view_info().into())
+            .into_vk();
+        let (src_stage_mask, _, mut image_barrier) = get_image_memory_barrier(&ImageBarrier {
+            next_accesses: from_ref(&next.access),
+            next_layout: image_access_layout(next.access),
+            previous_accesses: from_ref(&prev_access),
+            previous_layout: image_access_layout(prev_access),
+            discard_contents: false,
+            src_queue_family_index,
+            dst_queue_family_index,
+            image: **image,
+            range,
+        });
 
-            if !physical_pass.exec_descriptor_sets.is_empty() {
-                Self::write_descriptor_sets(cmd_buf, &self.graph.bindings, pass, physical_pass)?;
-            }
+        trace!(
+            "  release image {:?} {:?} -> {:?} (queue family {} -> {})",
+            image,
+            image_barrier.old_layout,
+            image_barrier.new_layout,
+            src_queue_family_index,
+            dst_queue_family_index,
+        );
 
-            Self::record_execution_barriers(
-                "  ",
-                cmd_buf,
-                &mut self.graph.bindings,
-                &mut pass.execs[0],
-                true,
+        image_barrier.dst_access_mask = vk::AccessFlags::empty();
+
+        unsafe {
+            cmd_buf.device.cmd_pipeline_barrier(
+                **cmd_buf,
+                src_stage_mask,
+                vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                from_ref(&image_barrier),
             );
+        }
+    }
 
-            let render_area = if is_graphic {
-                for exec_idx in 1..pass.execs.len() {
-                    Self::record_execution_barriers(
-                        "  ",
-                        cmd_buf,
-                        &mut self.graph.bindings,
-                        &mut pass.execs[exec_idx],
-                        false,
-                    );
-                }
+    // Binds the pipeline, dynamic state, and descriptor sets of an execution and then records the
+    // commands of its callback
+    fn record_execution_commands(
+        device: &Device,
+        cmd_buf: vk::CommandBuffer,
+        bindings: &[Binding],
+        pass: &Pass,
+        physical_pass: &PhysicalPass,
+        commands: ExecutionCommands,
+    ) {
+        let exec_idx = commands.exec_idx;
+        let exec = &pass.execs[exec_idx];
+
+        if let Some((pipeline, vk_pipeline)) = exec.pipeline.as_ref().zip(commands.pipeline) {
+            unsafe {
+                device.cmd_bind_pipeline(cmd_buf, pipeline.bind_point(), vk_pipeline);
+            }
 
-                let render_area = Self::render_area(&self.graph.bindings, pass);
-                Self::begin_render_pass(
+            if physical_pass.is_graphic() && pass.render_area.is_none() {
+                let render_area = Self::render_area(bindings, pass);
+
+                // In this case we set the viewport and scissor for the user
+                Self::set_viewport(
+                    device,
                     cmd_buf,
-                    &self.graph.bindings,
-                    pass,
-                    physical_pass,
-                    render_area,
-                )?;
-                Some(render_area)
-            } else {
-                None
-            };
+                    render_area.width as _,
+                    render_area.height as _,
+                    exec.depth_stencil
+                        .map(|depth_stencil| {
+                            let min = depth_stencil.min.0;
+                            let max = depth_stencil.max.0;
+                            min..max
+                        })
+                        .unwrap_or(0.0..1.0),
+                );
+                Self::set_scissor(device, cmd_buf, render_area.width, render_area.height);
+            }
 
-            for exec_idx in 0..pass.execs.len() {
-                let exec = &mut pass.execs[exec_idx];
+            if let ExecutionPipeline::Graphic(pipeline) = pipeline {
+                Self::set_dynamic_state(device, cmd_buf, pipeline, exec.depth_stencil);
+            }
 
-                if is_graphic && exec_idx > 0 {
-                    Self::next_subpass(cmd_buf);
-                }
+            Self::bind_descriptor_sets(device, cmd_buf, pipeline, physical_pass, exec_idx);
+        }
 
-                if let Some(pipeline) = exec.pipeline.as_mut() {
-                    Self::bind_pipeline(
-                        cmd_buf,
-                        physical_pass,
-                        exec_idx,
-                        pipeline,
-                        exec.depth_stencil,
-                    )?;
+        // Dispatches of conditional passes are made indirect so that they may be skipped
+        let conditional_dispatch = physical_pass
+            .conditional_dispatch_args
+            .as_ref()
+            .zip(pass.condition)
+            .zip(exec.pipeline.as_ref().zip(commands.pipeline))
+            .map(
+                |((args, condition), (pipeline, exec_pipeline))| ConditionalDispatch {
+                    args_buf: **args.buffer,
+                    args_idx: exec_idx as _,
+                    condition_buf: **bindings[condition.node_idx].as_driver_buffer().unwrap(),
+                    condition_offset: condition.offset,
+                    descriptor_set: *args.descriptor_set,
+                    exec_descriptor_sets: physical_pass
+                        .exec_descriptor_sets
+                        .get(&exec_idx)
+                        .map(|descriptor_sets| {
+                            descriptor_sets
+                                .iter()
+                                .map(|descriptor_set| **descriptor_set)
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    exec_layout: pipeline.layout(),
+                    exec_pipeline,
+                    layout: args.layout,
+                    pipeline: args.pipeline,
+                },
+            );
 
-                    if is_graphic && pass.render_area.is_none() {
-                        let render_area = render_area.unwrap();
-                        // In this case we set the viewport and scissor for the user
-                        Self::set_viewport(
-                            cmd_buf,
-                            render_area.width as _,
-                            render_area.height as _,
-                            exec.depth_stencil
-                                .map(|depth_stencil| {
-                                    let min = depth_stencil.min.0;
-                                    let max = depth_stencil.max.0;
-                                    min..max
-                                })
-                                .unwrap_or(0.0..1.0),
-                        );
-                        Self::set_scissor(cmd_buf, render_area.width, render_area.height);
-                    }
+        trace!("    > exec[{exec_idx}]");
 
-                    Self::bind_descriptor_sets(cmd_buf, pipeline, physical_pass, exec_idx);
-                }
+        {
+            profiling::scope!("Execute callback");
 
-                if exec_idx > 0 && !is_graphic {
-                    Self::record_execution_barriers(
-                        "    ",
-                        cmd_buf,
-                        &mut self.graph.bindings,
-                        exec,
-                        true,
-                    );
-                }
+            let exec_func = commands.exec_func.0;
+            exec_func(
+                device,
+                cmd_buf,
+                Bindings::new(bindings, exec, conditional_dispatch.as_ref()),
+            );
+        }
+    }
+
+    #[profiling::function]
+    fn record_scheduled_pass(
+        &mut self,
+        cmd_buf: &mut CommandBuffer,
+        pass_idx: usize,
+        split_barriers: &SplitBarriers,
+        secondary_cmd_bufs: &SecondaryCommandBuffers,
+    ) -> Result<(), DriverError> {
+        use std::slice::{from_mut, from_ref};
 
-                trace!("    > exec[{exec_idx}]");
+        let pass = &mut self.graph.passes[pass_idx];
 
-                {
-                    profiling::scope!("Execute callback");
+        profiling::scope!("Pass", &pass.name);
 
-                    let exec_func = exec.func.take().unwrap().0;
-                    exec_func(
-                        &cmd_buf.device,
-                        **cmd_buf,
-                        Bindings::new(&self.graph.bindings, exec),
-                    );
-                }
-            }
+        let physical_pass = &mut self.physical_passes[pass_idx];
+        let is_conditional =
+            pass.condition.is_some() && cmd_buf.device.conditional_rendering_ext.is_some();
+        let is_graphic = physical_pass.is_graphic();
+        let secondary_cmd_bufs = secondary_cmd_bufs.get(&pass_idx);
+        let contents = if secondary_cmd_bufs.is_some() {
+            vk::SubpassContents::SECONDARY_COMMAND_BUFFERS
+        } else {
+            vk::SubpassContents::INLINE
+        };
 
-            if is_graphic {
-                self.end_render_pass(cmd_buf);
-            }
+        trace!("recording pass [{}: {}]", pass_idx, pass.name);
+
+        Device::begin_debug_label(&cmd_buf.device, **cmd_buf, &pass.name);
+
+        // Secondary command buffers bind descriptor sets which were written before recording them
+        if secondary_cmd_bufs.is_none() && !physical_pass.exec_descriptor_sets.is_empty() {
+            Self::write_descriptor_sets(cmd_buf, &self.graph.bindings, pass, physical_pass)?;
         }
 
-        thread_local! {
-            static PASSES: RefCell<Vec<Pass>> = Default::default();
+        // Graphic passes record the barriers of every execution before the render pass begins
+        let barrier_execs = if is_graphic { pass.execs.len() } else { 1 };
+        Self::record_execution_barriers(
+            "  ",
+            cmd_buf,
+            &mut self.graph.bindings,
+            &mut self.barrier_state,
+            &mut pass.execs[0..barrier_execs],
+            split_barriers.waits.get(&pass_idx),
+        );
+
+        if is_conditional {
+            Self::begin_conditional_rendering(cmd_buf, &self.graph.bindings, pass);
         }
 
-        PASSES.with_borrow_mut(|passes| {
-            debug_assert!(passes.is_empty());
+        if is_graphic {
+            let render_area = Self::render_area(&self.graph.bindings, pass);
+            Self::begin_render_pass(
+                cmd_buf,
+                &self.graph.bindings,
+                pass,
+                physical_pass,
+                render_area,
+                contents,
+            )?;
+        }
 
-            // We have to keep the bindings and pipelines alive until the gpu is done
-            schedule.passes.sort_unstable();
-            while let Some(schedule_idx) = schedule.passes.pop() {
-                debug_assert!(!self.graph.passes.is_empty());
+        for exec_idx in 0..pass.execs.len() {
+            if is_graphic && exec_idx > 0 {
+                Self::next_subpass(cmd_buf, contents);
+            }
 
-                while let Some(pass) = self.graph.passes.pop() {
-                    let pass_idx = self.graph.passes.len();
+            if exec_idx > 0 && !is_graphic {
+                Self::record_execution_barriers(
+                    "    ",
+                    cmd_buf,
+                    &mut self.graph.bindings,
+                    &mut self.barrier_state,
+                    from_mut(&mut pass.execs[exec_idx]),
+                    None,
+                );
+            }
 
-                    if pass_idx == schedule_idx {
-                        // This was a scheduled pass - store it!
-                        CommandBuffer::push_fenced_drop(
-                            cmd_buf,
-                            (pass, self.physical_passes.pop().unwrap()),
-                        );
-                        break;
-                    } else {
-                        debug_assert!(pass_idx > schedule_idx);
+            if let Some(secondary_cmd_bufs) = secondary_cmd_bufs {
+                trace!("    execute secondary command buffer [{exec_idx}]");
 
-                        passes.push(pass);
-                    }
+                unsafe {
+                    cmd_buf
+                        .device
+                        .cmd_execute_commands(**cmd_buf, from_ref(&secondary_cmd_bufs[exec_idx]));
                 }
+
+                continue;
             }
 
-            debug_assert!(self.physical_passes.is_empty());
+            let exec = &mut pass.execs[exec_idx];
+            let pipeline = exec
+                .pipeline
+                .as_mut()
+                .map(|pipeline| {
+                    Self::exec_pipeline(physical_pass, exec_idx, pipeline, exec.depth_stencil)
+                })
+                .transpose()?;
+            let commands = ExecutionCommands {
+                exec_func: exec.func.take().unwrap(),
+                exec_idx,
+                pass_idx,
+                pipeline,
+            };
+            Self::record_execution_commands(
+                &cmd_buf.device,
+                **cmd_buf,
+                &self.graph.bindings,
+                pass,
+                physical_pass,
+                commands,
+            );
+        }
 
-            // Put the other passes back for future resolves
-            self.graph.passes.extend(passes.drain(..).rev());
-        });
+        if is_graphic {
+            Self::end_render_pass(cmd_buf, physical_pass);
+        }
 
-        log::trace!("Recorded passes");
+        if is_conditional {
+            Self::end_conditional_rendering(cmd_buf);
+        }
+
+        for (event, prev_accesses) in split_barriers.signals.get(&pass_idx).into_iter().flatten() {
+            trace!("  signal {event:?} {prev_accesses:?}");
+
+            set_event(&cmd_buf.device, **cmd_buf, *event, prev_accesses);
+        }
+
+        Device::end_debug_label(&cmd_buf.device, **cmd_buf);
 
         Ok(())
     }
 
-    /// Records any pending render graph passes that have not been previously scheduled.
     #[profiling::function]
-    pub fn record_unscheduled_passes<P>(
+    fn record_scheduled_passes<P>(
         &mut self,
         pool: &mut P,
         cmd_buf: &mut CommandBuffer,
+        schedule: &mut Schedule,
+        end_pass_idx: usize,
     ) -> Result<(), DriverError>
     where
         P: Pool<DescriptorPoolInfo, DescriptorPool> + Pool<RenderPassInfo, RenderPass> + ?Sized,
     {
-        if self.graph.passes.is_empty() {
+        if schedule.passes.is_empty() {
             return Ok(());
         }
 
-        thread_local! {
-            static SCHEDULE: RefCell<Schedule> = Default::default();
+        self.wait_scheduled_dependencies(schedule)?;
+        self.prepare_scheduled_passes(pool, cmd_buf, None, schedule, end_pass_idx)?;
+
+        let split_barriers = self.split_barriers(cmd_buf, schedule.passes.iter().copied())?;
+        let secondary_cmd_bufs =
+            self.record_secondary_cmd_bufs(cmd_buf, schedule.passes.iter().copied())?;
+
+        for pass_idx in schedule.passes.iter().copied() {
+            self.record_scheduled_pass(cmd_buf, pass_idx, &split_barriers, &secondary_cmd_bufs)?;
         }
 
-        SCHEDULE.with_borrow_mut(|schedule| {
-            schedule
-                .access_cache
-                .update(&self.graph, self.graph.passes.len());
-            schedule.passes.clear();
-            schedule.passes.extend(0..self.graph.passes.len());
+        self.retire_scheduled_passes(cmd_buf, schedule);
 
-            self.record_scheduled_passes(pool, cmd_buf, schedule, self.graph.passes.len())
-        })
+        log::trace!("Recorded passes");
+
+        Ok(())
     }
 
+    // Records the executions of the given passes into secondary command buffers using the
+    // recording threads, returning nothing if parallel recording is not enabled. Descriptor sets
+    // are written and graphic pipelines are created on this thread beforehand.
     #[profiling::function]
-    fn render_area(bindings: &[Binding], pass: &Pass) -> Area {
-        pass.render_area.unwrap_or_else(|| {
-            // set_render_area was not specified so we're going to guess using the minimum common
-            // attachment extents
-            let first_exec = pass.execs.first().unwrap();
-
+    fn record_secondary_cmd_bufs(
+        &mut self,
+        cmd_buf: &mut CommandBuffer,
+        passes: impl Iterator<Item = usize>,
+    ) -> Result<SecondaryCommandBuffers, DriverError> {
+        use std::{panic::resume_unwind, thread::scope};
+
+        let mut secondary_cmd_bufs = SecondaryCommandBuffers::default();
+
+        if self.recording_threads < 2 {
+            return Ok(secondary_cmd_bufs);
+        }
+
+        let mut commands = vec![];
+        for pass_idx in passes {
+            let pass = &mut self.graph.passes[pass_idx];
+            let physical_pass = &mut self.physical_passes[pass_idx];
+
+            // Conditional passes are recorded inline unless the predicate may be inherited
+            if pass.condition.is_some()
+                && cmd_buf.device.conditional_rendering_ext.is_some()
+                && !cmd_buf
+                    .device
+                    .physical_device
+                    .conditional_rendering_features
+                    .inherited_conditional_rendering
+            {
+                continue;
+            }
+
+            if !physical_pass.exec_descriptor_sets.is_empty() {
+                Self::write_descriptor_sets(cmd_buf, &self.graph.bindings, pass, physical_pass)?;
+            }
+
+            for (exec_idx, exec) in pass.execs.iter_mut().enumerate() {
+                let pipeline = exec
+                    .pipeline
+                    .as_mut()
+                    .map(|pipeline| {
+                        Self::exec_pipeline(physical_pass, exec_idx, pipeline, exec.depth_stencil)
+                    })
+                    .transpose()?;
+                commands.push(ExecutionCommands {
+                    exec_func: exec.func.take().unwrap(),
+                    exec_idx,
+                    pass_idx,
+                    pipeline,
+                });
+            }
+        }
+
+        if commands.is_empty() {
+            return Ok(secondary_cmd_bufs);
+        }
+
+        // Each thread records a contiguous range of executions using its own command pool
+        let chunk_len = commands
+            .len()
+            .div_ceil(self.recording_threads.min(commands.len()));
+        let thread_count = commands.len().div_ceil(chunk_len);
+
+        trace!(
+            "recording {} secondary command buffers on {thread_count} threads",
+            commands.len()
+        );
+
+        let device = Arc::clone(&cmd_buf.device);
+        let device = device.as_ref();
+        let secondary_pools = CommandBuffer::secondary_pools(cmd_buf, thread_count)?;
+        let bindings = self.graph.bindings.as_slice();
+        let passes = self.graph.passes.as_slice();
+        let physical_passes = self.physical_passes.as_slice();
+        let mut commands = commands.into_iter();
+
+        let recorded_cmd_bufs = scope(|scope| {
+            let threads = secondary_pools
+                .iter_mut()
+                .map(|secondary_pool| {
+                    let commands = commands.by_ref().take(chunk_len).collect::<Vec<_>>();
+
+                    scope.spawn(move || {
+                        profiling::scope!("Record secondary command buffers");
+
+                        commands
+                            .into_iter()
+                            .map(|commands| {
+                                let pass_idx = commands.pass_idx;
+
+                                Self::record_secondary_cmd_buf(
+                                    device,
+                                    secondary_pool,
+                                    bindings,
+                                    &passes[pass_idx],
+                                    &physical_passes[pass_idx],
+                                    commands,
+                                )
+                                .map(|secondary_cmd_buf| (pass_idx, secondary_cmd_buf))
+                            })
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            threads
+                .into_iter()
+                .map(|thread| thread.join().unwrap_or_else(|err| resume_unwind(err)))
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+
+        // Each pass receives the command buffers of its executions in order
+        for (pass_idx, secondary_cmd_buf) in recorded_cmd_bufs.into_iter().flatten() {
+            secondary_cmd_bufs
+                .entry(pass_idx)
+                .or_default()
+                .push(secondary_cmd_buf);
+        }
+
+        Ok(secondary_cmd_bufs)
+    }
+
+    // Records one execution into a secondary command buffer which continues the render pass of
+    // graphic passes
+    fn record_secondary_cmd_buf(
+        device: &Device,
+        secondary_pool: &mut SecondaryCommandPool,
+        bindings: &[Binding],
+        pass: &Pass,
+        physical_pass: &PhysicalPass,
+        commands: ExecutionCommands,
+    ) -> Result<vk::CommandBuffer, DriverError> {
+        let cmd_buf = SecondaryCommandPool::next_cmd_buf(secondary_pool, device)?;
+        let mut flags = vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT;
+        let mut inheritance_info = vk::CommandBufferInheritanceInfo::builder();
+        let mut inheritance_rendering_info = physical_pass.rendering.as_ref().map(|rendering| {
+            vk::CommandBufferInheritanceRenderingInfo::builder()
+                .color_attachment_formats(&rendering.formats.color_formats)
+                .depth_attachment_format(rendering.formats.depth_format)
+                .stencil_attachment_format(rendering.formats.stencil_format)
+                .view_mask(rendering.formats.view_mask)
+                .rasterization_samples(rendering.samples)
+        });
+        let mut inheritance_conditional_rendering_info =
+            (pass.condition.is_some() && device.conditional_rendering_ext.is_some()).then(|| {
+                vk::CommandBufferInheritanceConditionalRenderingInfoEXT::builder()
+                    .conditional_rendering_enable(true)
+            });
+
+        if let Some(render_pass) = physical_pass.render_pass.as_ref() {
+            flags |= vk::CommandBufferUsageFlags::RENDER_PASS_CONTINUE;
+            inheritance_info = inheritance_info
+                .render_pass(***render_pass)
+                .subpass(commands.exec_idx as _);
+        } else if let Some(inheritance_rendering_info) = inheritance_rendering_info.as_mut() {
+            flags |= vk::CommandBufferUsageFlags::RENDER_PASS_CONTINUE;
+            inheritance_info = inheritance_info.push_next(inheritance_rendering_info);
+        }
+
+        if let Some(inheritance_conditional_rendering_info) =
+            inheritance_conditional_rendering_info.as_mut()
+        {
+            inheritance_info = inheritance_info.push_next(inheritance_conditional_rendering_info);
+        }
+
+        unsafe {
+            device
+                .begin_command_buffer(
+                    cmd_buf,
+                    &vk::CommandBufferBeginInfo::builder()
+                        .flags(flags)
+                        .inheritance_info(&inheritance_info),
+                )
+                .map_err(|_| DriverError::OutOfMemory)?;
+        }
+
+        Self::record_execution_commands(device, cmd_buf, bindings, pass, physical_pass, commands);
+
+        unsafe {
+            device
+                .end_command_buffer(cmd_buf)
+                .map_err(|_| DriverError::OutOfMemory)?;
+        }
+
+        Ok(cmd_buf)
+    }
+
+    /// Records any pending render graph passes that have not been previously scheduled.
+    #[profiling::function]
+    pub fn record_unscheduled_passes<P>(
+        &mut self,
+        pool: &mut P,
+        cmd_buf: &mut CommandBuffer,
+    ) -> Result<(), DriverError>
+    where
+        P: Pool<DescriptorPoolInfo, DescriptorPool> + Pool<RenderPassInfo, RenderPass> + ?Sized,
+    {
+        if self.graph.passes.is_empty() {
+            return Ok(());
+        }
+
+        thread_local! {
+            static SCHEDULE: RefCell<Schedule> = Default::default();
+        }
+
+        SCHEDULE.with_borrow_mut(|schedule| {
+            schedule
+                .access_cache
+                .update(&self.graph, self.graph.passes.len());
+            schedule.passes.clear();
+            schedule.passes.extend(0..self.graph.passes.len());
+
+            self.record_scheduled_passes(pool, cmd_buf, schedule, self.graph.passes.len())
+        })
+    }
+
+    #[profiling::function]
+    fn render_area(bindings: &[Binding], pass: &Pass) -> Area {
+        pass.render_area.unwrap_or_else(|| {
+            // set_render_area was not specified so we're going to guess using the minimum common
+            // attachment extents
+            let first_exec = pass.execs.first().unwrap();
+
             // We must be able to find the render area because render passes require at least one
             // image to be attached
             let (mut width, mut height) = (u32::MAX, u32::MAX);
@@ -2461,8 +4232,7 @@ impl Resolver {
     }
 
     #[profiling::function]
-    //fn reorder_scheduled_passes(&mut self, schedule: &mut Schedule) {
-    fn reorder_scheduled_passes(&mut self, schedule: &mut Schedule, end_pass_idx: usize) {
+    fn reorder_scheduled_passes(schedule: &mut Schedule, end_pass_idx: usize) {
         // It must be a party
         if schedule.passes.len() < 3 {
             return;
@@ -2519,6 +4289,257 @@ impl Resolver {
         });
     }
 
+    // Mirrors record_scheduled_passes without modifying the graph or any of its resources
+    #[profiling::function]
+    fn report_scheduled_passes(
+        &self,
+        mut schedule: Schedule,
+        end_pass_idx: usize,
+    ) -> ScheduleReport {
+        Self::reorder_scheduled_passes(&mut schedule, end_pass_idx);
+
+        // Group passes the same way merge_scheduled_passes does
+        let mut physical_passes = vec![];
+        let mut idx = 0;
+        while idx < schedule.passes.len() {
+            let pass = &self.graph.passes[schedule.passes[idx]];
+            let mut end = idx + 1;
+            while end < schedule.passes.len()
+                && Self::allow_merge_passes(pass, &self.graph.passes[schedule.passes[end]])
+            {
+                end += 1;
+            }
+
+            physical_passes.push(schedule.passes[idx..end].to_vec());
+            idx = end;
+        }
+
+        // Follow the access state of each node and elide barriers the same way
+        // record_execution_barriers does, as if recorded into a new command buffer of a queue which
+        // supports events
+        let mut node_accesses = self
+            .graph
+            .bindings
+            .iter()
+            .map(Binding::peek_access)
+            .collect::<Vec<_>>();
+        let mut barrier_state = BarrierState::default();
+        let mut aliases = self.barrier_state.aliases.clone();
+        let mut split_waits = HashMap::<usize, Vec<NodeIndex>>::new();
+        for ((_, pass_idx), nodes) in
+            Self::split_barrier_nodes(physical_passes.iter().map(|physical_pass| {
+                (
+                    physical_pass[0],
+                    physical_pass
+                        .iter()
+                        .flat_map(|pass_idx| &self.graph.passes[*pass_idx].execs),
+                )
+            }))
+        {
+            split_waits
+                .entry(pass_idx)
+                .or_default()
+                .extend(nodes.into_iter().map(|(node_idx, _)| node_idx));
+        }
+
+        let mut barriers = vec![];
+
+        for physical_pass in &physical_passes {
+            let is_graphic = self.graph.passes[physical_pass[0]]
+                .execs
+                .first()
+                .and_then(|exec| exec.pipeline.as_ref())
+                .map(|pipeline| pipeline.is_graphic())
+                .unwrap_or_default();
+            let split_waits = split_waits.get(&physical_pass[0]);
+            let execs = physical_pass.iter().flat_map(|&pass_idx| {
+                self.graph.passes[pass_idx]
+                    .execs
+                    .iter()
+                    .enumerate()
+                    .map(move |(exec_idx, exec)| (pass_idx, exec_idx, exec))
+            });
+
+            for (merged_exec_idx, (pass_idx, exec_idx, exec)) in execs.enumerate() {
+                // Graphic passes record the barriers of every execution before the render pass
+                // begins, where framebuffer accesses of later executions are handled by subpass
+                // dependencies
+                let is_first_exec = merged_exec_idx == 0;
+                let record_framebuffer_access = is_first_exec || !is_graphic;
+                let is_split_exec = is_first_exec || is_graphic;
+
+                for (node_idx, [early, late]) in Self::sorted_accesses(exec) {
+                    let next_access = early.access;
+                    let prev_access = replace(&mut node_accesses[node_idx], late.access);
+                    let is_image = self.graph.bindings[node_idx].image_info().is_some();
+                    let is_alias = aliases.remove(&node_idx).is_some();
+                    let is_required = is_alias
+                        || barrier_state.is_required(
+                            node_idx,
+                            early.subresource,
+                            is_image,
+                            prev_access,
+                            next_access,
+                        );
+                    let barrier = |kind, subresource| BarrierReport {
+                        exec_idx,
+                        kind,
+                        next_access,
+                        node_idx,
+                        pass_idx,
+                        prev_access,
+                        subresource,
+                    };
+
+                    if is_image
+                        && early.subresource.is_some()
+                        && !record_framebuffer_access
+                        && is_framebuffer_access(next_access)
+                    {
+                        barrier_state.access(node_idx, early.subresource, early, late, false);
+
+                        // The render pass transitions the image but shared memory must be available
+                        if is_alias {
+                            barriers.push(barrier(BarrierKind::Alias, None));
+                        }
+
+                        continue;
+                    }
+
+                    barrier_state.access(node_idx, early.subresource, early, late, true);
+
+                    if !is_required {
+                        continue;
+                    }
+
+                    let kind = if is_alias {
+                        BarrierKind::Alias
+                    } else if is_split_exec
+                        && split_waits.is_some_and(|nodes| nodes.contains(&node_idx))
+                    {
+                        BarrierKind::SplitWait
+                    } else {
+                        BarrierKind::Pipeline
+                    };
+
+                    barriers.push(barrier(kind, early.subresource));
+                }
+            }
+        }
+
+        let nodes = self
+            .graph
+            .bindings
+            .iter()
+            .map(|binding| {
+                let (kind, name) = match binding {
+                    Binding::AccelerationStructure(..) => ("AccelerationStructure", None),
+                    Binding::AccelerationStructureLease(..) => ("AccelerationStructureLease", None),
+                    Binding::Buffer(buffer, _) => ("Buffer", buffer.name.clone()),
+                    Binding::BufferLease(buffer, _) => ("BufferLease", buffer.name.clone()),
+                    Binding::Image(image, _) => ("Image", image.name.clone()),
+                    Binding::ImageLease(image, _) => ("ImageLease", image.name.clone()),
+                    Binding::QueryPool(..) => ("QueryPool", None),
+                    Binding::QueryPoolLease(..) => ("QueryPoolLease", None),
+                    Binding::SwapchainImage(image, _) => ("SwapchainImage", image.name.clone()),
+                    Binding::TransientBuffer(..) => ("TransientBuffer", None),
+                    Binding::TransientImage(..) => ("TransientImage", None),
+                };
+
+                NodeReport { kind, name }
+            })
+            .collect();
+        let passes = self
+            .graph
+            .passes
+            .iter()
+            .map(|pass| PassReport {
+                accesses: pass
+                    .execs
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(exec_idx, exec)| {
+                        Self::sorted_accesses(exec).map(move |(node_idx, [early, late])| {
+                            NodeAccessReport {
+                                early: early.access,
+                                exec_idx,
+                                late: late.access,
+                                node_idx,
+                                subresource: early.subresource,
+                            }
+                        })
+                    })
+                    .collect(),
+                name: pass.name.clone(),
+            })
+            .collect();
+
+        ScheduleReport {
+            barriers,
+            nodes,
+            passes,
+            physical_passes,
+            schedule: schedule.passes,
+        }
+    }
+
+    // Returns `true` if an image must be released by the queue family of its previous access and
+    // acquired by the queue family of its next access: concurrently shared resources, and exclusively
+    // owned images whose contents are discarded, may be used by any queue family without a transfer
+    fn requires_queue_family_transfer(
+        binding: &Binding,
+        prev_access: AccessType,
+        next_access: AccessType,
+        src_queue_family_index: u32,
+        dst_queue_family_index: u32,
+    ) -> bool {
+        matches!(binding, Binding::SwapchainImage(..))
+            && src_queue_family_index != dst_queue_family_index
+            && prev_access != AccessType::Nothing
+            && !is_write_access(next_access)
+    }
+
+    // Moves the scheduled passes into the command buffer so that the resources they reference live
+    // until the command buffer has executed
+    #[profiling::function]
+    fn retire_scheduled_passes(&mut self, cmd_buf: &mut CommandBuffer, schedule: &mut Schedule) {
+        thread_local! {
+            static PASSES: RefCell<Vec<Pass>> = Default::default();
+        }
+
+        PASSES.with_borrow_mut(|passes| {
+            debug_assert!(passes.is_empty());
+
+            // We have to keep the bindings and pipelines alive until the gpu is done
+            schedule.passes.sort_unstable();
+            while let Some(schedule_idx) = schedule.passes.pop() {
+                debug_assert!(!self.graph.passes.is_empty());
+
+                while let Some(pass) = self.graph.passes.pop() {
+                    let pass_idx = self.graph.passes.len();
+
+                    if pass_idx == schedule_idx {
+                        // This was a scheduled pass - store it!
+                        CommandBuffer::push_fenced_drop(
+                            cmd_buf,
+                            (pass, self.physical_passes.pop().unwrap()),
+                        );
+                        break;
+                    } else {
+                        debug_assert!(pass_idx > schedule_idx);
+
+                        passes.push(pass);
+                    }
+                }
+            }
+
+            debug_assert!(self.physical_passes.is_empty());
+
+            // Put the other passes back for future resolves
+            self.graph.passes.extend(passes.drain(..).rev());
+        });
+    }
+
     /// Returns a vec of pass indexes that are required to be executed, in order, for the given
     /// node.
     #[profiling::function]
@@ -2661,12 +4682,151 @@ impl Resolver {
         });
     }
 
-    fn set_scissor(cmd_buf: &CommandBuffer, width: u32, height: u32) {
+    /// Returns a report of the work [`Self::submit`] would record, without recording or submitting
+    /// anything.
+    ///
+    /// The report contains the pass schedule after re-ordering, the passes which would be merged
+    /// into subpasses and the barriers which would be recorded given the current access state of
+    /// each node. Redundant barriers are elided and barriers are split into event waits as if the
+    /// passes were recorded into a new command buffer of a queue which supports graphics or compute
+    /// commands. Transient nodes share memory once their passes are recorded, so alias barriers are
+    /// only reported for transient nodes created by an earlier call to [`Self::record_node`].
+    ///
+    /// See [`ScheduleReport::to_dot`] and [`ScheduleReport::to_json`].
+    #[profiling::function]
+    pub fn schedule_report(&self) -> ScheduleReport {
+        let end_pass_idx = self.graph.passes.len();
+        let mut schedule = Schedule::default();
+        schedule.access_cache.update(&self.graph, end_pass_idx);
+        schedule.passes.extend(0..end_pass_idx);
+
+        self.report_scheduled_passes(schedule, end_pass_idx)
+    }
+
+    /// Enables recording the passes of this instance into secondary command buffers using up to
+    /// `thread_count` threads.
+    ///
+    /// Each execution of a pass is recorded into its own secondary command buffer; the primary
+    /// command buffer records barriers, begins render passes, and executes the secondary command
+    /// buffers in order. This allows graphs containing many passes, or passes with many
+    /// executions, to spread the CPU cost of recording across cores. Execution callbacks may run on
+    /// other threads and must not begin, end, or change subpasses of render passes.
+    ///
+    /// A `thread_count` of zero or one records every pass on the calling thread, which is the
+    /// default.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use ash::vk;
+    /// # use screen_13::driver::DriverError;
+    /// # use screen_13::driver::device::{Device, DeviceInfo};
+    /// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+    /// # use screen_13::graph::RenderGraph;
+    /// # use screen_13::pool::lazy::LazyPool;
+    /// # fn main() -> Result<(), DriverError> {
+    /// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+    /// # let mut pool = LazyPool::new(&device);
+    /// # let info = BufferInfo::device_mem(1024, vk::BufferUsageFlags::TRANSFER_DST);
+    /// # let buf = Buffer::create(&device, info)?;
+    /// let mut my_graph = RenderGraph::new();
+    /// let buf = my_graph.bind_node(buf);
+    ///
+    /// for _ in 0..64 {
+    ///     my_graph.fill_buffer(buf, 0);
+    /// }
+    ///
+    /// let mut resolver = my_graph.resolve();
+    /// resolver.set_recording_threads(4);
+    /// resolver.submit(&mut pool, 0, 0)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn set_recording_threads(&mut self, thread_count: usize) -> &mut Self {
+        self.recording_threads = thread_count;
+        self
+    }
+
+    /// Retains the schedule and physical passes of this instance in `template`, or reuses them if
+    /// `template` was compiled from a graph of the same shape.
+    ///
+    /// See [`GraphTemplate`].
+    pub fn set_template(&mut self, template: &GraphTemplate) -> &mut Self {
+        self.template = Some(template.clone());
+        self
+    }
+
+    // Sets the dynamic state of a graphic pipeline to the values given by its information and the
+    // depth/stencil mode of the execution, which the execution may then change
+    fn set_dynamic_state(
+        device: &Device,
+        cmd_buf: vk::CommandBuffer,
+        pipeline: &GraphicPipeline,
+        depth_stencil: Option<DepthStencilMode>,
+    ) {
+        let depth_stencil = depth_stencil.unwrap_or(DepthStencilMode::IGNORE);
+
+        unsafe {
+            device.cmd_set_blend_constants(cmd_buf, &[0.0; 4]);
+            device.cmd_set_depth_bias(cmd_buf, 0.0, 0.0, 0.0);
+            device.cmd_set_depth_bounds(cmd_buf, *depth_stencil.min, *depth_stencil.max);
+            device.cmd_set_line_width(cmd_buf, 1.0);
+
+            for (face_mask, stencil) in [
+                (vk::StencilFaceFlags::FRONT, depth_stencil.front),
+                (vk::StencilFaceFlags::BACK, depth_stencil.back),
+            ] {
+                device.cmd_set_stencil_compare_mask(cmd_buf, face_mask, stencil.compare_mask);
+                device.cmd_set_stencil_reference(cmd_buf, face_mask, stencil.reference);
+                device.cmd_set_stencil_write_mask(cmd_buf, face_mask, stencil.write_mask);
+            }
+        }
+
+        let Some(extended_dynamic_state_ext) = device.extended_dynamic_state_ext.as_ref() else {
+            return;
+        };
+
+        unsafe {
+            extended_dynamic_state_ext.cmd_set_cull_mode(cmd_buf, pipeline.info.cull_mode);
+            extended_dynamic_state_ext.cmd_set_front_face(cmd_buf, pipeline.info.front_face);
+            extended_dynamic_state_ext.cmd_set_depth_test_enable(cmd_buf, depth_stencil.depth_test);
+            extended_dynamic_state_ext
+                .cmd_set_depth_write_enable(cmd_buf, depth_stencil.depth_write);
+            extended_dynamic_state_ext.cmd_set_depth_compare_op(cmd_buf, depth_stencil.compare_op);
+            extended_dynamic_state_ext
+                .cmd_set_depth_bounds_test_enable(cmd_buf, depth_stencil.bounds_test);
+            extended_dynamic_state_ext
+                .cmd_set_stencil_test_enable(cmd_buf, depth_stencil.stencil_test);
+
+            for (face_mask, stencil) in [
+                (vk::StencilFaceFlags::FRONT, depth_stencil.front),
+                (vk::StencilFaceFlags::BACK, depth_stencil.back),
+            ] {
+                extended_dynamic_state_ext.cmd_set_stencil_op(
+                    cmd_buf,
+                    face_mask,
+                    stencil.fail_op,
+                    stencil.pass_op,
+                    stencil.depth_fail_op,
+                    stencil.compare_op,
+                );
+            }
+
+            if GraphicPipeline::has_vertex_input(pipeline) {
+                extended_dynamic_state_ext
+                    .cmd_set_primitive_topology(cmd_buf, pipeline.info.topology);
+            }
+        }
+    }
+
+    fn set_scissor(device: &Device, cmd_buf: vk::CommandBuffer, width: u32, height: u32) {
         use std::slice::from_ref;
 
         unsafe {
-            cmd_buf.device.cmd_set_scissor(
-                **cmd_buf,
+            device.cmd_set_scissor(
+                cmd_buf,
                 0,
                 from_ref(&vk::Rect2D {
                     extent: vk::Extent2D { width, height },
@@ -2676,12 +4836,18 @@ impl Resolver {
         }
     }
 
-    fn set_viewport(cmd_buf: &CommandBuffer, width: f32, height: f32, depth: Range<f32>) {
+    fn set_viewport(
+        device: &Device,
+        cmd_buf: vk::CommandBuffer,
+        width: f32,
+        height: f32,
+        depth: Range<f32>,
+    ) {
         use std::slice::from_ref;
 
         unsafe {
-            cmd_buf.device.cmd_set_viewport(
-                **cmd_buf,
+            device.cmd_set_viewport(
+                cmd_buf,
                 0,
                 from_ref(&vk::Viewport {
                     x: 0.0,
@@ -2695,13 +4861,355 @@ impl Resolver {
         }
     }
 
+    // Node accesses of an execution in a stable order
+    fn sorted_accesses(
+        exec: &Execution,
+    ) -> impl Iterator<Item = (NodeIndex, [SubresourceAccess; 2])> {
+        let mut accesses = exec
+            .accesses
+            .iter()
+            .map(|(&node_idx, &accesses)| (node_idx, accesses))
+            .collect::<Vec<_>>();
+        accesses.sort_unstable_by_key(|(node_idx, _)| *node_idx);
+
+        accesses.into_iter()
+    }
+
+    // Returns the nodes, along with their previous access, whose barriers may be split between each
+    // pair of the given passes which access them
+    fn split_barrier_nodes<'a, E>(
+        passes: impl IntoIterator<Item = (usize, E)>,
+    ) -> BTreeMap<(usize, usize), Vec<(NodeIndex, AccessType)>>
+    where
+        E: IntoIterator<Item = &'a Execution>,
+    {
+        let mut prev_accesses = HashMap::<NodeIndex, (usize, usize, AccessType)>::new();
+        let mut splits = BTreeMap::<(usize, usize), Vec<(NodeIndex, AccessType)>>::new();
+
+        for (pass_position, (pass_idx, execs)) in passes.into_iter().enumerate() {
+            for (exec_idx, exec) in execs.into_iter().enumerate() {
+                for (node_idx, [_, late]) in Self::sorted_accesses(exec) {
+                    if let Some((prev_pass_position, prev_pass_idx, prev_access)) =
+                        prev_accesses.insert(node_idx, (pass_position, pass_idx, late.access))
+                    {
+                        // Only the barriers of the first execution are recorded before the pass
+                        if exec_idx == 0 && prev_pass_position + 1 < pass_position {
+                            splits
+                                .entry((prev_pass_idx, pass_idx))
+                                .or_default()
+                                .push((node_idx, prev_access));
+                        }
+                    }
+                }
+            }
+        }
+
+        splits
+    }
+
+    /// Finds the nodes which are accessed again by a pass recorded at least two passes after the
+    /// previous access, so that the pipeline barrier between the accesses may be split into an event
+    /// signalled after the earlier pass and waited on before the later pass. The passes recorded in
+    /// between do not access the node and may overlap with the earlier pass.
+    ///
+    /// Events may only be set and waited on by queues which support graphics or compute commands, so
+    /// command buffers of other queue families record plain pipeline barriers instead.
+    #[profiling::function]
+    fn split_barriers(
+        &self,
+        cmd_buf: &mut CommandBuffer,
+        passes: impl IntoIterator<Item = usize>,
+    ) -> Result<SplitBarriers, DriverError> {
+        let mut split_barriers = SplitBarriers::default();
+
+        if !cmd_buf.device.physical_device.queue_families[cmd_buf.info.queue_family_index as usize]
+            .queue_flags
+            .intersects(vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE)
+        {
+            return Ok(split_barriers);
+        }
+
+        let splits = Self::split_barrier_nodes(
+            passes
+                .into_iter()
+                .map(|pass_idx| (pass_idx, &self.graph.passes[pass_idx].execs)),
+        );
+
+        // Each pair of passes uses one event so that the stages waited on before the later pass
+        // match the stages signalled after the earlier pass
+        for ((prev_pass_idx, pass_idx), nodes) in splits {
+            let event = CommandBuffer::next_event(cmd_buf)?;
+
+            trace!("split barrier {prev_pass_idx} -> {pass_idx} using {event:?}");
+
+            let mut prev_accesses = vec![];
+            for (_, prev_access) in nodes.iter().copied() {
+                // HACK: It would be nice if AccessType was PartialOrd..
+                if !prev_accesses.contains(&prev_access) {
+                    prev_accesses.push(prev_access);
+                }
+            }
+
+            split_barriers
+                .signals
+                .entry(prev_pass_idx)
+                .or_default()
+                .push((event, prev_accesses));
+
+            let wait = split_barriers.waits.entry(pass_idx).or_default();
+            wait.events.push(event);
+            wait.nodes
+                .extend(nodes.into_iter().map(|(node_idx, _)| node_idx));
+        }
+
+        Ok(split_barriers)
+    }
+
     /// Submits the remaining commands stored in this instance.
+    ///
+    /// Passes which have been placed on another kind of queue using [`PassRef::set_queue`] are
+    /// recorded into separate command buffers and submitted to queues of the matching family;
+    /// semaphores order those submissions wherever passes access common nodes. The returned
+    /// command buffer executes last on the given queue, so it may be used to track the completion
+    /// of every pass.
+    ///
+    /// [`PassRef::set_queue`]: super::pass_ref::PassRef::set_queue
     #[profiling::function]
     pub fn submit<P>(
-        mut self,
+        self,
+        pool: &mut P,
+        queue_family_index: usize,
+        queue_index: usize,
+    ) -> Result<Lease<CommandBuffer>, DriverError>
+    where
+        P: Pool<CommandBufferInfo, CommandBuffer>
+            + Pool<DescriptorPoolInfo, DescriptorPool>
+            + Pool<RenderPassInfo, RenderPass>,
+    {
+        self.submit_with_semaphores(pool, queue_family_index, queue_index, &[], &[])
+    }
+
+    /// Submits the remaining commands stored in this instance, waiting on and signaling the given
+    /// timeline semaphore values.
+    ///
+    /// No pass begins executing until every semaphore in `wait_semaphores` has reached the paired
+    /// value. Every semaphore in `signal_semaphores` is set to the paired value once all passes have
+    /// executed.
+    ///
+    /// See [`Self::submit`].
+    #[profiling::function]
+    pub fn submit_with_semaphores<P>(
+        self,
         pool: &mut P,
         queue_family_index: usize,
         queue_index: usize,
+        wait_semaphores: &[(&TimelineSemaphore, u64)],
+        signal_semaphores: &[(&TimelineSemaphore, u64)],
+    ) -> Result<Lease<CommandBuffer>, DriverError>
+    where
+        P: Pool<CommandBufferInfo, CommandBuffer>
+            + Pool<DescriptorPoolInfo, DescriptorPool>
+            + Pool<RenderPassInfo, RenderPass>,
+    {
+        trace!("submit");
+
+        let cmd_buf = pool.lease(CommandBufferInfo::new(queue_family_index as _))?;
+
+        self.submit_queues(
+            pool,
+            cmd_buf,
+            (queue_family_index, queue_index),
+            wait_semaphores,
+            signal_semaphores,
+        )
+    }
+
+    /// Submits the remaining commands stored in this instance and returns a token which represents
+    /// the submitted work.
+    ///
+    /// Later render graphs may wait on the GPU for the submitted work before accessing a resource
+    /// by binding it with [`RenderGraph::bind_node_after`], even if those graphs are submitted to a
+    /// different queue or from a different thread.
+    ///
+    /// See [`Self::submit`].
+    #[profiling::function]
+    pub fn submit_with_token<P>(
+        self,
+        pool: &mut P,
+        queue_family_index: usize,
+        queue_index: usize,
+    ) -> Result<(Lease<CommandBuffer>, SubmissionToken), DriverError>
+    where
+        P: Pool<CommandBufferInfo, CommandBuffer>
+            + Pool<DescriptorPoolInfo, DescriptorPool>
+            + Pool<RenderPassInfo, RenderPass>,
+    {
+        trace!("submit");
+
+        let cmd_buf = pool.lease(CommandBufferInfo::new(queue_family_index as _))?;
+        let token = SubmissionToken {
+            semaphore: Arc::new(TimelineSemaphore::create(&cmd_buf.device, 0)?),
+            value: 1,
+        };
+        let mut cmd_buf = self.submit_queues(
+            pool,
+            cmd_buf,
+            (queue_family_index, queue_index),
+            &[],
+            &[(&token.semaphore, token.value)],
+        )?;
+
+        CommandBuffer::push_fenced_drop(&mut cmd_buf, token.clone());
+
+        Ok((cmd_buf, token))
+    }
+
+    // Records and submits one batch of contiguous passes which execute on the same queue
+    #[profiling::function]
+    fn submit_queue_batch(
+        &mut self,
+        cmd_buf: &mut CommandBuffer,
+        batch: &QueueBatch,
+        query_pool: Option<&QueryPool>,
+    ) -> Result<(), DriverError> {
+        use std::slice::from_ref;
+
+        let (queue_family_index, queue_index) = batch.queue;
+
+        trace!(
+            "submitting passes {:?} to queue {queue_family_index}.{queue_index}",
+            batch.passes
+        );
+
+        unsafe {
+            Device::wait_for_fence(&cmd_buf.device, &cmd_buf.fence)
+                .map_err(|_| DriverError::OutOfMemory)?;
+
+            cmd_buf
+                .device
+                .begin_command_buffer(
+                    **cmd_buf,
+                    &vk::CommandBufferBeginInfo::builder()
+                        .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
+                )
+                .map_err(|_| DriverError::OutOfMemory)?;
+        }
+
+        for (node_idx, next, src_queue_family_index) in batch.acquires.iter().copied() {
+            Self::record_queue_acquire(
+                cmd_buf,
+                &self.graph.bindings[node_idx],
+                next,
+                src_queue_family_index,
+            );
+        }
+
+        // Timestamps are written before the barriers and after the end of the render pass of each
+        // physical pass, using two queries per pass
+        let query_pool =
+            query_pool.filter(|_| Self::is_timed_queue_family(&cmd_buf.device, queue_family_index));
+        let reset_queries = !cmd_buf
+            .device
+            .physical_device
+            .features_v1_2
+            .host_query_reset;
+
+        let split_barriers = self.split_barriers(cmd_buf, batch.passes.clone())?;
+        let secondary_cmd_bufs = self.record_secondary_cmd_bufs(cmd_buf, batch.passes.clone())?;
+
+        for pass_idx in batch.passes.clone() {
+            let first_query = 2 * pass_idx as u32;
+
+            if let Some(query_pool) = query_pool {
+                unsafe {
+                    if reset_queries {
+                        cmd_buf.device.cmd_reset_query_pool(
+                            **cmd_buf,
+                            **query_pool,
+                            first_query,
+                            2,
+                        );
+                    }
+
+                    cmd_buf.device.cmd_write_timestamp(
+                        **cmd_buf,
+                        vk::PipelineStageFlags::TOP_OF_PIPE,
+                        **query_pool,
+                        first_query,
+                    );
+                }
+            }
+
+            self.record_scheduled_pass(cmd_buf, pass_idx, &split_barriers, &secondary_cmd_bufs)?;
+
+            if let Some(query_pool) = query_pool {
+                unsafe {
+                    cmd_buf.device.cmd_write_timestamp(
+                        **cmd_buf,
+                        vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                        **query_pool,
+                        first_query + 1,
+                    );
+                }
+            }
+        }
+
+        for (node_idx, next, dst_queue_family_index) in batch.releases.iter().copied() {
+            Self::record_queue_release(
+                cmd_buf,
+                &self.graph.bindings[node_idx],
+                next,
+                dst_queue_family_index,
+            );
+        }
+
+        let wait_dst_stage_mask = vec![vk::PipelineStageFlags::ALL_COMMANDS; batch.waits.len()];
+        let mut timeline_semaphore_info = vk::TimelineSemaphoreSubmitInfo::builder()
+            .wait_semaphore_values(&batch.wait_values)
+            .signal_semaphore_values(&batch.signal_values);
+        let mut submit_info = vk::SubmitInfo::builder()
+            .command_buffers(from_ref(cmd_buf))
+            .wait_semaphores(&batch.waits)
+            .wait_dst_stage_mask(&wait_dst_stage_mask)
+            .signal_semaphores(&batch.signals);
+
+        // Binary semaphores ignore their values, so the values are only required for timelines
+        if batch.has_timeline_semaphores {
+            submit_info = submit_info.push_next(&mut timeline_semaphore_info);
+        }
+
+        unsafe {
+            cmd_buf
+                .device
+                .end_command_buffer(**cmd_buf)
+                .map_err(|_| DriverError::OutOfMemory)?;
+            cmd_buf
+                .device
+                .reset_fences(from_ref(&cmd_buf.fence))
+                .map_err(|_| DriverError::OutOfMemory)?;
+            cmd_buf
+                .device
+                .queue_submit(
+                    cmd_buf.device.queues[queue_family_index][queue_index],
+                    from_ref(&submit_info),
+                    cmd_buf.fence,
+                )
+                .map_err(|_| DriverError::OutOfMemory)?;
+        }
+
+        Ok(())
+    }
+
+    // Splits the schedule into batches of passes by queue and submits each batch in order; the
+    // given command buffer records the final batch, which always executes on the given queue
+    #[profiling::function]
+    fn submit_queues<P>(
+        mut self,
+        pool: &mut P,
+        mut cmd_buf: Lease<CommandBuffer>,
+        queue: (usize, usize),
+        wait_semaphores: &[(&TimelineSemaphore, u64)],
+        signal_semaphores: &[(&TimelineSemaphore, u64)],
     ) -> Result<Lease<CommandBuffer>, DriverError>
     where
         P: Pool<CommandBufferInfo, CommandBuffer>
@@ -2710,56 +5218,312 @@ impl Resolver {
     {
         use std::slice::from_ref;
 
-        trace!("submit");
+        debug_assert!(
+            queue.0 < cmd_buf.device.physical_device.queue_families.len(),
+            "Queue family index must be within the range of the available queues created by the device."
+        );
+        debug_assert!(
+            queue.1 < cmd_buf.device.physical_device.queue_families[queue.0].queue_count as usize,
+            "Queue index must be within the range of the available queues created by the device."
+        );
+
+        let device = Arc::clone(&cmd_buf.device);
+        let end_pass_idx = self.graph.passes.len();
+        let mut schedule = Schedule::default();
+        schedule.access_cache.update(&self.graph, end_pass_idx);
+        schedule.passes.extend(0..end_pass_idx);
+
+        self.prepare_scheduled_passes(
+            pool,
+            &mut cmd_buf,
+            Some(queue),
+            &mut schedule,
+            end_pass_idx,
+        )?;
+
+        let mut batches = Vec::<QueueBatch>::new();
+        for pass_idx in schedule.passes.iter().copied() {
+            let pass_queue = Self::pass_queue(&device, &self.graph.passes[pass_idx], queue);
+
+            match batches.last_mut() {
+                Some(batch) if batch.queue == pass_queue => batch.passes.end += 1,
+                _ => batches.push(QueueBatch::new(pass_queue, pass_idx..pass_idx + 1)),
+            }
+        }
+
+        if batches
+            .last()
+            .map(|batch| batch.queue != queue)
+            .unwrap_or(true)
+        {
+            batches.push(QueueBatch::new(queue, end_pass_idx..end_pass_idx));
+        }
+
+        // Find the earlier batch each batch must wait for: nodes which are accessed on another
+        // queue, or which were last accessed before this submission (which we treat as access on
+        // the given queue) require a semaphore and possibly an image layout transition
+        let mut node_batches = vec![None; self.graph.bindings.len()];
+        let mut first_node_batches = vec![None; self.graph.bindings.len()];
+        let mut dependencies = vec![];
+        let mut releases = vec![];
+
+        for (batch_idx, batch) in batches.iter_mut().enumerate() {
+            let mut waits = vec![];
+
+            for pass_idx in batch.passes.clone() {
+                for exec in &self.graph.passes[pass_idx].execs {
+                    for (node_idx, [early, _]) in Self::sorted_accesses(exec) {
+                        let (prev_batch_idx, prev_queue) = node_batches[node_idx]
+                            .replace((batch_idx, batch.queue))
+                            .map(|(prev_batch_idx, prev_queue)| (Some(prev_batch_idx), prev_queue))
+                            .unwrap_or_else(|| {
+                                first_node_batches[node_idx] = Some(batch_idx);
+
+                                (None, queue)
+                            });
+
+                        if prev_queue == batch.queue {
+                            continue;
+                        }
+
+                        batch.acquires.push((node_idx, early, prev_queue.0 as _));
+
+                        // Exclusively owned images must also be released by the previous queue
+                        if prev_queue.0 != batch.queue.0
+                            && matches!(self.graph.bindings[node_idx], Binding::SwapchainImage(..))
+                        {
+                            releases.push((prev_batch_idx, (node_idx, early, batch.queue.0 as _)));
+                        }
+
+                        if !waits.contains(&prev_batch_idx) {
+                            waits.push(prev_batch_idx);
+                        }
+                    }
+                }
+            }
+
+            dependencies.push(waits);
+        }
+
+        // The final batch waits for every other queue so that the returned command buffer is the
+        // last to execute
+        let last_batch_idx = batches.len() - 1;
+        for (batch_idx, batch) in batches.iter().enumerate().rev().skip(1) {
+            if batch.queue != queue
+                && !batches[batch_idx + 1..]
+                    .iter()
+                    .any(|later_batch| later_batch.queue == batch.queue)
+                && !dependencies[last_batch_idx].contains(&Some(batch_idx))
+            {
+                dependencies[last_batch_idx].push(Some(batch_idx));
+            }
+        }
+
+        // Batches which depend on work submitted before this graph wait for the given queue; the
+        // latest batch on the given queue covers any earlier work submitted to it
+        let latest_queue_batch = |batches: &[QueueBatch], batch_idx: usize| {
+            batches[..batch_idx]
+                .iter()
+                .rposition(|prev_batch| prev_batch.queue == queue)
+        };
+        let mut start_signals = vec![];
+        for (batch_idx, waits) in dependencies.into_iter().enumerate() {
+            for prev_batch_idx in waits {
+                let prev_batch_idx =
+                    prev_batch_idx.or_else(|| latest_queue_batch(&batches, batch_idx));
+
+                // Semaphores are reused once the returned command buffer has executed
+                let semaphore = CommandBuffer::next_semaphore(&mut cmd_buf)?;
+                if let Some(prev_batch_idx) = prev_batch_idx {
+                    batches[prev_batch_idx].push_signal(semaphore, 0);
+                } else {
+                    start_signals.push(semaphore);
+                }
+
+                batches[batch_idx].push_wait(semaphore, 0);
+            }
+        }
+
+        let mut start_releases = vec![];
+        for (prev_batch_idx, release) in releases {
+            let batch_idx = first_node_batches[release.0].unwrap();
+            if let Some(prev_batch_idx) =
+                prev_batch_idx.or_else(|| latest_queue_batch(&batches, batch_idx))
+            {
+                batches[prev_batch_idx].releases.push(release);
+            } else {
+                start_releases.push(release);
+            }
+        }
+
+        // Timeline semaphores are waited on by the first batch of each queue and signaled by the
+        // final batch, which executes after every other batch
+        for batch_idx in 0..batches.len() {
+            if batches[..batch_idx]
+                .iter()
+                .all(|prev_batch| prev_batch.queue != batches[batch_idx].queue)
+            {
+                for (semaphore, value) in wait_semaphores.iter().copied() {
+                    batches[batch_idx].push_wait(**semaphore, value);
+                    batches[batch_idx].has_timeline_semaphores = true;
+                }
+            }
+        }
+
+        for (semaphore, value) in signal_semaphores.iter().copied() {
+            batches[last_batch_idx].push_signal(**semaphore, value);
+            batches[last_batch_idx].has_timeline_semaphores = true;
+        }
+
+        // Nodes bound after another submission wait for it before their first access; the final
+        // batch waits for nodes which are not accessed so that later submissions remain ordered
+        for (node_idx, token) in &self.graph.dependencies {
+            let batch = &mut batches[first_node_batches[*node_idx].unwrap_or(last_batch_idx)];
+
+            if !batch.waits.contains(&token.semaphore) {
+                batch.push_wait(**token.semaphore, token.value);
+                batch.has_timeline_semaphores = true;
+            }
+        }
+
+        // Exclusively owned images which are first accessed on another queue family are released
+        // by the given queue before the batches which wait for it
+        let mut cmd_bufs = Vec::with_capacity(last_batch_idx + 1);
+        if !start_releases.is_empty() {
+            let start_cmd_buf = pool.lease(CommandBufferInfo::new(queue.0 as _))?;
+
+            unsafe {
+                Device::wait_for_fence(&device, &start_cmd_buf.fence)
+                    .map_err(|_| DriverError::OutOfMemory)?;
+
+                device
+                    .begin_command_buffer(
+                        **start_cmd_buf,
+                        &vk::CommandBufferBeginInfo::builder()
+                            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
+                    )
+                    .map_err(|_| DriverError::OutOfMemory)?;
+            }
+
+            for (node_idx, next, dst_queue_family_index) in start_releases {
+                Self::record_queue_release(
+                    &start_cmd_buf,
+                    &self.graph.bindings[node_idx],
+                    next,
+                    dst_queue_family_index,
+                );
+            }
+
+            unsafe {
+                device
+                    .end_command_buffer(**start_cmd_buf)
+                    .map_err(|_| DriverError::OutOfMemory)?;
+                device
+                    .reset_fences(from_ref(&start_cmd_buf.fence))
+                    .map_err(|_| DriverError::OutOfMemory)?;
+                device
+                    .queue_submit(
+                        device.queues[queue.0][queue.1],
+                        from_ref(
+                            &vk::SubmitInfo::builder()
+                                .command_buffers(from_ref(&start_cmd_buf))
+                                .signal_semaphores(&start_signals),
+                        ),
+                        start_cmd_buf.fence,
+                    )
+                    .map_err(|_| DriverError::OutOfMemory)?;
+            }
+
+            cmd_bufs.push(start_cmd_buf);
+        } else if !start_signals.is_empty() {
+            unsafe {
+                device
+                    .queue_submit(
+                        device.queues[queue.0][queue.1],
+                        from_ref(&vk::SubmitInfo::builder().signal_semaphores(&start_signals)),
+                        vk::Fence::null(),
+                    )
+                    .map_err(|_| DriverError::OutOfMemory)?;
+            }
+        }
 
-        let mut cmd_buf = pool.lease(CommandBufferInfo::new(queue_family_index as _))?;
+        // Timestamps are opt-in and so the query pool is created for this submission only
+        let query_pool = if self.timestamps.is_some() && !schedule.passes.is_empty() {
+            let query_count = 2 * schedule.passes.len() as u32;
+            let query_pool = QueryPool::create(&device, QueryPoolInfo::timestamp(query_count))?;
 
-        debug_assert!(
-            queue_family_index < cmd_buf.device.physical_device.queue_families.len(),
-            "Queue family index must be within the range of the available queues created by the device."
-        );
-        debug_assert!(
-            queue_index
-                < cmd_buf.device.physical_device.queue_families[queue_family_index].queue_count
-                    as usize,
-            "Queue index must be within the range of the available queues created by the device."
-        );
+            if device.physical_device.features_v1_2.host_query_reset {
+                QueryPool::reset(&query_pool, 0, query_count)?;
+            }
 
-        unsafe {
-            Device::wait_for_fence(&cmd_buf.device, &cmd_buf.fence)
-                .map_err(|_| DriverError::OutOfMemory)?;
+            Some(Arc::new(query_pool))
+        } else {
+            None
+        };
 
-            cmd_buf
-                .device
-                .begin_command_buffer(
-                    **cmd_buf,
-                    &vk::CommandBufferBeginInfo::builder()
-                        .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
-                )
-                .map_err(|_| DriverError::OutOfMemory)?;
+        let batch_query_pool = query_pool.as_deref();
+        for batch in &batches[..last_batch_idx] {
+            let mut batch_cmd_buf = pool.lease(CommandBufferInfo::new(batch.queue.0 as _))?;
+            self.submit_queue_batch(&mut batch_cmd_buf, batch, batch_query_pool)?;
+            cmd_bufs.push(batch_cmd_buf);
         }
 
-        self.record_unscheduled_passes(pool, &mut cmd_buf)?;
+        self.submit_queue_batch(&mut cmd_buf, &batches[last_batch_idx], batch_query_pool)?;
 
-        unsafe {
-            cmd_buf
-                .device
-                .end_command_buffer(**cmd_buf)
-                .map_err(|_| DriverError::OutOfMemory)?;
-            cmd_buf
-                .device
-                .reset_fences(from_ref(&cmd_buf.fence))
-                .map_err(|_| DriverError::OutOfMemory)?;
-            cmd_buf
-                .device
-                .queue_submit(
-                    cmd_buf.device.queues[queue_family_index][queue_index],
-                    from_ref(&vk::SubmitInfo::builder().command_buffers(from_ref(&cmd_buf))),
-                    cmd_buf.fence,
-                )
-                .map_err(|_| DriverError::OutOfMemory)?;
+        if let Some((timestamps, query_pool)) = self.timestamps.take().zip(query_pool) {
+            let graph_passes = &self.graph.passes;
+            let passes = batches
+                .iter()
+                .filter_map(|batch| {
+                    let valid_bits =
+                        device.physical_device.queue_families[batch.queue.0].timestamp_valid_bits;
+
+                    Self::is_timed_queue_family(&device, batch.queue.0).then(|| {
+                        batch
+                            .passes
+                            .clone()
+                            .map(move |pass_idx| PassTimestampQuery {
+                                first_query: 2 * pass_idx as u32,
+                                name: graph_passes[pass_idx].name.clone(),
+                                queue_family_index: batch.queue.0 as _,
+                                valid_bits,
+                            })
+                    })
+                })
+                .flatten()
+                .collect();
+
+            CommandBuffer::push_fenced_drop(&mut cmd_buf, Arc::clone(&query_pool));
+
+            timestamps
+                .queries
+                .set(PassTimestampQueries {
+                    passes,
+                    query_pool,
+                    timestamp_period: device
+                        .physical_device
+                        .properties_v1_0
+                        .limits
+                        .timestamp_period,
+                })
+                .unwrap_or_else(|_| unreachable!());
+        }
+
+        // Readbacks take their staging buffers and are marked executed once the fence signals
+        for (node_idx, readback) in self.graph.readbacks.drain(..) {
+            let Binding::TransientBuffer(_, buffer) = &mut self.graph.bindings[node_idx] else {
+                unreachable!();
+            };
+            let readback = readback.submit(buffer.take().unwrap(), &device, cmd_buf.fence);
+
+            CommandBuffer::push_fenced_drop(&mut cmd_buf, readback);
         }
 
+        self.retire_scheduled_passes(&mut cmd_buf, &mut schedule);
+
+        // The other command buffers have completed once the final batch has
+        CommandBuffer::push_fenced_drop(&mut cmd_buf, cmd_bufs);
+
         // This graph contains references to buffers, images, and other resources which must be kept
         // alive until this graph execution completes on the GPU. Once those references are dropped
         // they will return to the pool for other things to use. The drop will happen the next time
@@ -2778,6 +5542,39 @@ impl Resolver {
         node.unbind(self)
     }
 
+    // Recording without submitting cannot wait on the GPU, so nodes bound after another submission
+    // are waited for on the host before the first scheduled pass which accesses them
+    #[profiling::function]
+    fn wait_scheduled_dependencies(&mut self, schedule: &Schedule) -> Result<(), DriverError> {
+        if self.graph.dependencies.is_empty() {
+            return Ok(());
+        }
+
+        let mut accessed = vec![false; self.graph.bindings.len()];
+        for pass_idx in schedule.passes.iter().copied() {
+            for exec in &self.graph.passes[pass_idx].execs {
+                for node_idx in exec.accesses.keys().copied() {
+                    accessed[node_idx] = true;
+                }
+            }
+        }
+
+        for (_, token) in self
+            .graph
+            .dependencies
+            .iter()
+            .filter(|(node_idx, _)| accessed[*node_idx])
+        {
+            token.wait_until_executed()?;
+        }
+
+        self.graph
+            .dependencies
+            .retain(|(node_idx, _)| !accessed[*node_idx]);
+
+        Ok(())
+    }
+
     #[profiling::function]
     fn write_descriptor_sets(
         cmd_buf: &CommandBuffer,
@@ -3059,8 +5856,666 @@ impl Resolver {
     }
 }
 
+/// A handle to the work submitted by [`Resolver::submit_with_token`].
+///
+/// See [`RenderGraph::bind_node_after`].
+#[derive(Clone, Debug)]
+pub struct SubmissionToken {
+    semaphore: Arc<TimelineSemaphore>,
+    value: u64,
+}
+
+impl SubmissionToken {
+    /// Returns `true` after the GPU has executed the submitted work.
+    ///
+    /// See [`Self::wait_until_executed`] to block while checking.
+    #[profiling::function]
+    pub fn has_executed(&self) -> Result<bool, DriverError> {
+        TimelineSemaphore::has_reached(&self.semaphore, self.value)
+    }
+
+    /// Stalls by blocking the current thread until the GPU has executed the submitted work.
+    ///
+    /// See [`Self::has_executed`] to check without blocking.
+    #[profiling::function]
+    pub fn wait_until_executed(&self) -> Result<(), DriverError> {
+        TimelineSemaphore::wait(&self.semaphore, self.value)
+    }
+}
+
+/// A handle to the GPU timestamps recorded by the submission of a [`Resolver`].
+///
+/// See [`Resolver::query_timestamps`].
+#[derive(Clone, Debug)]
+pub struct PassTimestamps {
+    queries: Arc<OnceLock<PassTimestampQueries>>,
+}
+
+impl PassTimestamps {
+    /// Returns the GPU execution time of each submitted physical pass, in recording order.
+    ///
+    /// Returns `None` if the submission has not yet executed, or if the resolver has not been
+    /// submitted.
+    #[profiling::function]
+    pub fn report(&self) -> Result<Option<Vec<PassTimestampReport>>, DriverError> {
+        let Some(queries) = self.queries.get() else {
+            return Ok(None);
+        };
+
+        let mut res = Vec::with_capacity(queries.passes.len());
+        for pass in &queries.passes {
+            let mut timestamps = [0u64; 2];
+            if !QueryPool::results(&queries.query_pool, pass.first_query, 2, &mut timestamps)? {
+                return Ok(None);
+            }
+
+            // Bits outside of the valid range are undefined and the counter may wrap
+            let mask = u64::MAX >> (64 - pass.valid_bits.min(64));
+            let ticks = (timestamps[1] & mask).wrapping_sub(timestamps[0] & mask) & mask;
+
+            res.push(PassTimestampReport {
+                duration: Duration::from_nanos(
+                    (ticks as f64 * queries.timestamp_period as f64) as _,
+                ),
+                name: pass.name.clone(),
+                queue_family_index: pass.queue_family_index,
+            });
+        }
+
+        Ok(Some(res))
+    }
+}
+
+#[derive(Debug)]
+struct PassTimestampQueries {
+    passes: Vec<PassTimestampQuery>,
+    query_pool: Arc<QueryPool>,
+    timestamp_period: f32,
+}
+
+#[derive(Debug)]
+struct PassTimestampQuery {
+    first_query: u32,
+    name: String,
+    queue_family_index: u32,
+    valid_bits: u32,
+}
+
+#[derive(Debug)]
+struct QueueBatch {
+    acquires: Vec<(NodeIndex, SubresourceAccess, u32)>,
+    has_timeline_semaphores: bool,
+    passes: Range<usize>,
+    queue: (usize, usize),
+    releases: Vec<(NodeIndex, SubresourceAccess, u32)>,
+    signal_values: Vec<u64>,
+    signals: Vec<vk::Semaphore>,
+    wait_values: Vec<u64>,
+    waits: Vec<vk::Semaphore>,
+}
+
+impl QueueBatch {
+    fn new(queue: (usize, usize), passes: Range<usize>) -> Self {
+        Self {
+            acquires: vec![],
+            has_timeline_semaphores: false,
+            passes,
+            queue,
+            releases: vec![],
+            signal_values: vec![],
+            signals: vec![],
+            wait_values: vec![],
+            waits: vec![],
+        }
+    }
+
+    fn push_signal(&mut self, semaphore: vk::Semaphore, value: u64) {
+        self.signals.push(semaphore);
+        self.signal_values.push(value);
+    }
+
+    fn push_wait(&mut self, semaphore: vk::Semaphore, value: u64) {
+        self.waits.push(semaphore);
+        self.wait_values.push(value);
+    }
+}
+
+/// Handle to the counts of the barriers recorded by a [`Resolver`].
+///
+/// See [`Resolver::barrier_counter`].
+#[derive(Clone, Debug, Default)]
+pub struct BarrierCounter(Arc<BarrierCounterInner>);
+
+impl BarrierCounter {
+    /// Returns the current counts.
+    pub fn counts(&self) -> BarrierCounts {
+        BarrierCounts {
+            elided: self.0.elided.load(Ordering::Relaxed),
+            pipeline_barriers: self.0.pipeline_barriers.load(Ordering::Relaxed),
+            recorded: self.0.recorded.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct BarrierCounterInner {
+    elided: AtomicUsize,
+    pipeline_barriers: AtomicUsize,
+    recorded: AtomicUsize,
+}
+
+/// Counts of the barriers recorded by a [`Resolver`].
+///
+/// See [`BarrierCounter::counts`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BarrierCounts {
+    /// The number of resource barriers which were not recorded because earlier barriers made them
+    /// redundant.
+    pub elided: usize,
+
+    /// The number of pipeline barrier commands recorded.
+    ///
+    /// Each command may contain many resource barriers. Barriers which wait on events are not
+    /// included.
+    pub pipeline_barriers: usize,
+
+    /// The number of resource barriers recorded.
+    pub recorded: usize,
+}
+
+/// Tracks the accesses of each node recorded into a command buffer so that redundant barriers may
+/// be elided.
+#[derive(Debug, Default)]
+struct BarrierState {
+    /// The last accesses of the resources which previously used the memory of each transient node.
+    ///
+    /// The first barrier of the node waits on these accesses.
+    aliases: HashMap<NodeIndex, Vec<AccessType>>,
+
+    cmd_buf: vk::CommandBuffer,
+    counter: BarrierCounter,
+
+    /// The reads of each node since it was last written, which have been made visible by barriers.
+    reads: HashMap<NodeIndex, Vec<(Option<Subresource>, AccessType)>>,
+}
+
+impl BarrierState {
+    /// Tracks an access of a node, where `is_visible` indicates a barrier which made the access
+    /// visible has been recorded or elided.
+    fn access(
+        &mut self,
+        node_idx: NodeIndex,
+        subresource: Option<Subresource>,
+        early: SubresourceAccess,
+        late: SubresourceAccess,
+        is_visible: bool,
+    ) {
+        let reads = self.reads.entry(node_idx).or_default();
+
+        if is_write_access(early.access) || is_write_access(late.access) {
+            reads.clear();
+        } else if is_visible && !reads.contains(&(subresource, early.access)) {
+            reads.push((subresource, early.access));
+        }
+    }
+
+    /// Forgets the accesses of every node if `cmd_buf` is not the command buffer which was
+    /// previously recorded.
+    fn begin(&mut self, cmd_buf: vk::CommandBuffer) {
+        if self.cmd_buf != cmd_buf {
+            self.cmd_buf = cmd_buf;
+            self.reads.clear();
+        }
+    }
+
+    /// Returns `true` if a barrier is required between the given accesses of a node.
+    fn is_required(
+        &self,
+        node_idx: NodeIndex,
+        subresource: Option<Subresource>,
+        is_image: bool,
+        prev_access: AccessType,
+        next_access: AccessType,
+    ) -> bool {
+        // Writes and image layout transitions always require a barrier
+        if is_write_access(prev_access)
+            || is_write_access(next_access)
+            || (is_image && prev_access != next_access)
+        {
+            return true;
+        }
+
+        // Reads only require a barrier if they have not been made visible since the last write
+        !self
+            .reads
+            .get(&node_idx)
+            .is_some_and(|reads| reads.contains(&(subresource, next_access)))
+    }
+
+    /// Returns the reads of a node since it was last written.
+    ///
+    /// Barriers which follow elided reads must also wait on them.
+    fn reads(&self, node_idx: NodeIndex) -> impl Iterator<Item = AccessType> + '_ {
+        self.reads
+            .get(&node_idx)
+            .into_iter()
+            .flatten()
+            .map(|(_, access)| *access)
+    }
+}
+
+/// The work of one execution, which may be recorded on another thread once its pipeline exists.
+struct ExecutionCommands {
+    exec_func: ExecutionFunction,
+    exec_idx: usize,
+    pass_idx: usize,
+    pipeline: Option<vk::Pipeline>,
+}
+
+/// The secondary command buffers of each pass, in execution order.
+type SecondaryCommandBuffers = HashMap<usize, Vec<vk::CommandBuffer>>;
+
+/// Pipeline barriers which have been split into an event signalled after one pass and waited on
+/// before a later pass.
+#[derive(Debug, Default)]
+struct SplitBarriers {
+    /// Events to signal after each pass, along with the accesses they signal.
+    signals: HashMap<usize, Vec<(vk::Event, Vec<AccessType>)>>,
+
+    /// Events to wait on before each pass, along with the nodes they make available.
+    waits: HashMap<usize, SplitBarrierWait>,
+}
+
+#[derive(Debug, Default)]
+struct SplitBarrierWait {
+    events: Vec<vk::Event>,
+    nodes: Vec<NodeIndex>,
+}
+
+/// The location of a transient resource within a block of memory shared with other resources.
+#[derive(Debug)]
+struct TransientPlacement {
+    /// The scheduled passes which access the resource.
+    lifetime: Range<usize>,
+    node_idx: NodeIndex,
+    offset: vk::DeviceSize,
+    size: vk::DeviceSize,
+}
+
+impl TransientPlacement {
+    /// Places each resource at the lowest offset which is not used by a resource with an
+    /// overlapping lifetime, and returns the requirements of the memory block which holds them.
+    fn place(
+        resources: impl Iterator<Item = (NodeIndex, Range<usize>, vk::MemoryRequirements)>,
+        memory_type_bits: u32,
+    ) -> (Vec<Self>, vk::MemoryRequirements) {
+        use std::cmp::Reverse;
+
+        let mut resources = resources.collect::<Vec<_>>();
+
+        // Larger resources are placed first so that smaller ones fill the gaps between them
+        resources.sort_by_key(|(_, _, requirements)| Reverse(requirements.size));
+
+        let mut placements = Vec::<Self>::with_capacity(resources.len());
+        let mut block_requirements = vk::MemoryRequirements {
+            size: 0,
+            alignment: 1,
+            memory_type_bits,
+        };
+
+        for (node_idx, lifetime, requirements) in resources {
+            let mut placement = Self {
+                lifetime,
+                node_idx,
+                offset: 0,
+                size: requirements.size,
+            };
+
+            // Move past each resource which is in use at the same time as this one
+            while let Some(other) = placements.iter().find(|other| {
+                other.lifetime.start < placement.lifetime.end
+                    && placement.lifetime.start < other.lifetime.end
+                    && other.overlaps(&placement)
+            }) {
+                placement.offset =
+                    (other.offset + other.size).next_multiple_of(requirements.alignment);
+            }
+
+            block_requirements.size = block_requirements
+                .size
+                .max(placement.offset + placement.size);
+            block_requirements.alignment = block_requirements.alignment.max(requirements.alignment);
+
+            placements.push(placement);
+        }
+
+        (placements, block_requirements)
+    }
+
+    /// Returns `true` if the memory of both placements overlaps.
+    fn overlaps(&self, other: &Self) -> bool {
+        self.offset < other.offset + other.size && other.offset < self.offset + self.size
+    }
+}
+
 #[derive(Default)]
 struct Schedule {
     access_cache: AccessCache,
     passes: Vec<usize>,
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            driver::{buffer::BufferSubresource, image::SampleCount},
+            graph::node::TransientBufferNode,
+        },
+        ash::vk::Handle,
+    };
+
+    fn access(
+        state: &mut BarrierState,
+        node_idx: NodeIndex,
+        subresource: Option<Subresource>,
+        access: AccessType,
+    ) {
+        let access = SubresourceAccess {
+            access,
+            subresource,
+        };
+        state.access(node_idx, subresource, access, access, true);
+    }
+
+    fn buffer(start: vk::DeviceSize, end: vk::DeviceSize) -> Option<Subresource> {
+        Some(Subresource::Buffer(BufferSubresource { start, end }))
+    }
+
+    #[test]
+    pub fn barrier_state_elides_visible_reads() {
+        let mut state = BarrierState::default();
+        let read = AccessType::TransferRead;
+
+        assert!(state.is_required(0, buffer(0, 64), false, AccessType::TransferWrite, read));
+
+        access(&mut state, 0, buffer(0, 64), read);
+
+        assert!(!state.is_required(0, buffer(0, 64), false, read, read));
+        assert!(state.is_required(0, buffer(0, 32), false, read, read));
+        assert!(state.is_required(1, buffer(0, 64), false, read, read));
+    }
+
+    #[test]
+    pub fn barrier_state_requires_writes() {
+        let mut state = BarrierState::default();
+        let read = AccessType::ComputeShaderReadOther;
+        let write = AccessType::ComputeShaderWrite;
+
+        access(&mut state, 0, buffer(0, 64), read);
+
+        assert!(state.is_required(0, buffer(0, 64), false, read, write));
+
+        access(&mut state, 0, buffer(0, 64), write);
+
+        assert!(state.is_required(0, buffer(0, 64), false, write, read));
+        assert!(state.is_required(0, buffer(0, 64), false, read, read));
+        assert_eq!(state.reads(0).count(), 0);
+    }
+
+    #[test]
+    pub fn barrier_state_requires_layout_transitions() {
+        let mut state = BarrierState::default();
+        let sampled = AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer;
+        let transfer = AccessType::TransferRead;
+
+        access(&mut state, 0, None, sampled);
+        access(&mut state, 0, None, transfer);
+
+        assert!(state.is_required(0, None, true, transfer, sampled));
+        assert!(!state.is_required(0, None, false, transfer, sampled));
+    }
+
+    #[test]
+    pub fn barrier_state_reads() {
+        let mut state = BarrierState::default();
+        let reads = [
+            AccessType::TransferRead,
+            AccessType::VertexBuffer,
+            AccessType::TransferRead,
+        ];
+
+        for read in reads {
+            access(&mut state, 0, buffer(0, 64), read);
+        }
+
+        let state_reads = state.reads(0).collect::<Vec<_>>();
+
+        assert_eq!(state_reads.len(), 2);
+        assert!(state_reads.contains(&AccessType::TransferRead));
+        assert!(state_reads.contains(&AccessType::VertexBuffer));
+
+        state.begin(vk::CommandBuffer::from_raw(1));
+
+        assert_eq!(state.reads(0).count(), 0);
+    }
+
+    fn placements(
+        resources: &[(Range<usize>, vk::DeviceSize, vk::DeviceSize)],
+    ) -> (Vec<TransientPlacement>, vk::MemoryRequirements) {
+        let (mut placements, requirements) =
+            TransientPlacement::place(
+                resources.iter().cloned().enumerate().map(
+                    |(node_idx, (lifetime, size, alignment))| {
+                        (
+                            node_idx,
+                            lifetime,
+                            vk::MemoryRequirements {
+                                size,
+                                alignment,
+                                memory_type_bits: 1,
+                            },
+                        )
+                    },
+                ),
+                1,
+            );
+        placements.sort_by_key(|placement| placement.node_idx);
+
+        (placements, requirements)
+    }
+
+    fn schedule_report_buffer(graph: &mut RenderGraph) -> TransientBufferNode {
+        graph.create_buffer(BufferInfo::device_mem(
+            64,
+            vk::BufferUsageFlags::TRANSFER_SRC | vk::BufferUsageFlags::TRANSFER_DST,
+        ))
+    }
+
+    #[test]
+    pub fn schedule_report_elides_repeated_reads() {
+        let mut graph = RenderGraph::new();
+        let src = schedule_report_buffer(&mut graph);
+        let dsts = [(); 3].map(|_| schedule_report_buffer(&mut graph));
+
+        graph.fill_buffer(src, 0);
+
+        for dst in dsts {
+            graph.copy_buffer(src, dst);
+        }
+
+        let report = graph.resolve().schedule_report();
+        let src_barriers = report
+            .barriers
+            .iter()
+            .filter(|barrier| barrier.node_idx == src.index())
+            .map(|barrier| (barrier.prev_access, barrier.next_access))
+            .collect::<Vec<_>>();
+
+        assert_eq!(report.schedule.len(), 4);
+        assert_eq!(report.barriers.len(), 5);
+        assert_eq!(
+            src_barriers,
+            [
+                (AccessType::Nothing, AccessType::TransferWrite),
+                (AccessType::TransferWrite, AccessType::TransferRead),
+            ]
+        );
+        assert!(report
+            .barriers
+            .iter()
+            .all(|barrier| barrier.kind == BarrierKind::Pipeline));
+    }
+
+    #[test]
+    pub fn schedule_report_split_waits() {
+        let mut graph = RenderGraph::new();
+        let src = schedule_report_buffer(&mut graph);
+        let other = schedule_report_buffer(&mut graph);
+        let dst = schedule_report_buffer(&mut graph);
+
+        graph.fill_buffer(src, 0);
+        graph.fill_buffer(other, 0);
+        graph.copy_buffer(src, dst);
+
+        let report = graph.resolve().schedule_report();
+        let split_barriers = report
+            .barriers
+            .iter()
+            .filter(|barrier| barrier.kind == BarrierKind::SplitWait)
+            .collect::<Vec<_>>();
+
+        assert_eq!(split_barriers.len(), 1);
+        assert_eq!(split_barriers[0].node_idx, src.index());
+        assert_eq!(split_barriers[0].prev_access, AccessType::TransferWrite);
+        assert_eq!(split_barriers[0].next_access, AccessType::TransferRead);
+    }
+
+    #[test]
+    pub fn transient_placement_aliases_disjoint_lifetimes() {
+        let (placements, requirements) = placements(&[(0..1, 1024, 64), (1..2, 1024, 64)]);
+
+        assert_eq!(placements[0].offset, 0);
+        assert_eq!(placements[1].offset, 0);
+        assert_eq!(requirements.size, 1024);
+        assert_eq!(requirements.alignment, 64);
+    }
+
+    #[test]
+    pub fn transient_placement_separates_overlapping_lifetimes() {
+        let (placements, requirements) =
+            placements(&[(0..1, 1024, 64), (1..3, 1024, 64), (0..2, 100, 256)]);
+
+        assert_eq!(placements[0].offset, 0);
+        assert_eq!(placements[1].offset, 0);
+        assert_eq!(placements[2].offset, 1024);
+        assert_eq!(requirements.size, 1124);
+        assert_eq!(requirements.alignment, 256);
+    }
+
+    #[test]
+    pub fn transient_placement_fills_gaps() {
+        let (placements, requirements) = placements(&[
+            (0..4, 512, 1),
+            (1..2, 2048, 1),
+            (2..3, 256, 1),
+            (3..4, 256, 1),
+        ]);
+
+        assert_eq!(placements[1].offset, 0);
+        assert_eq!(placements[0].offset, 2048);
+        assert_eq!(placements[2].offset, 0);
+        assert_eq!(placements[3].offset, 0);
+        assert_eq!(requirements.size, 2560);
+    }
+
+    #[test]
+    pub fn transient_usage_is_inferred_from_accesses() {
+        use crate::driver::image::ImageInfo;
+
+        let mut graph = RenderGraph::new();
+        let image = graph.create_image(ImageInfo::image_2d(
+            4,
+            4,
+            vk::Format::R8G8B8A8_UNORM,
+            vk::ImageUsageFlags::SAMPLED,
+        ));
+        let buf = graph.create_buffer(BufferInfo::device_mem(64, vk::BufferUsageFlags::empty()));
+        let unused_buf =
+            graph.create_buffer(BufferInfo::device_mem(64, vk::BufferUsageFlags::empty()));
+        graph
+            .clear_color_image(image)
+            .copy_image_to_buffer(image, buf);
+
+        let resolver = graph.resolve();
+
+        assert_eq!(
+            resolver.graph.bindings[image.index()]
+                .image_info()
+                .unwrap()
+                .usage,
+            vk::ImageUsageFlags::SAMPLED
+                | vk::ImageUsageFlags::TRANSFER_SRC
+                | vk::ImageUsageFlags::TRANSFER_DST
+        );
+        assert_eq!(
+            resolver.graph.bindings[buf.index()]
+                .buffer_info()
+                .unwrap()
+                .usage,
+            vk::BufferUsageFlags::TRANSFER_DST
+        );
+        assert!(resolver.graph.bindings[unused_buf.index()]
+            .buffer_info()
+            .unwrap()
+            .usage
+            .is_empty());
+    }
+
+    #[test]
+    pub fn rendering_formats_skip_unused_attachments() {
+        let attachment = |fmt, sample_count| AttachmentInfo {
+            fmt,
+            sample_count,
+            ..Default::default()
+        };
+        let attachment_ref = |attachment, layout| AttachmentRef {
+            attachment,
+            aspect_mask: vk::ImageAspectFlags::empty(),
+            layout,
+        };
+        let mut subpass = SubpassInfo::with_capacity(2);
+        subpass.color_attachments.push(attachment_ref(
+            vk::ATTACHMENT_UNUSED,
+            vk::ImageLayout::UNDEFINED,
+        ));
+        subpass
+            .color_attachments
+            .push(attachment_ref(0, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL));
+        subpass.depth_stencil_attachment =
+            Some(attachment_ref(1, vk::ImageLayout::DEPTH_ATTACHMENT_OPTIMAL));
+        subpass.view_mask = 0b11;
+
+        let rendering = Rendering::new(RenderPassInfo {
+            attachments: vec![
+                attachment(vk::Format::R8G8B8A8_UNORM, SampleCount::Type4),
+                attachment(vk::Format::D32_SFLOAT, SampleCount::Type4),
+            ],
+            subpasses: vec![subpass],
+            dependencies: vec![],
+        });
+
+        assert_eq!(
+            rendering.formats.color_formats,
+            [vk::Format::UNDEFINED, vk::Format::R8G8B8A8_UNORM]
+        );
+        assert_eq!(rendering.formats.depth_format, vk::Format::D32_SFLOAT);
+        assert_eq!(rendering.formats.stencil_format, vk::Format::UNDEFINED);
+        assert_eq!(rendering.formats.view_mask, 0b11);
+        assert_eq!(rendering.samples, vk::SampleCountFlags::TYPE_4);
+        assert_eq!(
+            Rendering::layout(vk::ImageLayout::DEPTH_ATTACHMENT_OPTIMAL),
+            vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL
+        );
+    }
+}
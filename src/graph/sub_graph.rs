@@ -0,0 +1,223 @@
+//! Reusable groups of passes which are built separately from the graphs that use them.
+
+use {
+    super::{
+        node::{
+            AnyAccelerationStructureNode, AnyBufferNode, AnyImageNode, AnyNode, AnyQueryPoolNode,
+            Node, TransientBufferNode, TransientImageNode,
+        },
+        NodeIndex, RenderGraph, Resolver,
+    },
+    crate::driver::{buffer::BufferInfo, image::ImageInfo},
+    std::{
+        collections::BTreeMap,
+        ops::{Deref, DerefMut},
+    },
+};
+
+/// A render graph with named input and output nodes which may be appended to another graph.
+///
+/// Sub-graphs allow rendering features such as shadows, bloom or UI to be built separately from
+/// the graph which uses them. `SubGraph` dereferences to [`RenderGraph`], so functions which add
+/// passes to a `&mut RenderGraph` may also be used to build a sub-graph.
+///
+/// Inputs are nodes of the sub-graph which are replaced by nodes of the parent graph when it is
+/// appended using [`RenderGraph::append`]. Inputs declared with [`Self::input_buffer`] or
+/// [`Self::input_image`] are transient, which allows a sub-graph to be resolved on its own, such as
+/// in tests, in which case those inputs are created by the graph.
+#[derive(Debug)]
+pub struct SubGraph {
+    graph: RenderGraph,
+    inputs: BTreeMap<String, NodeIndex>,
+    outputs: SubGraphOutputs,
+}
+
+impl SubGraph {
+    /// Constructs a new `SubGraph`.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            graph: RenderGraph::new(),
+            inputs: Default::default(),
+            outputs: Default::default(),
+        }
+    }
+
+    /// Declares a transient buffer input which is replaced by a buffer node of the parent graph.
+    ///
+    /// See [`RenderGraph::create_buffer`].
+    pub fn input_buffer(
+        &mut self,
+        name: impl Into<String>,
+        info: impl Into<BufferInfo>,
+    ) -> TransientBufferNode {
+        let node = self.graph.create_buffer(info);
+        self.set_input(name, node);
+
+        node
+    }
+
+    /// Declares a transient image input which is replaced by an image node of the parent graph.
+    ///
+    /// See [`RenderGraph::create_image`].
+    pub fn input_image(
+        &mut self,
+        name: impl Into<String>,
+        info: impl Into<ImageInfo>,
+    ) -> TransientImageNode {
+        let node = self.graph.create_image(info);
+        self.set_input(name, node);
+
+        node
+    }
+
+    pub(super) fn into_parts(
+        this: Self,
+    ) -> (RenderGraph, BTreeMap<String, NodeIndex>, SubGraphOutputs) {
+        (this.graph, this.inputs, this.outputs)
+    }
+
+    /// Returns the output nodes of this sub-graph.
+    pub fn outputs(&self) -> &SubGraphOutputs {
+        &self.outputs
+    }
+
+    /// Resolves this sub-graph on its own.
+    ///
+    /// See [`RenderGraph::resolve`].
+    pub fn resolve(self) -> Resolver {
+        self.graph.resolve()
+    }
+
+    /// Declares an existing node of this sub-graph as an input, replacing any previous input of the
+    /// same name.
+    ///
+    /// The node, and the resource bound to it, are used when the input is not given to
+    /// [`RenderGraph::append`].
+    pub fn set_input(&mut self, name: impl Into<String>, node: impl Into<AnyNode>) -> &mut Self {
+        self.inputs.insert(name.into(), node.into().index());
+        self
+    }
+
+    /// Declares a node of this sub-graph as an output, replacing any previous output of the same
+    /// name.
+    pub fn set_output(&mut self, name: impl Into<String>, node: impl Into<AnyNode>) -> &mut Self {
+        self.outputs.nodes.insert(name.into(), node.into());
+        self
+    }
+}
+
+impl Deref for SubGraph {
+    type Target = RenderGraph;
+
+    fn deref(&self) -> &Self::Target {
+        &self.graph
+    }
+}
+
+impl DerefMut for SubGraph {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.graph
+    }
+}
+
+/// The named output nodes of a [`SubGraph`].
+#[derive(Clone, Debug, Default)]
+pub struct SubGraphOutputs {
+    nodes: BTreeMap<String, AnyNode>,
+}
+
+impl SubGraphOutputs {
+    /// Returns the named acceleration structure output, if any.
+    pub fn acceleration_structure(&self, name: &str) -> Option<AnyAccelerationStructureNode> {
+        match self.get(name)? {
+            AnyNode::AccelerationStructure(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    /// Returns the named buffer output, if any.
+    pub fn buffer(&self, name: &str) -> Option<AnyBufferNode> {
+        match self.get(name)? {
+            AnyNode::Buffer(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    /// Returns the named output, if any.
+    pub fn get(&self, name: &str) -> Option<AnyNode> {
+        self.nodes.get(name).copied()
+    }
+
+    /// Returns the named image output, if any.
+    pub fn image(&self, name: &str) -> Option<AnyImageNode> {
+        match self.get(name)? {
+            AnyNode::Image(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    /// Returns the named query pool output, if any.
+    pub fn query_pool(&self, name: &str) -> Option<AnyQueryPoolNode> {
+        match self.get(name)? {
+            AnyNode::QueryPool(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    pub(super) fn remap(mut this: Self, mut f: impl FnMut(AnyNode) -> AnyNode) -> Self {
+        for node in this.nodes.values_mut() {
+            *node = f(*node);
+        }
+
+        this
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, ash::vk};
+
+    #[test]
+    pub fn append_remaps_nodes() {
+        let info = BufferInfo::device_mem(64, vk::BufferUsageFlags::TRANSFER_DST);
+
+        let mut sub_graph = SubGraph::new();
+        let src = sub_graph.input_buffer("src", info);
+        let dst = sub_graph.create_buffer(info);
+        sub_graph.copy_buffer(src, dst);
+        sub_graph.set_output("src", src);
+        sub_graph.set_output("dst", dst);
+
+        let mut graph = RenderGraph::new();
+        let unrelated = graph.create_buffer(info);
+        let buf = graph.create_buffer(info);
+        graph.fill_buffer(unrelated, 0);
+
+        let outputs = graph.append(sub_graph, [("src", buf.into())]);
+
+        assert_eq!(graph.bindings.len(), 3);
+        assert_eq!(graph.passes.len(), 2);
+        assert_eq!(outputs.buffer("src").unwrap().index(), buf.index());
+        assert_eq!(outputs.buffer("dst").unwrap().index(), 2);
+        assert!(outputs.image("dst").is_none());
+
+        let exec = &graph.passes[1].execs[0];
+
+        assert!(exec.accesses.contains_key(&buf.index()));
+        assert!(exec.accesses.contains_key(&2));
+        assert_eq!(exec.node_indices.as_deref(), Some([1, 2].as_slice()));
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn append_rejects_undeclared_inputs() {
+        let mut graph = RenderGraph::new();
+        let buf = graph.create_buffer(BufferInfo::device_mem(
+            64,
+            vk::BufferUsageFlags::TRANSFER_DST,
+        ));
+
+        graph.append(SubGraph::new(), [("src", buf.into())]);
+    }
+}
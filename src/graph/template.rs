@@ -0,0 +1,325 @@
+//! Retained scheduling results for graphs which are built the same way every frame.
+
+use {
+    super::{ExecutionPipeline, RenderGraph},
+    crate::driver::{DescriptorPoolInfo, RenderPassInfo},
+    std::{
+        collections::{hash_map::DefaultHasher, HashMap},
+        fmt::{Debug, Formatter},
+        hash::{Hash, Hasher},
+        sync::Arc,
+    },
+};
+
+#[cfg(feature = "parking_lot")]
+use parking_lot::Mutex;
+
+#[cfg(not(feature = "parking_lot"))]
+use std::sync::Mutex;
+
+/// A reusable compilation of the schedule and physical passes of a render graph.
+///
+/// Graphs which are built with the same passes, pipelines and node accesses every frame produce
+/// the same pass schedule, merged subpasses, render passes and descriptor pool sizes. A template
+/// retains these results from a graph resolved with it so that later graphs of the same shape skip
+/// scheduling and merge analysis and lease the same render passes. Graphs which do not match the
+/// compiled shape are fully resolved and replace the compiled results.
+///
+/// The shape of a graph does not include the resources bound to it: each frame may bind new
+/// buffers, images and swapchain images, so long as nodes are bound in the same order and accessed
+/// the same way. Clear values, render areas and the commands recorded by each execution may also
+/// change freely.
+///
+/// A template keeps the pipelines of the compiled graph alive. Templates are cheap to clone and may
+/// be shared between threads.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use ash::vk;
+/// # use screen_13::driver::DriverError;
+/// # use screen_13::driver::device::{Device, DeviceInfo};
+/// # use screen_13::driver::buffer::{Buffer, BufferInfo};
+/// # use screen_13::graph::{GraphTemplate, RenderGraph};
+/// # use screen_13::pool::lazy::LazyPool;
+/// # fn main() -> Result<(), DriverError> {
+/// # let device = Arc::new(Device::create_headless(DeviceInfo::new())?);
+/// # let mut pool = LazyPool::new(&device);
+/// # let info = BufferInfo::device_mem(1024, vk::BufferUsageFlags::TRANSFER_DST);
+/// let template = GraphTemplate::default();
+///
+/// for _ in 0..3 {
+///     let buf = Buffer::create(&device, info)?;
+///     let mut my_graph = RenderGraph::new();
+///     let buf = my_graph.bind_node(buf);
+///     my_graph.fill_buffer(buf, 0);
+///
+///     let mut resolver = my_graph.resolve();
+///     resolver.set_template(&template);
+///     resolver.submit(&mut pool, 0, 0)?;
+/// }
+///
+/// assert!(template.is_compiled());
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Default)]
+pub struct GraphTemplate {
+    compiled: Arc<Mutex<Option<Arc<CompiledGraph>>>>,
+}
+
+impl GraphTemplate {
+    /// Discards the compiled results so that the next graph resolved with this template is fully
+    /// resolved.
+    pub fn clear(&self) {
+        *self.lock() = None;
+    }
+
+    /// Stores the results of a fully resolved graph, replacing any previous results.
+    pub(super) fn compile(&self, compiled: CompiledGraph) {
+        *self.lock() = Some(Arc::new(compiled));
+    }
+
+    /// Returns the compiled results if they were compiled from a graph of the given shape.
+    pub(super) fn compiled(&self, key: u64) -> Option<Arc<CompiledGraph>> {
+        self.lock()
+            .as_ref()
+            .filter(|compiled| compiled.key == key)
+            .cloned()
+    }
+
+    /// Returns `true` if a graph has been compiled into this template.
+    pub fn is_compiled(&self) -> bool {
+        self.lock().is_some()
+    }
+
+    fn lock(&self) -> impl std::ops::DerefMut<Target = Option<Arc<CompiledGraph>>> + '_ {
+        #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+        let compiled = self.compiled.lock();
+
+        #[cfg(not(feature = "parking_lot"))]
+        let compiled = compiled.unwrap();
+
+        compiled
+    }
+}
+
+impl Debug for GraphTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GraphTemplate")
+            .field("compiled", &self.is_compiled())
+            .finish()
+    }
+}
+
+/// The results of resolving a graph.
+pub(super) struct CompiledGraph {
+    /// The shape of the graph these results were compiled from; see [`shape_key`].
+    pub key: u64,
+
+    /// The number of scheduled passes merged into each physical pass.
+    pub merged_pass_counts: Vec<usize>,
+
+    /// The physical passes, in schedule order.
+    pub passes: Vec<CompiledPass>,
+
+    // Pipelines are identified by address so they must outlive the compiled results
+    _pipelines: Vec<ExecutionPipeline>,
+
+    /// The pass schedule after re-ordering and before merging.
+    pub schedule: Vec<usize>,
+}
+
+impl CompiledGraph {
+    pub fn new(
+        graph: &RenderGraph,
+        key: u64,
+        schedule: Vec<usize>,
+        merged_pass_counts: Vec<usize>,
+        passes: Vec<CompiledPass>,
+    ) -> Self {
+        let pipelines = graph
+            .passes
+            .iter()
+            .flat_map(|pass| pass.execs.iter())
+            .filter_map(|exec| exec.pipeline.clone())
+            .collect();
+
+        Self {
+            key,
+            merged_pass_counts,
+            passes,
+            _pipelines: pipelines,
+            schedule,
+        }
+    }
+}
+
+/// The resources leased for a physical pass.
+#[derive(Debug)]
+pub(super) struct CompiledPass {
+    pub descriptor_pool_info: Option<DescriptorPoolInfo>,
+    pub render_pass_info: Option<RenderPassInfo>,
+}
+
+/// Hashes everything which the schedule, merged passes, render passes and descriptor pools of a
+/// graph depend on.
+pub(super) fn shape_key(graph: &RenderGraph, schedule: &[usize], end_pass_idx: usize) -> u64 {
+    fn hash_sorted<K, V>(map: &HashMap<K, V>, state: &mut impl Hasher)
+    where
+        K: Hash + Ord,
+        V: Hash,
+    {
+        let mut entries = map.iter().collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|&(key, _)| key);
+        entries.hash(state);
+    }
+
+    let mut state = DefaultHasher::new();
+
+    graph.bindings.len().hash(&mut state);
+    schedule.hash(&mut state);
+    end_pass_idx.hash(&mut state);
+
+    for pass in &graph.passes {
+        pass.condition.is_some().hash(&mut state);
+        pass.queue.hash(&mut state);
+        pass.execs.len().hash(&mut state);
+
+        for exec in &pass.execs {
+            let mut accesses = exec.accesses.iter().collect::<Vec<_>>();
+            accesses.sort_unstable_by_key(|(node_idx, _)| **node_idx);
+            for (node_idx, accesses) in accesses {
+                node_idx.hash(&mut state);
+
+                for access in accesses {
+                    (access.access as i32).hash(&mut state);
+                    access.subresource.hash(&mut state);
+                }
+            }
+
+            for (descriptor, (node_idx, _)) in &exec.bindings {
+                descriptor.hash(&mut state);
+                node_idx.hash(&mut state);
+            }
+
+            exec.correlated_view_mask.hash(&mut state);
+            exec.depth_stencil.hash(&mut state);
+            exec.view_mask.hash(&mut state);
+
+            hash_sorted(&exec.color_attachments, &mut state);
+            hash_sorted(&exec.color_loads, &mut state);
+            hash_sorted(&exec.color_resolves, &mut state);
+            hash_sorted(&exec.color_stores, &mut state);
+
+            let mut color_clears = exec
+                .color_clears
+                .iter()
+                .map(|(attachment_idx, (attachment, _))| (attachment_idx, attachment))
+                .collect::<Vec<_>>();
+            color_clears.sort_unstable_by_key(|(attachment_idx, _)| **attachment_idx);
+            color_clears.hash(&mut state);
+
+            exec.depth_stencil_attachment.hash(&mut state);
+            exec.depth_stencil_clear
+                .map(|(attachment, _)| attachment)
+                .hash(&mut state);
+            exec.depth_stencil_load.hash(&mut state);
+            exec.depth_stencil_resolve.hash(&mut state);
+            exec.depth_stencil_store.hash(&mut state);
+
+            match &exec.pipeline {
+                Some(ExecutionPipeline::Compute(pipeline)) => {
+                    (0, Arc::as_ptr(pipeline) as usize).hash(&mut state)
+                }
+                Some(ExecutionPipeline::Graphic(pipeline)) => {
+                    (1, Arc::as_ptr(pipeline) as usize).hash(&mut state)
+                }
+                Some(ExecutionPipeline::RayTrace(pipeline)) => {
+                    (2, Arc::as_ptr(pipeline) as usize).hash(&mut state)
+                }
+                None => 3.hash(&mut state),
+            }
+        }
+    }
+
+    state.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn template_matches_compiled_shape() {
+        let graph = RenderGraph::new();
+        let key = shape_key(&graph, &[], 0);
+        let template = GraphTemplate::default();
+
+        assert!(!template.is_compiled());
+        assert!(template.compiled(key).is_none());
+
+        template.compile(CompiledGraph::new(&graph, key, vec![], vec![], vec![]));
+
+        assert!(template.is_compiled());
+        assert!(template.compiled(key).is_some());
+        assert!(template.compiled(shape_key(&graph, &[], 1)).is_none());
+        assert!(template.clone().compiled(key).is_some());
+
+        template.clear();
+
+        assert!(!template.is_compiled());
+    }
+
+    #[test]
+    pub fn template_matches_graphs_of_the_same_shape() {
+        use {crate::driver::buffer::BufferInfo, ash::vk};
+
+        fn graph_key(pass_count: usize, data: u32) -> u64 {
+            let mut graph = RenderGraph::new();
+            let buf = graph.create_buffer(BufferInfo::device_mem(
+                1024,
+                vk::BufferUsageFlags::TRANSFER_DST,
+            ));
+
+            for _ in 0..pass_count {
+                graph.fill_buffer(buf, data);
+            }
+
+            let schedule = (0..graph.passes.len()).collect::<Vec<_>>();
+
+            shape_key(&graph, &schedule, graph.passes.len())
+        }
+
+        let first_key = graph_key(1, 0);
+        let second_key = graph_key(1, 42);
+        let other_key = graph_key(2, 0);
+        let template = GraphTemplate::default();
+
+        template.compile(CompiledGraph::new(
+            &RenderGraph::new(),
+            first_key,
+            vec![0],
+            vec![1],
+            vec![],
+        ));
+
+        assert!(template.compiled(first_key).is_some());
+        assert!(template.compiled(second_key).is_some());
+        assert!(template.compiled(other_key).is_none());
+
+        template.compile(CompiledGraph::new(
+            &RenderGraph::new(),
+            other_key,
+            vec![0, 1],
+            vec![2],
+            vec![],
+        ));
+
+        assert!(template.compiled(first_key).is_none());
+        assert!(template.compiled(second_key).is_none());
+        assert!(template.compiled(other_key).is_some());
+    }
+}
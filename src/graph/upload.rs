@@ -0,0 +1,58 @@
+//! Data which is copied into render graph nodes from device staging memory.
+
+use {
+    crate::driver::{device::Device, DriverError, StagingRegion},
+    ash::vk,
+    std::{
+        fmt::{Debug, Formatter},
+        sync::{Arc, OnceLock},
+    },
+};
+
+/// Data which is held by a render graph until it is staged during resolution.
+///
+/// The staging region is shared with the pass which copies it and is released to the staging ring
+/// once both have been dropped, which happens after the command buffer has executed.
+pub(super) struct Upload {
+    alignment: vk::DeviceSize,
+    data: Option<Box<dyn AsRef<[u8]> + Send>>,
+    region: Arc<OnceLock<StagingRegion>>,
+}
+
+impl Upload {
+    pub fn new(
+        data: impl AsRef<[u8]> + 'static + Send,
+        alignment: vk::DeviceSize,
+    ) -> (Self, Arc<OnceLock<StagingRegion>>) {
+        let region = Arc::new(OnceLock::new());
+
+        (
+            Self {
+                alignment,
+                data: Some(Box::new(data)),
+                region: Arc::clone(&region),
+            },
+            region,
+        )
+    }
+
+    /// Copies the data into staging memory, if it has not already been staged.
+    #[profiling::function]
+    pub fn stage(this: &mut Self, device: &Device) -> Result<(), DriverError> {
+        if let Some(data) = this.data.take() {
+            let region = Device::stage(device, data.as_ref().as_ref(), this.alignment)?;
+            this.region.set(region).unwrap();
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for Upload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Upload")
+            .field("alignment", &self.alignment)
+            .field("region", &self.region.get())
+            .finish()
+    }
+}
@@ -352,14 +352,16 @@ pub mod prelude {
                     ImageViewInfoBuilder, SampleCount,
                 },
                 physical_device::{
-                    AccelerationStructureProperties, PhysicalDevice, RayQueryFeatures,
-                    RayTraceFeatures, RayTraceProperties, Vulkan10Features, Vulkan10Limits,
-                    Vulkan10Properties, Vulkan11Features, Vulkan11Properties, Vulkan12Features,
-                    Vulkan12Properties,
+                    AccelerationStructureFeatures, AccelerationStructureProperties, PhysicalDevice,
+                    RayQueryFeatures, RayTraceFeatures, RayTraceProperties, Vulkan10Features,
+                    Vulkan10Limits, Vulkan10Properties, Vulkan11Features, Vulkan11Properties,
+                    Vulkan12Features, Vulkan12Properties,
                 },
+                query::{QueryPool, QueryPoolInfo, QueryPoolInfoBuilder},
                 ray_trace::{
                     RayTracePipeline, RayTracePipelineInfo, RayTracePipelineInfoBuilder,
-                    RayTraceShaderGroup, RayTraceShaderGroupType,
+                    RayTraceShaderGroup, RayTraceShaderGroupType, ShaderBindingTable,
+                    ShaderBindingTableInfo, ShaderRecord,
                 },
                 render_pass::ResolveMode,
                 shader::{
@@ -370,6 +372,7 @@ pub mod prelude {
                 swapchain::{
                     Swapchain, SwapchainError, SwapchainImage, SwapchainInfo, SwapchainInfoBuilder,
                 },
+                sync::TimelineSemaphore,
                 AccessType, CommandBuffer, DriverError, Instance,
             },
             event_loop::{EventLoop, EventLoopBuilder, FullscreenMode},
@@ -377,11 +380,14 @@ pub mod prelude {
             graph::{
                 node::{
                     AccelerationStructureLeaseNode, AccelerationStructureNode,
-                    AnyAccelerationStructureNode, AnyBufferNode, AnyImageNode, BufferLeaseNode,
-                    BufferNode, ImageLeaseNode, ImageNode, SwapchainImageNode,
+                    AnyAccelerationStructureNode, AnyBufferNode, AnyImageNode, AnyNode,
+                    AnyQueryPoolNode, BufferLeaseNode, BufferNode, ImageLeaseNode, ImageNode,
+                    QueryPoolLeaseNode, QueryPoolNode, SwapchainImageNode, TransientBufferNode,
+                    TransientImageNode,
                 },
                 pass_ref::{PassRef, PipelinePassRef},
-                Bind, ClearColorValue, RenderGraph, Unbind,
+                Bind, ClearColorValue, GraphTemplate, QueueType, Readback, RenderGraph, SubGraph,
+                SubGraphOutputs, Unbind,
             },
             pool::{
                 alias::{Alias, AliasPool},
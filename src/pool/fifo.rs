@@ -7,6 +7,7 @@ use {
         buffer::{Buffer, BufferInfo},
         device::Device,
         image::{Image, ImageInfo},
+        query::{QueryPool, QueryPoolInfo},
         CommandBuffer, CommandBufferInfo, DescriptorPool, DescriptorPoolInfo, DriverError,
         RenderPass, RenderPassInfo,
     },
@@ -49,6 +50,7 @@ pub struct FifoPool {
     device: Arc<Device>,
     image_cache: Cache<Image>,
     info: PoolInfo,
+    query_pool_cache: Cache<QueryPool>,
     render_pass_cache: HashMap<RenderPassInfo, Cache<RenderPass>>,
 }
 
@@ -71,6 +73,7 @@ impl FifoPool {
             device,
             image_cache: PoolInfo::explicit_cache(info.image_capacity),
             info,
+            query_pool_cache: PoolInfo::default_cache(),
             render_pass_cache: Default::default(),
         }
     }
@@ -294,6 +297,46 @@ impl Pool<ImageInfo, Image> for FifoPool {
     }
 }
 
+impl Pool<QueryPoolInfo, QueryPool> for FifoPool {
+    #[profiling::function]
+    fn lease(&mut self, info: QueryPoolInfo) -> Result<Lease<QueryPool>, DriverError> {
+        let cache_ref = Arc::downgrade(&self.query_pool_cache);
+
+        {
+            profiling::scope!("check cache");
+
+            #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+            let mut cache = self.query_pool_cache.lock();
+
+            #[cfg(not(feature = "parking_lot"))]
+            let mut cache = cache.unwrap();
+
+            // Look for a compatible query pool (same type, has enough queries and superset of
+            // statistics)
+            for idx in 0..cache.len() {
+                let item = unsafe { cache.get_unchecked(idx) };
+                if item.info.ty == info.ty
+                    && item.info.query_count >= info.query_count
+                    && item
+                        .info
+                        .pipeline_statistics
+                        .contains(info.pipeline_statistics)
+                {
+                    let item = cache.swap_remove(idx);
+
+                    return Ok(Lease::new(cache_ref, item));
+                }
+            }
+        }
+
+        debug!("Creating new {}", stringify!(QueryPool));
+
+        let item = QueryPool::create(&self.device, info)?;
+
+        Ok(Lease::new(cache_ref, item))
+    }
+}
+
 impl Pool<RenderPassInfo, RenderPass> for FifoPool {
     #[profiling::function]
     fn lease(&mut self, info: RenderPassInfo) -> Result<Lease<RenderPass>, DriverError> {
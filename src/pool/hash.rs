@@ -7,6 +7,7 @@ use {
         buffer::{Buffer, BufferInfo},
         device::Device,
         image::{Image, ImageInfo},
+        query::{QueryPool, QueryPoolInfo},
         CommandBuffer, CommandBufferInfo, DescriptorPool, DescriptorPoolInfo, DriverError,
         RenderPass, RenderPassInfo,
     },
@@ -45,6 +46,7 @@ pub struct HashPool {
     device: Arc<Device>,
     image_cache: HashMap<ImageInfo, Cache<Image>>,
     info: PoolInfo,
+    query_pool_cache: HashMap<QueryPoolInfo, Cache<QueryPool>>,
     render_pass_cache: HashMap<RenderPassInfo, Cache<RenderPass>>,
 }
 
@@ -67,6 +69,7 @@ impl HashPool {
             device,
             image_cache: Default::default(),
             info,
+            query_pool_cache: Default::default(),
             render_pass_cache: Default::default(),
         }
     }
@@ -183,6 +186,33 @@ impl Pool<DescriptorPoolInfo, DescriptorPool> for HashPool {
     }
 }
 
+impl Pool<QueryPoolInfo, QueryPool> for HashPool {
+    #[profiling::function]
+    fn lease(&mut self, info: QueryPoolInfo) -> Result<Lease<QueryPool>, DriverError> {
+        let cache_ref = self
+            .query_pool_cache
+            .entry(info)
+            .or_insert_with(PoolInfo::default_cache);
+        let item = {
+            #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+            let mut cache = cache_ref.lock();
+
+            #[cfg(not(feature = "parking_lot"))]
+            let mut cache = cache.unwrap();
+
+            cache.pop()
+        }
+        .map(Ok)
+        .unwrap_or_else(|| {
+            debug!("Creating new {}", stringify!(QueryPool));
+
+            QueryPool::create(&self.device, info)
+        })?;
+
+        Ok(Lease::new(Arc::downgrade(cache_ref), item))
+    }
+}
+
 impl Pool<RenderPassInfo, RenderPass> for HashPool {
     #[profiling::function]
     fn lease(&mut self, info: RenderPassInfo) -> Result<Lease<RenderPass>, DriverError> {
@@ -7,6 +7,7 @@ use {
         buffer::{Buffer, BufferInfo},
         device::Device,
         image::{Image, ImageInfo, ImageType, SampleCount},
+        query::{QueryPool, QueryPoolInfo},
         CommandBuffer, CommandBufferInfo, DescriptorPool, DescriptorPoolInfo, DriverError,
         RenderPass, RenderPassInfo,
     },
@@ -79,6 +80,7 @@ pub struct LazyPool {
     device: Arc<Device>,
     image_cache: HashMap<ImageKey, Cache<Image>>,
     info: PoolInfo,
+    query_pool_cache: HashMap<vk::QueryType, Cache<QueryPool>>,
     render_pass_cache: HashMap<RenderPassInfo, Cache<RenderPass>>,
 }
 
@@ -101,6 +103,7 @@ impl LazyPool {
             device,
             image_cache: Default::default(),
             info,
+            query_pool_cache: Default::default(),
             render_pass_cache: Default::default(),
         }
     }
@@ -346,6 +349,48 @@ impl Pool<ImageInfo, Image> for LazyPool {
     }
 }
 
+impl Pool<QueryPoolInfo, QueryPool> for LazyPool {
+    #[profiling::function]
+    fn lease(&mut self, info: QueryPoolInfo) -> Result<Lease<QueryPool>, DriverError> {
+        let cache = self
+            .query_pool_cache
+            .entry(info.ty)
+            .or_insert_with(PoolInfo::default_cache);
+        let cache_ref = Arc::downgrade(cache);
+
+        {
+            profiling::scope!("check cache");
+
+            #[cfg_attr(not(feature = "parking_lot"), allow(unused_mut))]
+            let mut cache = cache.lock();
+
+            #[cfg(not(feature = "parking_lot"))]
+            let mut cache = cache.unwrap();
+
+            // Look for a compatible query pool (has enough queries and superset of statistics)
+            for idx in 0..cache.len() {
+                let item = unsafe { cache.get_unchecked(idx) };
+                if item.info.query_count >= info.query_count
+                    && item
+                        .info
+                        .pipeline_statistics
+                        .contains(info.pipeline_statistics)
+                {
+                    let item = cache.swap_remove(idx);
+
+                    return Ok(Lease::new(cache_ref, item));
+                }
+            }
+        }
+
+        debug!("Creating new {}", stringify!(QueryPool));
+
+        let item = QueryPool::create(&self.device, info)?;
+
+        Ok(Lease::new(cache_ref, item))
+    }
+}
+
 impl Pool<RenderPassInfo, RenderPass> for LazyPool {
     #[profiling::function]
     fn lease(&mut self, info: RenderPassInfo) -> Result<Lease<RenderPass>, DriverError> {
@@ -96,6 +96,7 @@ use {
         },
         buffer::{Buffer, BufferInfo, BufferInfoBuilder},
         image::{Image, ImageInfo, ImageInfoBuilder},
+        query::{QueryPool, QueryPoolInfo, QueryPoolInfoBuilder},
         CommandBuffer, DriverError,
     },
     derive_builder::{Builder, UninitializedFieldError},
@@ -236,6 +237,7 @@ macro_rules! lease_builder {
 lease_builder!(AccelerationStructureInfo => AccelerationStructure);
 lease_builder!(BufferInfo => Buffer);
 lease_builder!(ImageInfo => Image);
+lease_builder!(QueryPoolInfo => QueryPool);
 
 /// Information used to create a [`FifoPool`](self::fifo::FifoPool),
 /// [`HashPool`](self::hash::HashPool) or [`LazyPool`](self::lazy::LazyPool) instance.